@@ -1,15 +1,25 @@
 fn main() {
+    println!("cargo::rustc-check-cfg=cfg(vf3_cxx)");
+    println!("cargo:rerun-if-env-changed=DOCS_RS");
     // Skip compilation on docs.rs to avoid build failures.
     if std::env::var("DOCS_RS").is_ok() {
         println!("cargo:rustc-cfg=docsrs");
-        println!("cargo:rerun-if-env-changed=DOCS_RS");
-        return;
+    } else {
+        #[cfg(feature = "cpp")]
+        compile_vf3lib();
     }
+}
+
+/// Compile the C++ bridge and tell the crate to use it instead of the
+/// pure-Rust backend.
+#[cfg(feature = "cpp")]
+fn compile_vf3lib() {
     // Initialize C++ build configuration.
-    let mut build = cxx_build::bridge("src/lib.rs");
+    let mut build = cxx_build::bridge("src/bridge.rs");
 
     // Add local C++ headers for CXX declarations.
     build.include("cxx");
+    println!("cargo:rerun-if-changed=cxx");
 
     // Locate vf3lib headers with the following priority:
     // 1. VF3LIB_DIR environment variable (for custom locations)
@@ -71,4 +81,5 @@ fn main() {
     }
 
     build.compile("vf3bridge");
+    println!("cargo:rustc-cfg=vf3_cxx");
 }
@@ -16,6 +16,7 @@
 #include <limits>
 #include <map>
 #include <memory>
+#include <mutex>
 #include <sstream>
 #include <string>
 #include <unordered_map>
@@ -46,7 +47,6 @@
 #include "VF3NodeSorter.hpp"
 #include "VF3SubState.hpp"
 #include "loaders/ARGLoader.hpp"
-#include "loaders/FastStreamARGLoader.hpp"
 
 // Parallel algorithm is Linux-only due to cpu_set_t and pthread_setaffinity_np
 #ifdef __linux__
@@ -55,7 +55,7 @@
 #include "parallel/ParallelMatchingEngineWLS.hpp"
 #endif
 
-#include "vf3lib-rs/src/lib.rs.h"  // Generated by CXX.
+#include "vf3lib-rs/src/bridge.rs.h"  // Generated by CXX.
 
 // Include platform-specific time headers
 #ifdef _WIN32
@@ -64,157 +64,217 @@
 #include <sys/time.h>
 #endif
 
+// MatchingVisitor declares its virtual destructor without defining it.
+template <typename VFState>
+vflib::MatchingVisitor<VFState>::~MatchingVisitor() {}
+
 namespace vf3ffi {
 
 // Default data type from VFLib.h (int32_t when VF3BIO is undefined).
 typedef std::int32_t data_t;
 
-using SeqState = vflib::VF3SubState<data_t, data_t, vflib::Empty, vflib::Empty>;
-using LightState = vflib::VF3LightSubState<data_t, data_t, vflib::Empty, vflib::Empty>;
+// Edges carry integer labels; formats without edge attributes use label 0.
+using ArgGraph = vflib::ARGraph<data_t, data_t>;
+using ArgLoader = vflib::ARGLoader<data_t, data_t>;
+using SeqState = vflib::VF3SubState<data_t, data_t, data_t, data_t>;
+using LightState = vflib::VF3LightSubState<data_t, data_t, data_t, data_t>;
 #ifdef __linux__
-using ParState = vflib::CloneableVF3ParallelSubState<data_t, data_t, vflib::Empty, vflib::Empty>;
+using ParState = vflib::CloneableVF3ParallelSubState<data_t, data_t, data_t, data_t>;
 #endif
 
-class PortableEdgeListLoader final : public vflib::ARGLoader<data_t, vflib::Empty> {
+// Loader for `.grf` files with the format and validation of vf3lib's
+// FastStreamARGLoader, which calls exit() or reads past its buffers on
+// malformed input. Failures are reported through ok() instead. Edges carry no
+// attributes and are labelled 0.
+class GrfLoader final : public ArgLoader {
   public:
-    PortableEdgeListLoader(std::istream &in, bool undirected) : undirected_(undirected) {
-        parse(in);
+    GrfLoader(std::istream &in, bool undirected) {
+        ok_ = read(in, undirected);
     }
 
-    bool is_valid() const {
-        return valid_;
+    bool ok() const {
+        return ok_;
     }
 
     uint32_t NodeCount() const override {
-        return node_count_;
+        return static_cast<uint32_t>(labels_.size());
     }
 
-    data_t GetNodeAttr(vflib::nodeID_t) override {
-        return node_attribute_;
+    data_t GetNodeAttr(vflib::nodeID_t node) override {
+        assert(node < labels_.size());
+        return labels_[node];
     }
 
     uint32_t OutEdgeCount(vflib::nodeID_t node) const override {
-        if (node >= adjacency_.size()) {
-            return 0;
-        }
-        return static_cast<uint32_t>(adjacency_[node].size());
+        assert(node < edges_.size());
+        return static_cast<uint32_t>(edges_[node].size());
     }
 
-    vflib::nodeID_t GetOutEdge(vflib::nodeID_t node, uint32_t i, vflib::Empty *pattr) override {
-        assert(node < adjacency_.size());
-        assert(i < adjacency_[node].size());
-        *pattr = edge_attribute_;
-        return adjacency_[node][i];
+    vflib::nodeID_t GetOutEdge(vflib::nodeID_t node, uint32_t i, data_t *pattr) override {
+        assert(i < OutEdgeCount(node));
+        *pattr = 0;
+        return edges_[node][i];
     }
 
   private:
-    static void trim(std::string &line) {
-        size_t start = 0;
-        while (start < line.size() && std::isspace(static_cast<unsigned char>(line[start]))) {
-            ++start;
+    // Parse the next token as a decimal number in [min, max], accepting the
+    // same spellings as Rust's integer parsing. Bounds stay within 32 bits.
+    static bool next_number(std::istream &in, std::int64_t min, std::uint64_t max, std::int64_t &out) {
+        std::string token;
+        if (!(in >> token)) {
+            return false;
         }
-        size_t end = line.size();
-        while (end > start && std::isspace(static_cast<unsigned char>(line[end - 1]))) {
-            --end;
+        std::size_t pos = 0;
+        bool negative = false;
+        if (token[0] == '+' || token[0] == '-') {
+            negative = token[0] == '-';
+            pos = 1;
         }
-        if (start == 0 && end == line.size()) {
-            return;
+        if (pos == token.size() || (negative && min >= 0)) {
+            return false;
+        }
+        std::uint64_t limit = negative ? static_cast<std::uint64_t>(-min) : max;
+        std::uint64_t value = 0;
+        for (; pos < token.size(); pos++) {
+            if (!std::isdigit(static_cast<unsigned char>(token[pos]))) {
+                return false;
+            }
+            std::uint64_t digit = token[pos] - '0';
+            if (value > (limit - digit) / 10) {
+                return false;
+            }
+            value = value * 10 + digit;
         }
-        line = line.substr(start, end - start);
+        out = negative ? -static_cast<std::int64_t>(value) : static_cast<std::int64_t>(value);
+        return true;
     }
 
-    void parse(std::istream &in) {
-        std::vector<std::pair<vflib::nodeID_t, vflib::nodeID_t>> edges;
-        std::vector<vflib::nodeID_t> nodes;
+    bool read(std::istream &in, bool undirected) {
+        const std::uint64_t max_id = std::numeric_limits<vflib::nodeID_t>::max();
         std::string line;
+        while (in.peek() == '#') {
+            std::getline(in, line);
+        }
 
-        while (std::getline(in, line)) {
-            if (!line.empty() && line.back() == '\r') {
-                line.pop_back();
-            }
-            trim(line);
-            if (line.empty() || line[0] == '#') {
-                continue;
-            }
-
-            std::istringstream iss(line);
-            unsigned long long raw_u = 0;
-            unsigned long long raw_v = 0;
-            if (!(iss >> raw_u >> raw_v)) {
-                valid_ = false;
-                return;
+        // Nodes are appended as they are read, so a huge count in a short
+        // file fails at the end of input rather than allocating up front.
+        std::int64_t count, id, label, degree, from, to;
+        if (!next_number(in, 0, max_id, count)) {
+            return false;
+        }
+        for (std::int64_t node = 0; node < count; node++) {
+            if (!next_number(in, 0, max_id, id) || id != node ||
+                !next_number(
+                    in, std::numeric_limits<data_t>::min(), std::numeric_limits<data_t>::max(),
+                    label)) {
+                return false;
             }
+            labels_.push_back(static_cast<data_t>(label));
+        }
 
-            const auto max_id =
-                static_cast<unsigned long long>((std::numeric_limits<vflib::nodeID_t>::max)());
-            if (raw_u == 0 || raw_v == 0 || raw_u > max_id || raw_v > max_id) {
-                valid_ = false;
-                return;
+        edges_.resize(labels_.size());
+        for (std::int64_t node = 0; node < count; node++) {
+            if (!next_number(in, 0, max_id, degree)) {
+                return false;
             }
-
-            const auto u = static_cast<vflib::nodeID_t>(raw_u - 1);
-            const auto v = static_cast<vflib::nodeID_t>(raw_v - 1);
-            if (u == v) {
-                valid_ = false;
-                return;
+            for (std::int64_t j = 0; j < degree; j++) {
+                if (!next_number(in, 0, max_id, from) || !next_number(in, 0, max_id, to) ||
+                    from != node || to >= count || from == to) {
+                    return false;
+                }
+                edges_[from].push_back(static_cast<vflib::nodeID_t>(to));
+                if (undirected) {
+                    edges_[to].push_back(static_cast<vflib::nodeID_t>(from));
+                }
             }
-
-            edges.emplace_back(u, v);
-            nodes.push_back(u);
-            nodes.push_back(v);
         }
 
-        if (nodes.empty()) {
-            adjacency_.clear();
-            node_count_ = 0;
-            valid_ = true;
-            return;
+        // FastStreamARGLoader keeps each node's edges in a map.
+        for (auto &targets : edges_) {
+            std::sort(targets.begin(), targets.end());
+            targets.erase(std::unique(targets.begin(), targets.end()), targets.end());
         }
+        return true;
+    }
 
-        std::sort(nodes.begin(), nodes.end());
-        nodes.erase(std::unique(nodes.begin(), nodes.end()), nodes.end());
-        if (nodes.size() > static_cast<size_t>((std::numeric_limits<uint32_t>::max)())) {
-            valid_ = false;
-            return;
-        }
+    bool ok_;
+    std::vector<data_t> labels_;
+    std::vector<std::vector<vflib::nodeID_t>> edges_;
+};
 
-        std::unordered_map<vflib::nodeID_t, vflib::nodeID_t> id_map;
-        id_map.reserve(nodes.size());
-        for (size_t idx = 0; idx < nodes.size(); ++idx) {
-            id_map[nodes[idx]] = static_cast<vflib::nodeID_t>(idx);
-        }
+// Loader over a CSR graph built on the Rust side.
+class GraphDataLoader final : public ArgLoader {
+  public:
+    explicit GraphDataLoader(const GraphData &graph) : graph_(graph) {}
 
-        adjacency_.assign(nodes.size(), {});
-        for (const auto &edge : edges) {
-            const auto it_u = id_map.find(edge.first);
-            const auto it_v = id_map.find(edge.second);
-            if (it_u == id_map.end() || it_v == id_map.end()) {
-                valid_ = false;
-                return;
-            }
-            adjacency_[it_u->second].push_back(it_v->second);
-            if (undirected_) {
-                adjacency_[it_v->second].push_back(it_u->second);
-            }
-        }
+    uint32_t NodeCount() const override {
+        return static_cast<uint32_t>(graph_.node_labels.size());
+    }
 
-        for (auto &neighbors : adjacency_) {
-            std::sort(neighbors.begin(), neighbors.end());
-            neighbors.erase(std::unique(neighbors.begin(), neighbors.end()), neighbors.end());
-        }
+    data_t GetNodeAttr(vflib::nodeID_t node) override {
+        assert(node < graph_.node_labels.size());
+        return graph_.node_labels[node];
+    }
+
+    uint32_t OutEdgeCount(vflib::nodeID_t node) const override {
+        assert(node + 1 < graph_.offsets.size());
+        return graph_.offsets[node + 1] - graph_.offsets[node];
+    }
 
-        node_count_ = static_cast<uint32_t>(adjacency_.size());
-        valid_ = true;
+    vflib::nodeID_t GetOutEdge(vflib::nodeID_t node, uint32_t i, data_t *pattr) override {
+        assert(i < OutEdgeCount(node));
+        *pattr = graph_.edge_labels[graph_.offsets[node] + i];
+        return graph_.targets[graph_.offsets[node] + i];
     }
 
-    bool undirected_;
-    bool valid_ = false;
-    uint32_t node_count_ = 0;
-    data_t node_attribute_{};
-    vflib::Empty edge_attribute_;
-    std::vector<std::vector<vflib::nodeID_t>> adjacency_;
+  private:
+    const GraphData &graph_;
 };
 
+// Order pattern nodes for the VF3 states.
+//
+// VF3NodeSorter dereferences an empty candidate list when the first node it
+// picks has no edges, so patterns with isolated nodes are ordered here instead:
+// each step takes the node with the most already ordered neighbours, then the
+// highest degree, which leaves isolated nodes last.
+static std::vector<vflib::nodeID_t> sort_pattern_nodes(ArgGraph &pattern, ArgGraph &target) {
+    const uint32_t count = pattern.NodeCount();
+    bool isolated = false;
+    for (vflib::nodeID_t node = 0; node < count; node++) {
+        isolated = isolated || pattern.EdgeCount(node) == 0;
+    }
+    if (!isolated || count < 2) {
+        vflib::VF3NodeSorter<data_t, data_t, vflib::SubIsoNodeProbability<data_t, data_t>> sorter(
+            &target);
+        return sorter.SortNodes(&pattern);
+    }
+
+    std::vector<vflib::nodeID_t> order;
+    std::vector<bool> used(count, false);
+    std::vector<uint32_t> links(count, 0);
+    while (order.size() < count) {
+        vflib::nodeID_t best = vflib::NULL_NODE;
+        for (vflib::nodeID_t node = 0; node < count; node++) {
+            if (used[node]) {
+                continue;
+            }
+            if (best == vflib::NULL_NODE || links[node] > links[best] ||
+                (links[node] == links[best] && pattern.EdgeCount(node) > pattern.EdgeCount(best))) {
+                best = node;
+            }
+        }
+        used[best] = true;
+        order.push_back(best);
+        for (uint32_t i = 0; i < pattern.OutEdgeCount(best); i++) {
+            links[pattern.GetOutEdge(best, i)]++;
+        }
+        for (uint32_t i = 0; i < pattern.InEdgeCount(best); i++) {
+            links[pattern.GetInEdge(best, i)]++;
+        }
+    }
+    return order;
+}
+
 // Convert Rust string slice to std::string.
 static inline std::string to_string_view(rust::Str s) {
     return std::string(s.data(), s.size());
@@ -226,52 +286,113 @@ static inline double elapsed_secs(const timeval &a, const timeval &b) {
 }
 
 // Create graph loader based on format string.
-static std::unique_ptr<vflib::ARGLoader<data_t, vflib::Empty>>
+static std::unique_ptr<ArgLoader>
 create_loader(std::istream &in, const std::string &fmt, bool undirected) {
     if (fmt == "vf") {
-        return std::unique_ptr<vflib::ARGLoader<data_t, vflib::Empty>>(
-            new vflib::FastStreamARGLoader<data_t, vflib::Empty>(in, undirected));
+        auto loader = std::unique_ptr<GrfLoader>(new GrfLoader(in, undirected));
+        if (loader->ok()) {
+            return loader;
+        }
     }
 
-    if (fmt == "edge") {
-        auto loader = std::make_unique<PortableEdgeListLoader>(in, undirected);
-        if (!loader->is_valid()) {
-            return nullptr;
+    return nullptr;
+}
+
+// Copy matcher solutions into the result, one target node per pattern node.
+static void store_mappings(const std::vector<vflib::MatchingSolution> &sols, VF3Result &out) {
+    for (const auto &sol : sols) {
+        SolutionData data;
+        for (const auto &pair : sol) {
+            data.targets.push_back(pair.second);
         }
-        return loader;
+        out.mappings.push_back(std::move(data));
     }
+}
 
-    return nullptr;
+// A pattern without nodes has exactly one, empty, match. The vf3lib states
+// assert on the empty class arrays it produces, so it is answered here.
+static VF3Result empty_pattern_result(bool store_solutions, SolutionSink *sink) {
+    VF3Result res{0, 1, 0.0, 0.0, {}};
+    if (store_solutions) {
+        res.mappings.push_back(SolutionData{});
+    }
+    if (sink && sink->enabled()) {
+        sink->visit(rust::Slice<const uint32_t>());
+    }
+    return res;
 }
 
+// Hands each solution to the Rust sink, if any, and collects it for the
+// parallel engines, whose own store_solutions path appends to a shared
+// vector without synchronization. Calls are serialized, and the sink is not
+// called again once it asked to stop; the parallel engines ignore the
+// request and search on.
 template <typename StateT>
-static VF3Result run_seq(
-    const std::string &patt_path,
-    const std::string &targ_path,
-    const std::string &fmt,
-    bool undirected,
-    bool store_solutions,
-    bool first_only,
-    bool verbose,
-    float repetition_time_limit,
-    bool edge_induced) {
-    VF3Result out{1, 0, 0.0, 0.0};
-    std::ifstream graphInPat(patt_path.c_str(), std::ios::in | std::ios::binary);
-    std::ifstream graphInTarg(targ_path.c_str(), std::ios::in | std::ios::binary);
+class SolutionVisitor final : public vflib::MatchingVisitor<StateT> {
+  public:
+    SolutionVisitor(SolutionSink *sink, bool collect) : sink_(sink), collect_(collect) {}
+
+    bool operator()(StateT &s) override {
+        vflib::MatchingSolution sol;
+        s.GetCoreSet(sol);
+        std::lock_guard<std::mutex> lock(mutex_);
+        if (!stopped_ && forwards()) {
+            targets_.clear();
+            for (const auto &pair : sol) {
+                targets_.push_back(pair.second);
+            }
+            stopped_ =
+                sink_->visit(rust::Slice<const uint32_t>(targets_.data(), targets_.size()));
+        }
+        if (collect_) {
+            solutions_.push_back(std::move(sol));
+        }
+        return stopped_;
+    }
 
-    if (!graphInPat.good() || !graphInTarg.good())
-        return out;
+    // Whether the engine needs to call the visitor at all.
+    bool active() const {
+        return collect_ || forwards();
+    }
 
-    auto pattloader = create_loader(graphInPat, fmt, undirected);
-    auto targloader = create_loader(graphInTarg, fmt, undirected);
+    void clear() {
+        solutions_.clear();
+    }
 
-    if (!pattloader || !targloader)
-        return out;
+    const std::vector<vflib::MatchingSolution> &solutions() const {
+        return solutions_;
+    }
+
+  private:
+    bool forwards() const {
+        return sink_ && sink_->enabled();
+    }
 
-    vflib::ARGraph<data_t, vflib::Empty> patt_graph(pattloader.get());
-    vflib::ARGraph<data_t, vflib::Empty> targ_graph(targloader.get());
-    vflib::MatchingEngine<StateT> *me =
-        new vflib::MatchingEngine<StateT>(store_solutions, edge_induced);
+    SolutionSink *sink_;
+    bool collect_;
+    bool stopped_ = false;
+    std::mutex mutex_;
+    std::vector<uint32_t> targets_;
+    std::vector<vflib::MatchingSolution> solutions_;
+};
+
+template <typename StateT>
+static VF3Result match_seq(
+    ArgGraph &patt_graph,
+    ArgGraph &targ_graph,
+    bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    SolutionSink *sink) {
+    if (patt_graph.NodeCount() == 0) {
+        return empty_pattern_result(store_solutions, sink);
+    }
+    VF3Result out{1, 0, 0.0, 0.0, {}};
+    SolutionVisitor<StateT> visitor(sink, false);
+    vflib::MatchingEngine<StateT> *me = new vflib::MatchingEngine<StateT>(
+        visitor.active() ? &visitor : nullptr, store_solutions, edge_induced);
 
     if (!me) {
         return out;
@@ -281,7 +402,7 @@ static VF3Result run_seq(
     double timeFirst = 0.0, timeAll = 0.0, totalExecTime = 0.0;
     int rep = 0;
 
-    vflib::FastCheck<data_t, data_t, vflib::Empty, vflib::Empty> check(&patt_graph, &targ_graph);
+    vflib::FastCheck<data_t, data_t, data_t, data_t> check(&patt_graph, &targ_graph);
     std::vector<uint32_t> class_patt, class_targ;
     uint32_t classes_count = 0;
 
@@ -293,8 +414,8 @@ static VF3Result run_seq(
             (void)fastcheck;
         }
 
-        vflib::NodeClassifier<data_t, vflib::Empty> classifier(&targ_graph);
-        vflib::NodeClassifier<data_t, vflib::Empty> classifier_patt(&patt_graph, classifier);
+        vflib::NodeClassifier<data_t, data_t> classifier(&targ_graph);
+        vflib::NodeClassifier<data_t, data_t> classifier_patt(&patt_graph, classifier);
         class_patt = classifier_patt.GetClasses();
         class_targ = classifier.GetClasses();
         classes_count = classifier.CountClasses();
@@ -306,10 +427,7 @@ static VF3Result run_seq(
         gettimeofday(&iter, NULL);
 
         if (check.CheckSubgraphIsomorphism()) {
-            vflib::VF3NodeSorter<
-                data_t, vflib::Empty, vflib::SubIsoNodeProbability<data_t, vflib::Empty>>
-                sorter(&targ_graph);
-            std::vector<vflib::nodeID_t> sorted = sorter.SortNodes(&patt_graph);
+            std::vector<vflib::nodeID_t> sorted = sort_pattern_nodes(patt_graph, targ_graph);
             StateT s0(
                 &patt_graph, &targ_graph, class_patt.data(), class_targ.data(), classes_count,
                 sorted.data(), edge_induced);
@@ -343,10 +461,65 @@ static VF3Result run_seq(
     out.solutions = me->GetSolutionsCount();
     out.time_first = timeFirst;
     out.time_all = timeAll;
+    if (store_solutions) {
+        std::vector<vflib::MatchingSolution> sols;
+        me->GetSolutions(sols);
+        store_mappings(sols, out);
+    }
     delete me;
     return out;
 }
 
+template <typename StateT>
+static VF3Result run_seq(
+    const std::string &patt_path,
+    const std::string &targ_path,
+    const std::string &fmt,
+    bool undirected,
+    bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced) {
+    VF3Result out{1, 0, 0.0, 0.0, {}};
+    std::ifstream graphInPat(patt_path.c_str(), std::ios::in | std::ios::binary);
+    std::ifstream graphInTarg(targ_path.c_str(), std::ios::in | std::ios::binary);
+
+    if (!graphInPat.good() || !graphInTarg.good())
+        return out;
+
+    auto pattloader = create_loader(graphInPat, fmt, undirected);
+    auto targloader = create_loader(graphInTarg, fmt, undirected);
+
+    if (!pattloader || !targloader)
+        return out;
+
+    ArgGraph patt_graph(pattloader.get());
+    ArgGraph targ_graph(targloader.get());
+    return match_seq<StateT>(
+        patt_graph, targ_graph, store_solutions, first_only, verbose, repetition_time_limit,
+        edge_induced, nullptr);
+}
+
+template <typename StateT>
+static VF3Result run_seq_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    SolutionSink &sink) {
+    GraphDataLoader pattloader(pattern);
+    GraphDataLoader targloader(target);
+    ArgGraph patt_graph(&pattloader);
+    ArgGraph targ_graph(&targloader);
+    return match_seq<StateT>(
+        patt_graph, targ_graph, store_solutions, first_only, verbose, repetition_time_limit,
+        edge_induced, &sink);
+}
+
 VF3Result run_vf3(
     rust::Str pattern,
     rust::Str target,
@@ -377,64 +550,78 @@ VF3Result run_vf3l(
         store_solutions, first_only, verbose, repetition_time_limit, edge_induced);
 }
 
-#ifdef __linux__
-VF3Result run_vf3p(
-    rust::Str pattern,
-    rust::Str target,
-    rust::Str format,
-    bool undirected,
+VF3Result run_vf3_graph(
+    const GraphData &pattern,
+    const GraphData &target,
     bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    SolutionSink &sink) {
+    return run_seq_graph<SeqState>(
+        pattern, target, store_solutions, first_only, verbose, repetition_time_limit,
+        edge_induced, sink);
+}
+
+VF3Result run_vf3l_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool first_only,
     bool verbose,
     float repetition_time_limit,
     bool edge_induced,
+    SolutionSink &sink) {
+    return run_seq_graph<LightState>(
+        pattern, target, store_solutions, first_only, verbose, repetition_time_limit,
+        edge_induced, sink);
+}
+
+#ifdef __linux__
+static VF3Result match_par(
+    ArgGraph &patt_graph,
+    ArgGraph &targ_graph,
+    bool store_solutions,
+    float repetition_time_limit,
+    bool edge_induced,
     std::int8_t algo,
     std::int16_t cpu,
     std::int16_t num_threads,
     bool lock_free,
     std::int16_t ssr_high_limit,
-    std::int16_t ssr_local_stack_limit) {
-    (void)verbose;  // Unused in parallel implementation.
-    VF3Result out{1, 0, 0.0, 0.0};
-    std::ifstream graphInPat(to_string_view(pattern).c_str(), std::ios::in | std::ios::binary);
-    std::ifstream graphInTarg(to_string_view(target).c_str(), std::ios::in | std::ios::binary);
-
-    if (!graphInPat.good() || !graphInTarg.good())
-        return out;
-
-    std::string fmt = to_string_view(format);
-
-    auto pattloader = create_loader(graphInPat, fmt, undirected);
-    auto targloader = create_loader(graphInTarg, fmt, undirected);
-
-    if (!pattloader || !targloader)
-        return out;
-
-    vflib::ARGraph<data_t, vflib::Empty> patt_graph(pattloader.get());
-    vflib::ARGraph<data_t, vflib::Empty> targ_graph(targloader.get());
-
+    std::int16_t ssr_local_stack_limit,
+    SolutionSink *sink) {
+    VF3Result out{1, 0, 0.0, 0.0, {}};
+    SolutionVisitor<ParState> visitor(sink, store_solutions);
+    vflib::MatchingVisitor<ParState> *visit = visitor.active() ? &visitor : nullptr;
     vflib::MatchingEngine<ParState> *me = nullptr;
 
     if (algo == 1) {
         me = new vflib::ParallelMatchingEngine<ParState>(
-            num_threads, store_solutions, lock_free, cpu);
+            num_threads, false, lock_free, cpu, visit);
     } else if (algo == 2) {
         me = new vflib::ParallelMatchingEngineWLS<ParState>(
-            num_threads, store_solutions, lock_free, cpu, ssr_high_limit, ssr_local_stack_limit);
+            num_threads, false, lock_free, cpu, ssr_high_limit, ssr_local_stack_limit, visit);
     } else {
         return out;
     }
+    if (patt_graph.NodeCount() == 0) {
+        delete me;
+        return empty_pattern_result(store_solutions, sink);
+    }
 
     struct timeval iter, end;
     double timeAll = 0.0, totalExecTime = 0.0;
     int rep = 0;
 
-    vflib::FastCheck<data_t, data_t, vflib::Empty, vflib::Empty> check(&patt_graph, &targ_graph);
+    vflib::FastCheck<data_t, data_t, data_t, data_t> check(&patt_graph, &targ_graph);
     std::vector<uint32_t> class_patt, class_targ;
     uint32_t classes_count = 0;
 
     if (check.CheckSubgraphIsomorphism()) {
-        vflib::NodeClassifier<data_t, vflib::Empty> classifier(&targ_graph);
-        vflib::NodeClassifier<data_t, vflib::Empty> classifier_patt(&patt_graph, classifier);
+        vflib::NodeClassifier<data_t, data_t> classifier(&targ_graph);
+        vflib::NodeClassifier<data_t, data_t> classifier_patt(&patt_graph, classifier);
         class_patt = classifier_patt.GetClasses();
         class_targ = classifier.GetClasses();
         classes_count = classifier.CountClasses();
@@ -443,13 +630,11 @@ VF3Result run_vf3p(
     do {
         rep++;
         me->ResetSolutionCounter();
+        visitor.clear();
         gettimeofday(&iter, NULL);
 
         if (check.CheckSubgraphIsomorphism()) {
-            vflib::VF3NodeSorter<
-                data_t, vflib::Empty, vflib::SubIsoNodeProbability<data_t, vflib::Empty>>
-                sorter(&targ_graph);
-            std::vector<vflib::nodeID_t> sorted = sorter.SortNodes(&patt_graph);
+            std::vector<vflib::nodeID_t> sorted = sort_pattern_nodes(patt_graph, targ_graph);
             ParState s0(
                 &patt_graph, &targ_graph, class_patt.data(), class_targ.data(), classes_count,
                 sorted.data(), edge_induced);
@@ -463,12 +648,75 @@ VF3Result run_vf3p(
 
     double timeFirst = timeAll;
     timeAll = totalExecTime / rep;
-    VF3Result res{0, me->GetSolutionsCount(), timeFirst, timeAll};
+    VF3Result res{0, me->GetSolutionsCount(), timeFirst, timeAll, {}};
+    store_mappings(visitor.solutions(), res);
     delete me;
     return res;
 }
+
+VF3Result run_vf3p(
+    rust::Str pattern,
+    rust::Str target,
+    rust::Str format,
+    bool undirected,
+    bool store_solutions,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    std::int8_t algo,
+    std::int16_t cpu,
+    std::int16_t num_threads,
+    bool lock_free,
+    std::int16_t ssr_high_limit,
+    std::int16_t ssr_local_stack_limit) {
+    (void)verbose;  // Unused in parallel implementation.
+    VF3Result out{1, 0, 0.0, 0.0, {}};
+    std::ifstream graphInPat(to_string_view(pattern).c_str(), std::ios::in | std::ios::binary);
+    std::ifstream graphInTarg(to_string_view(target).c_str(), std::ios::in | std::ios::binary);
+
+    if (!graphInPat.good() || !graphInTarg.good())
+        return out;
+
+    std::string fmt = to_string_view(format);
+
+    auto pattloader = create_loader(graphInPat, fmt, undirected);
+    auto targloader = create_loader(graphInTarg, fmt, undirected);
+
+    if (!pattloader || !targloader)
+        return out;
+
+    ArgGraph patt_graph(pattloader.get());
+    ArgGraph targ_graph(targloader.get());
+    return match_par(
+        patt_graph, targ_graph, store_solutions, repetition_time_limit, edge_induced, algo, cpu,
+        num_threads, lock_free, ssr_high_limit, ssr_local_stack_limit, nullptr);
+}
+
+VF3Result run_vf3p_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    std::int8_t algo,
+    std::int16_t cpu,
+    std::int16_t num_threads,
+    bool lock_free,
+    std::int16_t ssr_high_limit,
+    std::int16_t ssr_local_stack_limit,
+    SolutionSink &sink) {
+    (void)verbose;  // Unused in parallel implementation.
+    GraphDataLoader pattloader(pattern);
+    GraphDataLoader targloader(target);
+    ArgGraph patt_graph(&pattloader);
+    ArgGraph targ_graph(&targloader);
+    return match_par(
+        patt_graph, targ_graph, store_solutions, repetition_time_limit, edge_induced, algo, cpu,
+        num_threads, lock_free, ssr_high_limit, ssr_local_stack_limit, &sink);
+}
 #else
-// Stub implementation for non-Linux platforms
+// Stub implementations for non-Linux platforms
 VF3Result run_vf3p(
     rust::Str pattern,
     rust::Str target,
@@ -499,7 +747,37 @@ VF3Result run_vf3p(
     (void)lock_free;
     (void)ssr_high_limit;
     (void)ssr_local_stack_limit;
-    return VF3Result{2, 0, 0.0, 0.0};  // Return error code 2 for unsupported platform
+    return VF3Result{2, 0, 0.0, 0.0, {}};  // Return error code 2 for unsupported platform
+}
+
+VF3Result run_vf3p_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    std::int8_t algo,
+    std::int16_t cpu,
+    std::int16_t num_threads,
+    bool lock_free,
+    std::int16_t ssr_high_limit,
+    std::int16_t ssr_local_stack_limit,
+    SolutionSink &sink) {
+    (void)pattern;
+    (void)target;
+    (void)store_solutions;
+    (void)verbose;
+    (void)repetition_time_limit;
+    (void)edge_induced;
+    (void)algo;
+    (void)cpu;
+    (void)num_threads;
+    (void)lock_free;
+    (void)ssr_high_limit;
+    (void)ssr_local_stack_limit;
+    (void)sink;
+    return VF3Result{2, 0, 0.0, 0.0, {}};  // Return error code 2 for unsupported platform
 }
 #endif
 
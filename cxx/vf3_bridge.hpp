@@ -8,6 +8,8 @@
 namespace vf3ffi {
 
 struct VF3Result;
+struct GraphData;
+struct SolutionSink;
 
 VF3Result run_vf3(
     rust::Str pattern,
@@ -47,6 +49,41 @@ VF3Result run_vf3p(
     std::int16_t ssr_high_limit,
     std::int16_t ssr_local_stack_limit);
 
+VF3Result run_vf3_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    SolutionSink &sink);
+
+VF3Result run_vf3l_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool first_only,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    SolutionSink &sink);
+
+VF3Result run_vf3p_graph(
+    const GraphData &pattern,
+    const GraphData &target,
+    bool store_solutions,
+    bool verbose,
+    float repetition_time_limit,
+    bool edge_induced,
+    std::int8_t algo,
+    std::int16_t cpu,
+    std::int16_t num_threads,
+    bool lock_free,
+    std::int16_t ssr_high_limit,
+    std::int16_t ssr_local_stack_limit,
+    SolutionSink &sink);
+
 }  // namespace vf3ffi
 
 #endif  // VF3_BRIDGE_HPP
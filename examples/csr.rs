@@ -0,0 +1,55 @@
+//! Example of converting a `.grf` target to the binary CSR format and
+//! matching against the memory-mapped file.
+
+use std::{fs, io::BufReader, path::PathBuf};
+
+use vf3lib_rs::{
+    GraphFormat, RunOptions,
+    formats::{csr, grf},
+    run_vf3_graphs,
+};
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+
+fn fixture_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(FIXTURE_DIR).join(name);
+    if !path.exists() {
+        panic!("missing bundled fixture: {}", path.display());
+    }
+    path
+}
+
+fn main() {
+    let mut dir = std::env::temp_dir();
+    dir.push("vf3_csr_example");
+    fs::create_dir_all(&dir).expect("Failed to create temp directory");
+    let target_csr = dir.join("bvg1.csr");
+
+    // Convert once; later runs only map the file.
+    let grf_opts = RunOptions {
+        format: GraphFormat::VFLegacy,
+        ..Default::default()
+    };
+    csr::convert(fixture_path("bvg1.grf"), &target_csr, &grf_opts).expect("Conversion failed");
+
+    let target = csr::MappedGraph::open(&target_csr).expect("Failed to map target");
+    let pattern_file = fs::File::open(fixture_path("bvg1.sub.grf")).expect("Missing pattern");
+    let pattern = grf::read(BufReader::new(pattern_file), false).expect("Failed to read pattern");
+    println!(
+        "Target: {} nodes, {} edges",
+        target.node_count(),
+        target.edge_count()
+    );
+
+    let opts = RunOptions {
+        repetition_time_limit: 0.25,
+        ..Default::default()
+    };
+    match run_vf3_graphs(&pattern, &target, opts) {
+        Ok(res) => println!(
+            "Solutions: {}, Time to first: {:.3}s, Total time: {:.3}s",
+            res.solutions, res.time_first, res.time_all
+        ),
+        Err(e) => eprintln!("Error: {e}"),
+    }
+}
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vf3lib_rs::GraphFormat;
+
+fuzz_target!(|data: &[u8]| vf3lib_rs_fuzz::read(GraphFormat::Csr, data));
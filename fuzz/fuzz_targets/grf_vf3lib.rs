@@ -0,0 +1,34 @@
+#![no_main]
+
+//! `.grf` files handed to the backend's own loader by the path runners.
+
+use std::{fs, path::PathBuf, sync::OnceLock};
+
+use libfuzzer_sys::fuzz_target;
+use vf3lib_rs::{GraphFormat, run_vf3};
+
+/// A one-node target, so any pattern that loads is matched at once.
+fn target() -> &'static str {
+    static TARGET: OnceLock<String> = OnceLock::new();
+    TARGET.get_or_init(|| {
+        let path = scratch("target");
+        fs::write(&path, "1\n0 0\n0\n").expect("writing the target");
+        path.to_string_lossy().into_owned()
+    })
+}
+
+fn scratch(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("vf3_fuzz_{}_{name}.grf", std::process::id()))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, data)) = data.split_first() else {
+        return;
+    };
+    let pattern = scratch("pattern");
+    fs::write(&pattern, data).expect("writing the pattern");
+    let mut opts = vf3lib_rs_fuzz::options(GraphFormat::VFLegacy, flags);
+    opts.repetition_time_limit = 0.0;
+    // Loading fails with `ExecutionFailed`; anything that loads is matched.
+    let _ = run_vf3(pattern.to_string_lossy().as_ref(), target(), opts);
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vf3lib_rs::GraphFormat;
+
+fuzz_target!(|data: &[u8]| {
+    // The last bit picks `.gfd` over `.gfu`; the option flags ignore it.
+    let format = match data.first() {
+        Some(flags) if flags & 0x80 != 0 => GraphFormat::Gfd,
+        _ => GraphFormat::Gfu,
+    };
+    vf3lib_rs_fuzz::read(format, data);
+});
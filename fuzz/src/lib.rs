@@ -0,0 +1,116 @@
+//! Checks shared by the fuzz targets.
+//!
+//! Every target feeds arbitrary bytes to a graph reader. The first byte
+//! selects reader options; the rest is the file content. Reading may fail
+//! with any [`VF3Error`], but it must not panic or abort, and a graph it
+//! returns must satisfy the invariants the matchers rely on.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+
+use vf3lib_rs::{
+    EdgeListOptions, Graph, GraphFormat, GraphMlOptions, LabelDictionary, LadOptions, NodeId,
+    RunOptions, VF3Error, formats::csr,
+};
+
+/// Largest single allocation the targets may make.
+const ALLOCATION_LIMIT: usize = 64 << 20;
+
+/// Refuses allocations above [`ALLOCATION_LIMIT`]. Sizes declared in a file
+/// header must be reserved fallibly, so a refusal becomes a read error; an
+/// infallible allocation of that size aborts and is reported as a crash.
+struct Capped;
+
+unsafe impl GlobalAlloc for Capped {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > ALLOCATION_LIMIT {
+            return std::ptr::null_mut();
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > ALLOCATION_LIMIT {
+            return std::ptr::null_mut();
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Capped = Capped;
+
+/// Options for `format` with the flags of the first input byte.
+pub fn options(format: GraphFormat, flags: u8) -> RunOptions {
+    let bit = |n: u8| flags & (1 << n) != 0;
+    let key = |set: bool| set.then(|| "label".to_owned());
+    RunOptions {
+        format,
+        undirected: bit(0),
+        edge_list: EdgeListOptions {
+            index_base: u64::from(bit(1)),
+            string_ids: bit(2),
+        },
+        lad: LadOptions {
+            node_labels: bit(1),
+            edge_labels: bit(2),
+        },
+        graphml: GraphMlOptions {
+            node_label_key: key(bit(1)),
+            edge_label_key: key(bit(2)),
+        },
+        ..Default::default()
+    }
+}
+
+/// Read `data` in `format` as a path-based run would and check the result.
+pub fn read(format: GraphFormat, data: &[u8]) {
+    let Some((&flags, data)) = data.split_first() else {
+        return;
+    };
+    let opts = options(format, flags);
+    check(Graph::from_reader_with(
+        data,
+        &opts,
+        &mut LabelDictionary::new(),
+    ));
+}
+
+/// Check the invariants of a graph that was read successfully.
+pub fn check(result: Result<Graph, VF3Error>) {
+    let Ok(graph) = result else {
+        return;
+    };
+    let count = graph.node_count();
+    let mut arcs = 0;
+    for node in 0..count as NodeId {
+        let successors = graph.successors(node);
+        let labels = graph.successor_labels(node);
+        assert_eq!(successors.len(), labels.len());
+        assert!(successors.windows(2).all(|w| w[0] < w[1]), "unsorted");
+        for (&succ, &label) in successors.iter().zip(labels) {
+            assert!((succ as usize) < count && succ != node);
+            if !graph.is_directed() {
+                assert_eq!(graph.edge_label(succ, node), Some(label));
+            }
+        }
+        arcs += successors.len();
+    }
+    let edges = if graph.is_directed() { arcs } else { arcs / 2 };
+    assert_eq!(graph.edge_count(), edges);
+
+    // The binary CSR form must carry the same graph.
+    let mut bytes = Vec::new();
+    csr::write(&mut bytes, &graph).expect("writing to memory");
+    let copy = csr::read(bytes.as_slice()).expect("CSR written by the crate");
+    assert_eq!(copy.is_directed(), graph.is_directed());
+    assert_eq!(copy.node_count(), count);
+    for node in 0..count as NodeId {
+        assert_eq!(copy.node_label(node), graph.node_label(node));
+        assert_eq!(copy.successors(node), graph.successors(node));
+        assert_eq!(copy.successor_labels(node), graph.successor_labels(node));
+    }
+}
@@ -0,0 +1,513 @@
+//! Command-line front end mirroring the `vf3`, `vf3l` and `vf3p` executables
+//! of vf3lib.
+//!
+//! Mappings are printed in vf3lib's `SolutionToString` format, one solution
+//! per line as `pattern,target:` pairs, or as JSON with `--json`. `first` and
+//! `isomorphic` exit with status 1 when there is no match; errors exit with
+//! status 2.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
+use vf3lib_rs::{
+    EdgeListOptions, Graph, GraphFormat, GraphMlOptions, LabelDictionary, LadOptions, MixedEdges,
+    ParallelOptions, ResultData, RunOptions, Tolerance, VF3Error,
+    formats::{
+        csr, dot,
+        graph6::{self, Graph6Format},
+        graphml, grf,
+    },
+    run_vf3, run_vf3_graphs, run_vf3l, run_vf3l_graphs, run_vf3p, run_vf3p_graphs,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "vf3",
+    version,
+    about = "Subgraph isomorphism with VF3, VF3L and VF3P"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print every mapping of the pattern into the target.
+    Match(MatchArgs),
+    /// Print the number of mappings and the search times.
+    Count(MatchArgs),
+    /// Print the first mapping found.
+    First(MatchArgs),
+    /// Decide whether two graphs are isomorphic and print a mapping if so.
+    Isomorphic(IsomorphicArgs),
+    /// Rewrite a graph in another format.
+    Convert(ConvertArgs),
+}
+
+#[derive(Args)]
+struct MatchArgs {
+    /// Pattern graph file.
+    pattern: String,
+    /// Target graph file.
+    target: String,
+    #[command(flatten)]
+    read: ReadArgs,
+    #[command(flatten)]
+    search: SearchArgs,
+    #[command(flatten)]
+    engine: EngineArgs,
+    /// Print the result as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct IsomorphicArgs {
+    /// First graph file.
+    first: String,
+    /// Second graph file.
+    second: String,
+    #[command(flatten)]
+    read: ReadArgs,
+    #[command(flatten)]
+    engine: EngineArgs,
+    /// Print the result as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// Graph file to read, or `-` for standard input.
+    input: String,
+    /// File to write, or `-` for standard output.
+    output: String,
+    /// Format to write.
+    #[arg(long, value_enum)]
+    to: OutputFormat,
+    #[command(flatten)]
+    read: ReadArgs,
+}
+
+/// How graph files are read ([`RunOptions`] reader fields).
+#[derive(Args)]
+struct ReadArgs {
+    /// Input file format.
+    #[arg(short, long, value_enum, default_value_t = InputFormat::Vf)]
+    format: InputFormat,
+    /// Treat graphs as undirected.
+    #[arg(short, long)]
+    undirected: bool,
+    /// Treat the pattern as undirected or not, whatever `--undirected` says.
+    #[arg(long, value_name = "BOOL")]
+    pattern_undirected: Option<bool>,
+    /// Treat the target as undirected or not, whatever `--undirected` says.
+    #[arg(long, value_name = "BOOL")]
+    target_undirected: Option<bool>,
+    /// Smallest numeric node id in edge lists.
+    #[arg(long, default_value_t = 1)]
+    index_base: u64,
+    /// Read edge-list node ids as names rather than integers.
+    #[arg(long)]
+    string_ids: bool,
+    /// GraphML key supplying node labels (empty for none).
+    #[arg(long, default_value = "label")]
+    node_label_key: String,
+    /// GraphML key supplying edge labels (empty for none).
+    #[arg(long, default_value = "label")]
+    edge_label_key: String,
+    /// LAD node lines start with a node label.
+    #[arg(long)]
+    lad_node_labels: bool,
+    /// LAD successors are followed by edge labels.
+    #[arg(long)]
+    lad_edge_labels: bool,
+}
+
+/// What counts as a match ([`RunOptions`] search fields).
+#[derive(Args)]
+struct SearchArgs {
+    /// Match edge-induced subgraphs (monomorphisms) instead of node-induced ones.
+    #[arg(short, long)]
+    edge_induced: bool,
+    /// How an undirected pattern matches a directed target.
+    #[arg(long, value_enum, default_value_t = Mixed::Reciprocal)]
+    mixed_edges: Mixed,
+    /// Find homomorphisms, where pattern nodes may share target nodes.
+    #[arg(long)]
+    homomorphism: bool,
+    /// Pattern nodes that may share target nodes with each other.
+    #[arg(long, value_delimiter = ',', value_name = "NODES")]
+    may_share: Vec<u32>,
+    /// Count distinct occurrences instead of every mapping.
+    #[arg(long)]
+    distinct_occurrences: bool,
+    /// Missing or extra edges a match may contain.
+    #[arg(long, default_value_t = 0)]
+    edge_errors: usize,
+    /// Mismatched node and edge labels a match may contain.
+    #[arg(long, default_value_t = 0)]
+    label_errors: usize,
+}
+
+/// Which matcher runs and how ([`RunOptions`] run fields and [`ParallelOptions`]).
+#[derive(Args)]
+struct EngineArgs {
+    /// Matcher to run.
+    #[arg(long, value_enum, default_value_t = Variant::Vf3)]
+    variant: Variant,
+    /// Repeat the search for at least this many seconds and report the
+    /// average times, for benchmarking (0 runs it once).
+    #[arg(short, long, default_value_t = 0.0)]
+    repetition_time_limit: f32,
+    /// VF3P worker threads.
+    #[arg(short, long, default_value_t = 1)]
+    threads: i16,
+    /// VF3P algorithm.
+    #[arg(short = 'a', long, value_enum, default_value_t = Parallel::Gss)]
+    parallel_algorithm: Parallel,
+    /// First CPU VF3P threads are pinned to (-1 disables pinning).
+    #[arg(long, default_value_t = -1, allow_negative_numbers = true)]
+    cpu: i16,
+    /// Use VF3P's lock-free stack.
+    #[arg(long)]
+    lock_free: bool,
+    /// Depth up to which WLS states go to the global stack.
+    #[arg(long, default_value_t = 3)]
+    ssr_high_limit: i16,
+    /// Maximum size of each WLS thread-local stack.
+    #[arg(long, default_value_t = 10)]
+    ssr_local_stack_limit: i16,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    Vf,
+    Edge,
+    Graphml,
+    Lad,
+    Dimacs,
+    Gfu,
+    Gfd,
+    Graph6,
+    Csr,
+}
+
+impl From<InputFormat> for GraphFormat {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Vf => GraphFormat::VFLegacy,
+            InputFormat::Edge => GraphFormat::EdgeList,
+            InputFormat::Graphml => GraphFormat::GraphML,
+            InputFormat::Lad => GraphFormat::Lad,
+            InputFormat::Dimacs => GraphFormat::Dimacs,
+            InputFormat::Gfu => GraphFormat::Gfu,
+            InputFormat::Gfd => GraphFormat::Gfd,
+            InputFormat::Graph6 => GraphFormat::Graph6,
+            InputFormat::Csr => GraphFormat::Csr,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Vf,
+    Graphml,
+    Graph6,
+    Sparse6,
+    Digraph6,
+    Csr,
+    Dot,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mixed {
+    Reciprocal,
+    Either,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Variant {
+    Vf3,
+    Vf3l,
+    Vf3p,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Parallel {
+    Gss,
+    Wls,
+}
+
+impl ReadArgs {
+    fn apply(&self, opts: &mut RunOptions) {
+        let key = |key: &str| (!key.is_empty()).then(|| key.to_owned());
+        opts.format = self.format.into();
+        opts.undirected = self.undirected;
+        opts.pattern_undirected = self.pattern_undirected;
+        opts.target_undirected = self.target_undirected;
+        opts.edge_list = EdgeListOptions {
+            index_base: self.index_base,
+            string_ids: self.string_ids,
+        };
+        opts.graphml = GraphMlOptions {
+            node_label_key: key(&self.node_label_key),
+            edge_label_key: key(&self.edge_label_key),
+        };
+        opts.lad = LadOptions {
+            node_labels: self.lad_node_labels,
+            edge_labels: self.lad_edge_labels,
+        };
+    }
+}
+
+impl SearchArgs {
+    fn apply(&self, opts: &mut RunOptions) {
+        opts.edge_induced = self.edge_induced;
+        opts.mixed_edges = match self.mixed_edges {
+            Mixed::Reciprocal => MixedEdges::Reciprocal,
+            Mixed::Either => MixedEdges::Either,
+        };
+        opts.homomorphism = self.homomorphism;
+        opts.may_share = self.may_share.clone();
+        opts.distinct_occurrences = self.distinct_occurrences;
+        opts.tolerance = Tolerance {
+            edges: self.edge_errors,
+            labels: self.label_errors,
+        };
+    }
+}
+
+impl EngineArgs {
+    fn apply(&self, opts: &mut RunOptions) {
+        opts.repetition_time_limit = self.repetition_time_limit;
+    }
+
+    fn parallel(&self) -> ParallelOptions {
+        ParallelOptions {
+            algo: match self.parallel_algorithm {
+                Parallel::Gss => 1,
+                Parallel::Wls => 2,
+            },
+            cpu: self.cpu,
+            num_threads: self.threads,
+            lock_free: self.lock_free,
+            ssr_high_limit: self.ssr_high_limit,
+            ssr_local_stack_limit: self.ssr_local_stack_limit,
+        }
+    }
+
+    fn run_files(
+        &self,
+        pattern: &str,
+        target: &str,
+        opts: RunOptions,
+    ) -> Result<ResultData, VF3Error> {
+        match self.variant {
+            Variant::Vf3 => run_vf3(pattern, target, opts),
+            Variant::Vf3l => run_vf3l(pattern, target, opts),
+            Variant::Vf3p => run_vf3p(pattern, target, opts, self.parallel()),
+        }
+    }
+
+    fn run_graphs(
+        &self,
+        pattern: &Graph,
+        target: &Graph,
+        opts: RunOptions,
+    ) -> Result<ResultData, VF3Error> {
+        match self.variant {
+            Variant::Vf3 => run_vf3_graphs(pattern, target, opts),
+            Variant::Vf3l => run_vf3l_graphs(pattern, target, opts),
+            Variant::Vf3p => run_vf3p_graphs(pattern, target, opts, self.parallel()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("vf3: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Run a command, returning whether it found what it looked for.
+fn run(command: Command) -> Result<bool, VF3Error> {
+    let mut out = io::stdout().lock();
+    match command {
+        Command::Match(args) => {
+            let res = search(&args, |opts| opts.store_solutions = true)?;
+            if args.json {
+                print_json(&mut out, &result_json(&res))?;
+            } else {
+                for line in solution_lines(&res) {
+                    writeln!(out, "{line}")?;
+                }
+            }
+            Ok(true)
+        }
+        Command::Count(args) => {
+            let res = search(&args, |_| {})?;
+            if args.json {
+                print_json(&mut out, &result_json(&res))?;
+            } else {
+                writeln!(out, "{} {} {}", res.solutions, res.time_first, res.time_all)?;
+            }
+            Ok(true)
+        }
+        Command::First(args) => {
+            let res = search(&args, |opts| {
+                opts.store_solutions = true;
+                opts.first_only = true;
+            })?;
+            print_first(&mut out, &res, args.json, "found")
+        }
+        Command::Isomorphic(args) => {
+            let res = isomorphism(&args)?;
+            print_first(&mut out, &res, args.json, "isomorphic")
+        }
+        Command::Convert(args) => {
+            convert(&args)?;
+            Ok(true)
+        }
+    }
+}
+
+/// Run the matcher `args` select on their files, after `configure` has set
+/// the options the subcommand needs.
+fn search(
+    args: &MatchArgs,
+    configure: impl FnOnce(&mut RunOptions),
+) -> Result<ResultData, VF3Error> {
+    let mut opts = RunOptions::default();
+    args.read.apply(&mut opts);
+    args.search.apply(&mut opts);
+    args.engine.apply(&mut opts);
+    configure(&mut opts);
+    args.engine.run_files(&args.pattern, &args.target, opts)
+}
+
+/// Look for an isomorphism as a node-induced match between graphs of equal
+/// size, so that no node or edge of the second graph is left over.
+fn isomorphism(args: &IsomorphicArgs) -> Result<ResultData, VF3Error> {
+    let mut opts = RunOptions::default();
+    args.read.apply(&mut opts);
+    args.engine.apply(&mut opts);
+    opts.store_solutions = true;
+    opts.first_only = true;
+    let mut labels = LabelDictionary::new();
+    let first = read_graph(&args.first, &opts, opts.pattern_undirected, &mut labels)?;
+    let second = read_graph(&args.second, &opts, opts.target_undirected, &mut labels)?;
+    if first.is_directed() != second.is_directed()
+        || first.node_count() != second.node_count()
+        || first.edge_count() != second.edge_count()
+    {
+        return Ok(ResultData::default());
+    }
+    args.engine.run_graphs(&first, &second, opts)
+}
+
+/// Read one graph file, or standard input for `-`, as a path-based run would.
+fn read_graph(
+    path: &str,
+    opts: &RunOptions,
+    undirected: Option<bool>,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    let opts = RunOptions {
+        undirected: undirected.unwrap_or(opts.undirected),
+        ..opts.clone()
+    };
+    let reader: Box<dyn Read> = if path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(File::open(path)?)
+    };
+    Graph::from_reader_with(reader, &opts, labels)
+}
+
+fn convert(args: &ConvertArgs) -> Result<(), VF3Error> {
+    let mut opts = RunOptions::default();
+    args.read.apply(&mut opts);
+    let graph = read_graph(&args.input, &opts, None, &mut LabelDictionary::new())?;
+    let writer: Box<dyn Write> = if args.output == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(File::create(&args.output)?)
+    };
+    let mut writer = BufWriter::new(writer);
+    match args.to {
+        OutputFormat::Vf => grf::write(&mut writer, &graph)?,
+        OutputFormat::Graphml => graphml::write(&mut writer, &graph)?,
+        OutputFormat::Graph6 => graph6::write(&mut writer, [&graph], Graph6Format::Graph6)?,
+        OutputFormat::Sparse6 => graph6::write(&mut writer, [&graph], Graph6Format::Sparse6)?,
+        OutputFormat::Digraph6 => graph6::write(&mut writer, [&graph], Graph6Format::Digraph6)?,
+        OutputFormat::Csr => csr::write(&mut writer, &graph)?,
+        OutputFormat::Dot => dot::write(&mut writer, &graph)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Each stored mapping as vf3lib's `SolutionToString` prints it, using node
+/// names when both graphs have them.
+fn solution_lines(res: &ResultData) -> Vec<String> {
+    fn line<P: Display, T: Display>(pairs: impl IntoIterator<Item = (P, T)>) -> String {
+        pairs
+            .into_iter()
+            .map(|(p, t)| format!("{p},{t}:"))
+            .collect()
+    }
+    match res.named_mappings() {
+        Some(named) => named.into_iter().map(line).collect(),
+        None => res
+            .mappings
+            .iter()
+            .map(|m| line(m.iter().copied()))
+            .collect(),
+    }
+}
+
+/// Print the first stored mapping, if any, and report whether there was one.
+fn print_first(
+    out: &mut impl Write,
+    res: &ResultData,
+    json: bool,
+    key: &str,
+) -> Result<bool, VF3Error> {
+    let found = !res.mappings.is_empty();
+    if json {
+        let mut value = json!({ key: found, "mapping": Value::Null });
+        if found {
+            value["mapping"] = json!(res.mappings[0]);
+            if let Some(named) = res.named_mappings() {
+                value["named_mapping"] = json!(named[0]);
+            }
+        }
+        print_json(out, &value)?;
+    } else if let Some(line) = solution_lines(res).first() {
+        writeln!(out, "{line}")?;
+    }
+    Ok(found)
+}
+
+/// A result in its serde form, the same shape the library serializes.
+fn result_json(res: &ResultData) -> Value {
+    serde_json::to_value(res).expect("results serialize to JSON")
+}
+
+fn print_json(out: &mut impl Write, value: &Value) -> Result<(), VF3Error> {
+    writeln!(out, "{value}")?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! The cxx bridge to the vendored vf3lib.
+
+use crate::visit::SolutionSink;
+
+#[cxx::bridge(namespace = "vf3ffi")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) mod vf3ffi {
+    /// Execution result from C++ VF3 algorithms.
+    #[derive(Debug, Clone)]
+    pub struct VF3Result {
+        /// Status code: 0 on success, non-zero on error.
+        pub status: i32,
+        /// Number of isomorphic mappings found.
+        pub solutions: u64,
+        /// Time to first solution in seconds.
+        pub time_first: f64,
+        /// Average total execution time in seconds.
+        pub time_all: f64,
+        /// Stored solutions (empty unless solutions are stored).
+        pub mappings: Vec<SolutionData>,
+    }
+
+    /// Target node matched to each pattern node, indexed by pattern node.
+    #[derive(Debug, Clone)]
+    pub struct SolutionData {
+        pub targets: Vec<u32>,
+    }
+
+    /// Borrowed graph in compressed sparse row form.
+    #[derive(Debug, Clone, Copy)]
+    pub struct GraphData<'a> {
+        /// Label of each node.
+        pub node_labels: &'a [i32],
+        /// Start of each node's successors in `targets`, plus a final end offset.
+        pub offsets: &'a [u32],
+        /// Concatenated, sorted successor lists.
+        pub targets: &'a [u32],
+        /// Label of each edge, aligned with `targets`.
+        pub edge_labels: &'a [i32],
+    }
+
+    extern "Rust" {
+        /// Per-solution callback of the in-memory runs.
+        type SolutionSink<'a>;
+
+        /// Whether the search should hand solutions to `visit`.
+        fn enabled(self: &SolutionSink<'_>) -> bool;
+
+        /// Called with the target node of each pattern node; returns true to
+        /// stop the search.
+        fn visit(self: &mut SolutionSink<'_>, targets: &[u32]) -> bool;
+    }
+
+    unsafe extern "C++" {
+        include!("vf3_bridge.hpp");
+
+        /// VF3 algorithm with all heuristics (best for medium/large dense graphs).
+        fn run_vf3(
+            pattern: &str,
+            target: &str,
+            format: &str,
+            undirected: bool,
+            store_solutions: bool,
+            first_only: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+        ) -> VF3Result;
+
+        /// VF3L lightweight variant without look-ahead (best for small/sparse graphs).
+        fn run_vf3l(
+            pattern: &str,
+            target: &str,
+            format: &str,
+            undirected: bool,
+            store_solutions: bool,
+            first_only: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+        ) -> VF3Result;
+
+        /// VF3P parallel variant for multi-threaded execution.
+        fn run_vf3p(
+            pattern: &str,
+            target: &str,
+            format: &str,
+            undirected: bool,
+            store_solutions: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+            algo: i8,
+            cpu: i16,
+            num_threads: i16,
+            lock_free: bool,
+            ssr_high_limit: i16,
+            ssr_local_stack_limit: i16,
+        ) -> VF3Result;
+
+        /// VF3 on in-memory graphs.
+        fn run_vf3_graph(
+            pattern: &GraphData<'_>,
+            target: &GraphData<'_>,
+            store_solutions: bool,
+            first_only: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+            sink: &mut SolutionSink<'_>,
+        ) -> VF3Result;
+
+        /// VF3L on in-memory graphs.
+        fn run_vf3l_graph(
+            pattern: &GraphData<'_>,
+            target: &GraphData<'_>,
+            store_solutions: bool,
+            first_only: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+            sink: &mut SolutionSink<'_>,
+        ) -> VF3Result;
+
+        /// VF3P on in-memory graphs.
+        fn run_vf3p_graph(
+            pattern: &GraphData<'_>,
+            target: &GraphData<'_>,
+            store_solutions: bool,
+            verbose: bool,
+            repetition_time_limit: f32,
+            edge_induced: bool,
+            algo: i8,
+            cpu: i16,
+            num_threads: i16,
+            lock_free: bool,
+            ssr_high_limit: i16,
+            ssr_local_stack_limit: i16,
+            sink: &mut SolutionSink<'_>,
+        ) -> VF3Result;
+    }
+}
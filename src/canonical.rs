@@ -0,0 +1,279 @@
+//! Canonical labelling by colour refinement and individualisation.
+
+use crate::{GraphInput, NodeId, graph::Csr};
+
+/// Certificate of a graph's isomorphism class.
+///
+/// Two graphs get the same bytes exactly when they are isomorphic in the
+/// sense used for matching: a bijection between their nodes preserving node
+/// labels, edges and edge labels. Directedness is part of the certificate,
+/// and an undirected graph is compared through both directions of each edge.
+///
+/// The bytes are a deterministic serialisation of the relabelled graph, so
+/// they can be hashed or compared directly to deduplicate collections. They
+/// are stable for a given version of this crate but not guaranteed across
+/// versions.
+pub fn canonical_form(graph: &impl GraphInput) -> Vec<u8> {
+    Canon::new(&graph.csr()).run().0
+}
+
+/// Canonical position of every node: relabelling node `n` as
+/// `labeling[n]` yields the same graph for every graph in the isomorphism
+/// class, the one [`canonical_form`] serialises.
+pub fn canonical_labeling(graph: &impl GraphInput) -> Vec<NodeId> {
+    Canon::new(&graph.csr()).run().1
+}
+
+type Arcs = Vec<Vec<(NodeId, i32)>>;
+
+struct Canon {
+    directed: bool,
+    labels: Vec<i32>,
+    successors: Arcs,
+    predecessors: Arcs,
+    /// Smallest certificate so far and the labelling producing it.
+    best: Option<(Vec<u8>, Vec<NodeId>)>,
+    /// Certificate and labelling of the first leaf, with the nodes
+    /// individualised on the way to it.
+    first: Option<(Vec<u8>, Vec<NodeId>, Vec<NodeId>)>,
+    /// Automorphisms found as pairs of leaves with equal certificates.
+    automorphisms: Vec<Vec<NodeId>>,
+}
+
+impl Canon {
+    fn new(csr: &Csr<'_>) -> Self {
+        let count = csr.node_labels.len();
+        let mut successors = vec![Vec::new(); count];
+        let mut predecessors = vec![Vec::new(); count];
+        for (from, succ) in successors.iter_mut().enumerate() {
+            let range = csr.offsets[from] as usize..csr.offsets[from + 1] as usize;
+            for (&to, &label) in csr.targets[range.clone()]
+                .iter()
+                .zip(&csr.edge_labels[range])
+            {
+                succ.push((to, label));
+                predecessors[to as usize].push((from as NodeId, label));
+            }
+        }
+        Self {
+            directed: csr.directed,
+            labels: csr.node_labels.to_vec(),
+            successors,
+            predecessors,
+            best: None,
+            first: None,
+            automorphisms: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> (Vec<u8>, Vec<NodeId>) {
+        let colors = rank(&self.labels);
+        self.visit(colors, &mut Vec::new());
+        self.best.expect("the search reaches at least one leaf")
+    }
+
+    /// Search below the partition `colors`, reached by individualising
+    /// `path`. Returns the depth to go back to when a leaf equivalent to the
+    /// first one shows the rest of this subtree to be explored already.
+    fn visit(&mut self, colors: Vec<u32>, path: &mut Vec<NodeId>) -> Option<usize> {
+        let colors = self.refine(colors);
+        let count = colors.len();
+        let Some(cell) = target_cell(&colors) else {
+            return self.leaf(colors, path);
+        };
+
+        let depth = path.len();
+        let mut orbits = Orbits::new(count);
+        let mut merged = 0;
+        let mut explored: Vec<NodeId> = Vec::new();
+        for node in cell {
+            // Automorphisms fixing `path` map the subtrees of nodes in one
+            // orbit onto each other, so one node per orbit is enough.
+            for perm in &self.automorphisms[merged..] {
+                if path.iter().all(|&n| perm[n as usize] == n) {
+                    for (from, &to) in perm.iter().enumerate() {
+                        orbits.union(from as NodeId, to);
+                    }
+                }
+            }
+            merged = self.automorphisms.len();
+            let root = orbits.find(node);
+            if explored.iter().any(|&other| orbits.find(other) == root) {
+                continue;
+            }
+            explored.push(node);
+            let individualised = rank(
+                &(0..count)
+                    .map(|n| (colors[n], n as NodeId != node))
+                    .collect::<Vec<_>>(),
+            );
+            path.push(node);
+            let back_to = self.visit(individualised, path);
+            path.pop();
+            if back_to.is_some_and(|level| level < depth) {
+                return back_to;
+            }
+        }
+        None
+    }
+
+    /// Split colour classes by the colours and labels of their neighbours
+    /// until the partition is stable.
+    fn refine(&self, mut colors: Vec<u32>) -> Vec<u32> {
+        let mut cells = cell_count(&colors);
+        loop {
+            let signatures: Vec<_> = (0..colors.len())
+                .map(|n| {
+                    let around = |arcs: &[(NodeId, i32)]| {
+                        let mut seen: Vec<_> =
+                            arcs.iter().map(|&(m, l)| (colors[m as usize], l)).collect();
+                        seen.sort_unstable();
+                        seen
+                    };
+                    let pred = if self.directed {
+                        around(&self.predecessors[n])
+                    } else {
+                        Vec::new()
+                    };
+                    (colors[n], around(&self.successors[n]), pred)
+                })
+                .collect();
+            colors = rank(&signatures);
+            let refined = cell_count(&colors);
+            if refined == cells {
+                return colors;
+            }
+            cells = refined;
+        }
+    }
+
+    /// Record the leaf reached through `path`. A leaf equal to the first
+    /// one returns the depth where `path` leaves the first path: the
+    /// automorphism between them maps every later sibling there onto the
+    /// subtree already searched.
+    fn leaf(&mut self, labeling: Vec<NodeId>, path: &[NodeId]) -> Option<usize> {
+        let certificate = self.certificate(&labeling);
+        let Some((first, first_labeling, first_path)) = &self.first else {
+            self.first = Some((certificate.clone(), labeling.clone(), path.to_vec()));
+            self.best = Some((certificate, labeling));
+            return None;
+        };
+        if *first == certificate {
+            let automorphism = automorphism(&labeling, first_labeling);
+            let common = path.iter().zip(first_path).take_while(|(a, b)| a == b);
+            let depth = common.count();
+            self.automorphisms.push(automorphism);
+            return Some(depth);
+        }
+        match &self.best {
+            Some((best, _)) if *best < certificate => {}
+            Some((best, best_labeling)) if *best == certificate => {
+                let automorphism = automorphism(&labeling, best_labeling);
+                self.automorphisms.push(automorphism);
+            }
+            _ => self.best = Some((certificate, labeling)),
+        }
+        None
+    }
+
+    /// Serialise the graph relabelled by `labeling`: directedness, node count,
+    /// node labels, then each node's out-degree and sorted labelled arcs.
+    fn certificate(&self, labeling: &[NodeId]) -> Vec<u8> {
+        let count = labeling.len();
+        let mut at = vec![0; count];
+        for (node, &pos) in labeling.iter().enumerate() {
+            at[pos as usize] = node;
+        }
+        let mut bytes = vec![u8::from(self.directed)];
+        bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        for &node in &at {
+            bytes.extend_from_slice(&self.labels[node].to_le_bytes());
+        }
+        for &node in &at {
+            let mut arcs: Vec<_> = self.successors[node]
+                .iter()
+                .map(|&(to, label)| (labeling[to as usize], label))
+                .collect();
+            arcs.sort_unstable();
+            bytes.extend_from_slice(&(arcs.len() as u32).to_le_bytes());
+            for (to, label) in arcs {
+                bytes.extend_from_slice(&to.to_le_bytes());
+                bytes.extend_from_slice(&label.to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+/// Map each node to the node sharing its position in the leaf labelled
+/// `other`; the two nodes play the same role.
+fn automorphism(labeling: &[NodeId], other: &[NodeId]) -> Vec<NodeId> {
+    let mut at = vec![0; labeling.len()];
+    for (node, &pos) in other.iter().enumerate() {
+        at[pos as usize] = node as NodeId;
+    }
+    labeling.iter().map(|&pos| at[pos as usize]).collect()
+}
+
+/// Orbits of the automorphisms merged so far, as a union-find forest.
+struct Orbits {
+    parent: Vec<NodeId>,
+}
+
+impl Orbits {
+    fn new(count: usize) -> Self {
+        Self {
+            parent: (0..count as NodeId).collect(),
+        }
+    }
+
+    fn find(&mut self, mut node: NodeId) -> NodeId {
+        while self.parent[node as usize] != node {
+            let parent = self.parent[node as usize];
+            self.parent[node as usize] = self.parent[parent as usize];
+            node = parent;
+        }
+        node
+    }
+
+    fn union(&mut self, a: NodeId, b: NodeId) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b) as usize] = a.min(b);
+        }
+    }
+}
+
+/// Dense ranks of `keys`, so equal keys share a colour and colours follow
+/// key order.
+fn rank<T: Ord>(keys: &[T]) -> Vec<u32> {
+    let mut order: Vec<_> = (0..keys.len()).collect();
+    order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    let mut colors = vec![0; keys.len()];
+    let mut color = 0;
+    for (idx, &node) in order.iter().enumerate() {
+        if idx > 0 && keys[order[idx - 1]] != keys[node] {
+            color += 1;
+        }
+        colors[node] = color;
+    }
+    colors
+}
+
+fn cell_count(colors: &[u32]) -> usize {
+    colors.iter().max().map_or(0, |&c| c as usize + 1)
+}
+
+/// Nodes of the lowest colour shared by several nodes, if any.
+fn target_cell(colors: &[u32]) -> Option<Vec<NodeId>> {
+    let mut sizes = vec![0usize; cell_count(colors)];
+    for &c in colors {
+        sizes[c as usize] += 1;
+    }
+    let color = sizes.iter().position(|&size| size > 1)? as u32;
+    Some(
+        (0..colors.len() as NodeId)
+            .filter(|&n| colors[n as usize] == color)
+            .collect(),
+    )
+}
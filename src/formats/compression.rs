@@ -0,0 +1,88 @@
+//! Transparent decompression of graph files.
+//!
+//! Compression is recognised by the `.gz`/`.zst` extension or by the gzip and
+//! zstd magic bytes, and decoding requires the `gzip` or `zstd` feature.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::VF3Error;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Whether `path` holds compressed data.
+///
+/// Unreadable files are judged by their extension alone, leaving the error to
+/// whichever loader opens them.
+pub(crate) fn detect(path: &Path) -> Compression {
+    let mut head = [0; 4];
+    let read = File::open(path)
+        .and_then(|mut file| file.read(&mut head))
+        .unwrap_or(0);
+    detect_in(path, &head[..read])
+}
+
+/// Open a graph file, decompressing it on the fly when needed.
+pub(crate) fn open(path: &Path) -> Result<Box<dyn BufRead>, VF3Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = detect_in(path, reader.fill_buf()?);
+    decode(reader, compression, &path.display().to_string())
+}
+
+/// Wrap a reader, decompressing it when it starts with gzip or zstd magic
+/// bytes.
+pub(crate) fn wrap<'a, R: BufRead + 'a>(mut reader: R) -> Result<Box<dyn BufRead + 'a>, VF3Error> {
+    let compression = detect_in(Path::new(""), reader.fill_buf()?);
+    decode(reader, compression, "stream")
+}
+
+fn decode<'a, R: BufRead + 'a>(
+    reader: R,
+    compression: Compression,
+    source: &str,
+) -> Result<Box<dyn BufRead + 'a>, VF3Error> {
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(
+            reader,
+        )?))),
+        #[allow(unreachable_patterns)]
+        _ => Err(VF3Error::UnsupportedFormat {
+            format: format!(
+                "{compression:?}-compressed input {source} (enable the `{}` feature)",
+                if compression == Compression::Gzip {
+                    "gzip"
+                } else {
+                    "zstd"
+                }
+            ),
+        }),
+    }
+}
+
+fn detect_in(path: &Path, head: &[u8]) -> Compression {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    if head.starts_with(GZIP_MAGIC) || extension == Some("gz") {
+        Compression::Gzip
+    } else if head.starts_with(ZSTD_MAGIC) || extension == Some("zst") {
+        Compression::Zstd
+    } else {
+        Compression::None
+    }
+}
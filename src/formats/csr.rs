@@ -0,0 +1,332 @@
+//! Compact binary graph format in compressed sparse row (CSR) layout.
+//!
+//! All integers are little-endian. A 32-byte header is followed by four
+//! arrays of 4-byte words, so a memory-mapped file can be handed to the
+//! matchers without parsing or copying:
+//!
+//! | Field         | Type             | Contents                               |
+//! |---------------|------------------|----------------------------------------|
+//! | magic         | `[u8; 8]`        | `VF3CSR\0\0`                           |
+//! | version       | `u32`            | `1`                                    |
+//! | flags         | `u32`            | bit 0 set for directed graphs          |
+//! | node count    | `u64`            | `n`                                    |
+//! | arc count     | `u64`            | `m`; undirected edges are stored twice |
+//! | node labels   | `[i32; n]`       |                                        |
+//! | offsets       | `[u32; n + 1]`   | start of each successor list, then `m` |
+//! | targets       | `[u32; m]`       | sorted successor lists                 |
+//! | edge labels   | `[i32; m]`       | aligned with `targets`                 |
+//!
+//! An undirected edge is stored as an arc in each direction, both with the
+//! edge's label; files that break this are rejected.
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    Graph, LabelDictionary, NodeId, RunOptions, VF3Error,
+    graph::{Csr, Sealed},
+};
+
+const MAGIC: &[u8; 8] = b"VF3CSR\0\0";
+const VERSION: u32 = 1;
+const FLAG_DIRECTED: u32 = 1;
+const HEADER_LEN: usize = 32;
+
+/// A binary CSR graph file mapped into memory.
+///
+/// Pass it to [`run_vf3_graphs`](crate::run_vf3_graphs) and friends, alone or
+/// together with a [`Graph`], to match against it without loading it.
+#[derive(Debug)]
+pub struct MappedGraph {
+    mmap: Mmap,
+    layout: Layout,
+}
+
+impl MappedGraph {
+    /// Map and validate a binary CSR file.
+    ///
+    /// The file must not be modified while it is mapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VF3Error::Io`] if the file cannot be mapped and
+    /// [`VF3Error::Parse`] if it is not a valid CSR graph.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, VF3Error> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only; callers are told not to modify the
+        // file while it is mapped, as memmap2 requires.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let layout = Layout::validate(&mmap)?;
+        Ok(Self { mmap, layout })
+    }
+
+    /// Whether edges are directed.
+    pub fn is_directed(&self) -> bool {
+        self.layout.directed
+    }
+
+    /// Number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.layout.nodes
+    }
+
+    /// Number of edges; an undirected edge counts once.
+    pub fn edge_count(&self) -> usize {
+        self.layout.edge_count()
+    }
+
+    /// Copy the mapped graph into a [`Graph`].
+    pub fn to_graph(&self) -> Graph {
+        self.layout.csr(&self.mmap).to_graph()
+    }
+}
+
+impl Sealed for MappedGraph {
+    fn csr(&self) -> Csr<'_> {
+        self.layout.csr(&self.mmap)
+    }
+
+    fn names(&self) -> Option<&[String]> {
+        None
+    }
+}
+
+/// Write a graph in the binary CSR format.
+///
+/// Node names are not stored.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if writing fails.
+pub fn write<W: Write>(mut writer: W, graph: &Graph) -> Result<(), VF3Error> {
+    let csr = graph.to_csr();
+    let flags = if csr.directed { FLAG_DIRECTED } else { 0 };
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&flags.to_le_bytes())?;
+    writer.write_all(&(csr.node_labels.len() as u64).to_le_bytes())?;
+    writer.write_all(&(csr.targets.len() as u64).to_le_bytes())?;
+    for label in csr.node_labels.iter() {
+        writer.write_all(&label.to_le_bytes())?;
+    }
+    for offset in csr.offsets.iter().chain(csr.targets.iter()) {
+        writer.write_all(&offset.to_le_bytes())?;
+    }
+    for label in csr.edge_labels.iter() {
+        writer.write_all(&label.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Read a binary CSR graph into a [`Graph`] without memory-mapping it.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if reading fails and [`VF3Error::Parse`] if the
+/// data is not a valid CSR graph.
+pub fn read<R: Read>(mut reader: R) -> Result<Graph, VF3Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let layout = Layout::validate(&bytes)?;
+    Ok(layout.csr(&bytes).to_graph())
+}
+
+/// Convert a graph file in any supported format into a binary CSR file.
+///
+/// The input is read according to `opts.format` and the matching reader
+/// options; `opts.undirected` applies as it does when matching.
+///
+/// # Errors
+///
+/// Returns the errors of the input's reader, or [`VF3Error::Io`] if writing
+/// fails.
+pub fn convert(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    opts: &RunOptions,
+) -> Result<(), VF3Error> {
+    let graph = super::load_graph(input.as_ref(), opts, &mut LabelDictionary::new())?;
+    let mut writer = std::io::BufWriter::new(File::create(output)?);
+    write(&mut writer, &graph)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Byte ranges of a validated CSR file.
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    directed: bool,
+    nodes: usize,
+    arcs: usize,
+}
+
+impl Layout {
+    fn validate(bytes: &[u8]) -> Result<Self, VF3Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(invalid("not a binary CSR graph"));
+        }
+        let word = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let long = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        if word(8) != VERSION {
+            return Err(invalid(format!("unsupported version {}", word(8))));
+        }
+        let (nodes, arcs) = (long(16), long(24));
+        if nodes > u64::from(NodeId::MAX) || arcs > u64::from(u32::MAX) {
+            return Err(invalid("graph too large"));
+        }
+        let layout = Self {
+            directed: word(12) & FLAG_DIRECTED != 0,
+            nodes: nodes as usize,
+            arcs: arcs as usize,
+        };
+        if bytes.len() as u64 != layout.len() {
+            return Err(invalid(format!(
+                "expected {} bytes, found {}",
+                layout.len(),
+                bytes.len()
+            )));
+        }
+
+        // The C++ loader indexes with these values, so check them all.
+        let csr = layout.csr(bytes);
+        if csr.offsets[0] != 0
+            || csr.offsets[layout.nodes] as usize != layout.arcs
+            || csr.offsets.windows(2).any(|w| w[0] > w[1])
+        {
+            return Err(invalid("offsets do not cover the arcs in order"));
+        }
+        for node in 0..layout.nodes {
+            let succ = &csr.targets[csr.offsets[node] as usize..csr.offsets[node + 1] as usize];
+            if succ.windows(2).any(|w| w[0] >= w[1])
+                || succ
+                    .iter()
+                    .any(|&t| t as usize >= layout.nodes || t as usize == node)
+            {
+                return Err(invalid(format!("invalid successors of node {node}")));
+            }
+        }
+        // An undirected edge is stored as two arcs with the same label; the
+        // matchers read the arcs as they are, so a missing half would turn
+        // the edge directed.
+        if !layout.directed {
+            let arcs = |node: usize| csr.offsets[node] as usize..csr.offsets[node + 1] as usize;
+            for from in 0..layout.nodes {
+                for idx in arcs(from) {
+                    let (to, label) = (csr.targets[idx], csr.edge_labels[idx]);
+                    let back = arcs(to as usize);
+                    let reverse = csr.targets[back.clone()]
+                        .binary_search(&(from as NodeId))
+                        .ok()
+                        .map(|at| csr.edge_labels[back.start + at]);
+                    if reverse != Some(label) {
+                        return Err(invalid(format!(
+                            "undirected edge {from}-{to} is not stored in both directions \
+                             with the same label"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(layout)
+    }
+
+    fn len(&self) -> u64 {
+        HEADER_LEN as u64 + 4 * (2 * self.nodes as u64 + 1 + 2 * self.arcs as u64)
+    }
+
+    fn edge_count(&self) -> usize {
+        if self.directed {
+            self.arcs
+        } else {
+            self.arcs / 2
+        }
+    }
+
+    fn csr<'a>(&self, bytes: &'a [u8]) -> Csr<'a> {
+        let mut at = HEADER_LEN;
+        let mut take = |count: usize| {
+            let slice = &bytes[at..at + 4 * count];
+            at += 4 * count;
+            slice
+        };
+        Csr {
+            directed: self.directed,
+            node_labels: words(take(self.nodes)),
+            offsets: words(take(self.nodes + 1)),
+            targets: words(take(self.arcs)),
+            edge_labels: words(take(self.arcs)),
+        }
+    }
+}
+
+impl Csr<'_> {
+    pub(crate) fn to_graph(&self) -> Graph {
+        let mut graph = if self.directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for &label in self.node_labels.iter() {
+            graph.add_node(label);
+        }
+        for node in 0..self.node_labels.len() {
+            let range = self.offsets[node] as usize..self.offsets[node + 1] as usize;
+            for (&to, &label) in self.targets[range.clone()]
+                .iter()
+                .zip(&self.edge_labels[range])
+            {
+                graph.add_labeled_edge(node as NodeId, to, label);
+            }
+        }
+        graph
+    }
+}
+
+/// 4-byte integers that are valid for every bit pattern.
+trait Word: Copy {
+    fn from_le(bytes: [u8; 4]) -> Self;
+}
+
+impl Word for i32 {
+    fn from_le(bytes: [u8; 4]) -> Self {
+        i32::from_le_bytes(bytes)
+    }
+}
+
+impl Word for u32 {
+    fn from_le(bytes: [u8; 4]) -> Self {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+/// Reinterpret little-endian words in place when possible, copying otherwise.
+fn words<T: Word>(bytes: &[u8]) -> Cow<'_, [T]> {
+    #[cfg(target_endian = "little")]
+    {
+        // SAFETY: `Word` is only implemented for i32 and u32, which are valid
+        // for any bit pattern and stored in native byte order here.
+        let (prefix, words, suffix) = unsafe { bytes.align_to::<T>() };
+        if prefix.is_empty() && suffix.is_empty() {
+            return Cow::Borrowed(words);
+        }
+    }
+    Cow::Owned(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| T::from_le(chunk.try_into().expect("4-byte chunk")))
+            .collect(),
+    )
+}
+
+fn invalid(message: impl Into<String>) -> VF3Error {
+    VF3Error::Parse {
+        line: 0,
+        message: message.into(),
+    }
+}
@@ -0,0 +1,91 @@
+//! DIMACS graph format: `c` comments, one `p edge <nodes> <edges>` problem
+//! line, `e u v` edges and optional `n v label` node labels, with 1-based ids.
+
+use std::io::BufRead;
+
+use super::parse_error;
+use crate::{Graph, NodeId, VF3Error};
+
+/// Parse a DIMACS graph. DIMACS graphs are undirected, so node `v` of the file
+/// becomes node `v - 1` of an undirected [`Graph`]; repeated edges are ignored.
+pub(crate) fn parse<R: BufRead>(reader: R) -> Result<Graph, VF3Error> {
+    let mut graph: Option<Graph> = None;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        let mut tokens = line.split_whitespace();
+        let Some(kind) = tokens.next() else {
+            continue;
+        };
+        match kind {
+            "c" => continue,
+            "p" => {
+                if graph.is_some() {
+                    return Err(parse_error(line_no, "duplicate problem line"));
+                }
+                let (Some(_format), Some(count)) = (tokens.next(), tokens.next()) else {
+                    return Err(parse_error(
+                        line_no,
+                        "expected `p <format> <nodes> <edges>`",
+                    ));
+                };
+                let count: usize = count
+                    .parse()
+                    .map_err(|_| parse_error(line_no, format!("invalid node count {count:?}")))?;
+                if count > NodeId::MAX as usize {
+                    return Err(parse_error(
+                        line_no,
+                        format!("node count {count} too large"),
+                    ));
+                }
+                let mut g = Graph::undirected();
+                g.try_reserve_nodes(count)
+                    .map_err(|_| parse_error(line_no, format!("cannot allocate {count} nodes")))?;
+                for _ in 0..count {
+                    g.add_node(0);
+                }
+                graph = Some(g);
+            }
+            "e" | "n" => {
+                let Some(g) = graph.as_mut() else {
+                    return Err(parse_error(line_no, "problem line missing before data"));
+                };
+                let (Some(a), Some(b)) = (tokens.next(), tokens.next()) else {
+                    return Err(parse_error(
+                        line_no,
+                        format!("expected two values after `{kind}`"),
+                    ));
+                };
+                let a = parse_node(a, g.node_count(), line_no)?;
+                if kind == "n" {
+                    let label = b
+                        .parse()
+                        .map_err(|_| parse_error(line_no, format!("invalid node label {b:?}")))?;
+                    g.set_node_label(a, label);
+                } else {
+                    let b = parse_node(b, g.node_count(), line_no)?;
+                    if a == b {
+                        return Err(parse_error(line_no, format!("self-loop on node {}", a + 1)));
+                    }
+                    g.add_edge(a, b);
+                }
+            }
+            other => {
+                return Err(parse_error(line_no, format!("unknown line type {other:?}")));
+            }
+        }
+    }
+
+    graph.ok_or_else(|| parse_error(1, "missing problem line"))
+}
+
+fn parse_node(token: &str, count: usize, line: usize) -> Result<NodeId, VF3Error> {
+    match token.parse::<usize>() {
+        Ok(id @ 1..) if id <= count => Ok((id - 1) as NodeId),
+        _ => Err(parse_error(
+            line,
+            format!("node {token:?} out of range 1..={count}"),
+        )),
+    }
+}
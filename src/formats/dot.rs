@@ -0,0 +1,327 @@
+//! Graphviz DOT rendering of graphs and matches, for inspecting small cases.
+//!
+//! Nodes are drawn with their name, or their id when the graph has no names,
+//! followed by `:label` unless every node label is `0`. Edges show their
+//! label unless every edge label is `0`. Undirected graphs become `graph`
+//! documents with each edge drawn once, directed ones `digraph` documents.
+//! Render the output with `dot -Tsvg`.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use crate::{Graph, MatchRegion, NodeId};
+
+/// Fill colour of highlighted nodes.
+const NODE_FILL: &str = "#ffd966";
+/// Colour of highlighted edges.
+const EDGE_COLOR: &str = "#d62728";
+
+/// Write a graph as DOT.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+pub fn write<W: Write>(mut writer: W, graph: &Graph) -> io::Result<()> {
+    let kind = Kind::of([graph]);
+    writeln!(writer, "{} G {{", kind.keyword())?;
+    write_graph(&mut writer, kind, graph, "n", "  ", &Highlight::default())?;
+    writeln!(writer, "}}")
+}
+
+/// Write `target` with the nodes and edges matched by `mapping` highlighted.
+///
+/// Each matched target node is annotated with the pattern nodes mapped onto
+/// it, and each image of a pattern edge with that edge, as `p-q` (`p->q` for
+/// directed patterns). Pattern edges whose image is missing, as in matches
+/// with tolerated errors, are not drawn.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+///
+/// # Panics
+///
+/// Panics if `mapping` names a node outside `pattern` or `target`.
+pub fn write_match<W: Write>(
+    mut writer: W,
+    pattern: &Graph,
+    target: &Graph,
+    mapping: &[(NodeId, NodeId)],
+) -> io::Result<()> {
+    let kind = Kind::of([target]);
+    writeln!(writer, "{} match {{", kind.keyword())?;
+    let highlight = Highlight::of_match(pattern, target, mapping);
+    write_graph(&mut writer, kind, target, "n", "  ", &highlight)?;
+    writeln!(writer, "}}")
+}
+
+/// Write `target` with the nodes and edges of `region` highlighted, each
+/// annotated with the number of matches covering it.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+pub fn write_region<W: Write>(
+    mut writer: W,
+    target: &Graph,
+    region: &MatchRegion,
+) -> io::Result<()> {
+    let kind = Kind::of([target]);
+    writeln!(writer, "{} region {{", kind.keyword())?;
+    let matches = |count: u64| match count {
+        1 => "1 match".to_owned(),
+        _ => format!("{count} matches"),
+    };
+    let highlight = Highlight {
+        nodes: region
+            .nodes
+            .iter()
+            .map(|(&node, &count)| (node, matches(count)))
+            .collect(),
+        edges: region
+            .edges
+            .iter()
+            .map(|(&edge, &count)| (edge, matches(count)))
+            .collect(),
+    };
+    write_graph(&mut writer, kind, target, "n", "  ", &highlight)?;
+    writeln!(writer, "}}")
+}
+
+/// Write `pattern` and `target` side by side, as clusters of one document,
+/// with the match highlighted as by [`write_match`] if a `mapping` is given
+/// and each pattern node joined to its image by a dashed line.
+///
+/// Pattern nodes are `p<id>` and target nodes `t<id>` in the document. An
+/// undirected graph drawn next to a directed one has its edges drawn once,
+/// without arrowheads.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+///
+/// # Panics
+///
+/// Panics if `mapping` names a node outside `pattern` or `target`.
+pub fn write_side_by_side<W: Write>(
+    mut writer: W,
+    pattern: &Graph,
+    target: &Graph,
+    mapping: Option<&[(NodeId, NodeId)]>,
+) -> io::Result<()> {
+    let kind = Kind::of([pattern, target]);
+    writeln!(writer, "{} match {{", kind.keyword())?;
+    let (pattern_highlight, target_highlight) = match mapping {
+        Some(mapping) => (
+            Highlight::of_pattern(pattern, mapping),
+            Highlight::of_match(pattern, target, mapping),
+        ),
+        None => Default::default(),
+    };
+    writeln!(writer, "  subgraph cluster_pattern {{")?;
+    writeln!(writer, "    label=\"pattern\";")?;
+    write_graph(&mut writer, kind, pattern, "p", "    ", &pattern_highlight)?;
+    writeln!(writer, "  }}")?;
+    writeln!(writer, "  subgraph cluster_target {{")?;
+    writeln!(writer, "    label=\"target\";")?;
+    write_graph(&mut writer, kind, target, "t", "    ", &target_highlight)?;
+    writeln!(writer, "  }}")?;
+    for &(p, t) in mapping.unwrap_or_default() {
+        writeln!(
+            writer,
+            "  p{p} {} t{t} [style=dashed, color=gray, constraint=false, dir=none];",
+            kind.edge_op()
+        )?;
+    }
+    writeln!(writer, "}}")
+}
+
+/// Document type, from the graphs it draws.
+#[derive(Clone, Copy)]
+enum Kind {
+    Graph,
+    Digraph,
+}
+
+impl Kind {
+    fn of<'g>(graphs: impl IntoIterator<Item = &'g Graph>) -> Self {
+        if graphs.into_iter().any(Graph::is_directed) {
+            Kind::Digraph
+        } else {
+            Kind::Graph
+        }
+    }
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Kind::Graph => "graph",
+            Kind::Digraph => "digraph",
+        }
+    }
+
+    fn edge_op(self) -> &'static str {
+        match self {
+            Kind::Graph => "--",
+            Kind::Digraph => "->",
+        }
+    }
+}
+
+/// Nodes and edges to highlight, with the text added to each. Edges of
+/// undirected graphs are keyed once, lower node first.
+#[derive(Default)]
+struct Highlight {
+    nodes: HashMap<NodeId, String>,
+    edges: HashMap<(NodeId, NodeId), String>,
+}
+
+impl Highlight {
+    /// The images of `mapping` in `target`, annotated with their pattern
+    /// counterparts.
+    fn of_match(pattern: &Graph, target: &Graph, mapping: &[(NodeId, NodeId)]) -> Self {
+        let mut highlight = Self::default();
+        for &(p, t) in mapping {
+            assert!((t as usize) < target.node_count(), "node {t} out of range");
+            let text = highlight.nodes.entry(t).or_default();
+            if !text.is_empty() {
+                text.push(',');
+            }
+            text.push_str(&name(pattern, p));
+        }
+        let images: HashMap<NodeId, NodeId> = mapping.iter().copied().collect();
+        let op = if pattern.is_directed() { "->" } else { "-" };
+        for &(p, from) in mapping {
+            for &q in pattern.successors(p) {
+                if !pattern.is_directed() && p > q {
+                    continue;
+                }
+                let Some(&to) = images.get(&q) else {
+                    continue;
+                };
+                let text = format!("{}{op}{}", name(pattern, p), name(pattern, q));
+                // An undirected pattern edge is matched by either direction.
+                let directions = if pattern.is_directed() { 1 } else { 2 };
+                for (a, b) in [(from, to), (to, from)].into_iter().take(directions) {
+                    if target.has_edge(a, b) {
+                        highlight.edges.insert(key(target, a, b), text.clone());
+                    }
+                }
+            }
+        }
+        highlight
+    }
+
+    /// The pattern nodes and edges of `mapping`, without annotations.
+    fn of_pattern(pattern: &Graph, mapping: &[(NodeId, NodeId)]) -> Self {
+        let mut highlight = Self::default();
+        for &(p, _) in mapping {
+            highlight.nodes.insert(p, String::new());
+        }
+        for &(p, _) in mapping {
+            for &q in pattern.successors(p) {
+                if highlight.nodes.contains_key(&q) {
+                    highlight.edges.insert(key(pattern, p, q), String::new());
+                }
+            }
+        }
+        highlight
+    }
+}
+
+fn write_graph<W: Write>(
+    writer: &mut W,
+    kind: Kind,
+    graph: &Graph,
+    prefix: &str,
+    indent: &str,
+    highlight: &Highlight,
+) -> io::Result<()> {
+    let count = graph.node_count() as NodeId;
+    let node_labels = (0..count).any(|node| graph.node_label(node) != 0);
+    let edge_labels = (0..count).any(|node| graph.successor_labels(node).iter().any(|&l| l != 0));
+
+    for node in 0..count {
+        let mut text = name(graph, node);
+        if node_labels {
+            let _ = write!(text, ":{}", graph.node_label(node));
+        }
+        write!(writer, "{indent}{prefix}{node} [label=\"{}", escape(&text))?;
+        match highlight.nodes.get(&node) {
+            Some(note) => {
+                if !note.is_empty() {
+                    write!(writer, "\\n({})", escape(note))?;
+                }
+                writeln!(
+                    writer,
+                    "\", style=filled, fillcolor=\"{NODE_FILL}\", penwidth=2];"
+                )?;
+            }
+            None => writeln!(writer, "\"];")?,
+        }
+    }
+
+    for from in 0..count {
+        for (&to, &label) in graph
+            .successors(from)
+            .iter()
+            .zip(graph.successor_labels(from))
+        {
+            if !graph.is_directed() && from > to {
+                continue;
+            }
+            let mut attrs = Vec::new();
+            let mut text = Vec::new();
+            if edge_labels {
+                text.push(label.to_string());
+            }
+            if let Some(note) = highlight.edges.get(&key(graph, from, to)) {
+                if !note.is_empty() {
+                    text.push(format!("({note})"));
+                }
+                attrs.push(format!("color=\"{EDGE_COLOR}\""));
+                attrs.push("penwidth=2".to_owned());
+            }
+            if !text.is_empty() {
+                attrs.insert(0, format!("label=\"{}\"", escape(&text.join(" "))));
+            }
+            if !graph.is_directed() && matches!(kind, Kind::Digraph) {
+                attrs.push("dir=none".to_owned());
+            }
+            write!(
+                writer,
+                "{indent}{prefix}{from} {} {prefix}{to}",
+                kind.edge_op()
+            )?;
+            if attrs.is_empty() {
+                writeln!(writer, ";")?;
+            } else {
+                writeln!(writer, " [{}];", attrs.join(", "))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A node's name, or its id when the graph has no names.
+fn name(graph: &Graph, node: NodeId) -> String {
+    graph
+        .node_name(node)
+        .map_or_else(|| node.to_string(), str::to_owned)
+}
+
+/// Key of an edge: as given when directed, lower node first otherwise.
+fn key(graph: &Graph, from: NodeId, to: NodeId) -> (NodeId, NodeId) {
+    if graph.is_directed() || from < to {
+        (from, to)
+    } else {
+        (to, from)
+    }
+}
+
+/// Escape text for a double-quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
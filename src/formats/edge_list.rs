@@ -0,0 +1,125 @@
+//! Edge list format: one `u v` pair per line, `#` starts a comment line.
+
+use std::{collections::HashMap, io::BufRead};
+
+use super::parse_error;
+use crate::{Graph, NodeId, VF3Error};
+
+/// Options controlling how edge-list node identifiers are interpreted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EdgeListOptions {
+    /// Smallest valid numeric node id (`1` for the historical one-based format).
+    pub index_base: u64,
+    /// Treat node ids as opaque tokens (e.g. `user:42`) instead of integers.
+    ///
+    /// Names are interned in order of first appearance and `index_base` is ignored.
+    pub string_ids: bool,
+}
+
+impl Default for EdgeListOptions {
+    fn default() -> Self {
+        Self {
+            index_base: 1,
+            string_ids: false,
+        }
+    }
+}
+
+/// Parse an edge list into a graph whose node names are the original tokens.
+///
+/// Numeric ids are compacted in ascending order, so node `0` of the result is
+/// the smallest id present in the file. All node labels are `0`.
+pub(crate) fn parse<R: BufRead>(
+    reader: R,
+    undirected: bool,
+    opts: &EdgeListOptions,
+) -> Result<Graph, VF3Error> {
+    let mut edges = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut interned: HashMap<String, NodeId> = HashMap::new();
+    let mut numeric: Vec<u64> = Vec::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let (Some(u), Some(v)) = (tokens.next(), tokens.next()) else {
+            return Err(parse_error(line_no, "expected two node ids"));
+        };
+        if u == v {
+            return Err(parse_error(line_no, format!("self-loop on node {u}")));
+        }
+
+        if opts.string_ids {
+            let mut intern = |token: &str| {
+                *interned.entry(token.to_owned()).or_insert_with(|| {
+                    names.push(token.to_owned());
+                    (names.len() - 1) as NodeId
+                })
+            };
+            edges.push((intern(u) as u64, intern(v) as u64));
+        } else {
+            let u = parse_numeric(u, opts.index_base, line_no)?;
+            let v = parse_numeric(v, opts.index_base, line_no)?;
+            if u == v {
+                return Err(parse_error(line_no, format!("self-loop on node {u}")));
+            }
+            numeric.push(u);
+            numeric.push(v);
+            edges.push((u, v));
+        }
+    }
+
+    let remap: Option<HashMap<u64, NodeId>> = if opts.string_ids {
+        None
+    } else {
+        numeric.sort_unstable();
+        numeric.dedup();
+        names = numeric.iter().map(u64::to_string).collect();
+        Some(
+            numeric
+                .iter()
+                .enumerate()
+                .map(|(idx, &id)| (id, idx as NodeId))
+                .collect(),
+        )
+    };
+
+    let mut graph = if undirected {
+        Graph::undirected()
+    } else {
+        Graph::directed()
+    };
+    for name in names {
+        let id = graph.add_node(0);
+        graph.set_node_name(id, name);
+    }
+    for (u, v) in edges {
+        let (u, v) = match &remap {
+            Some(remap) => (remap[&u], remap[&v]),
+            None => (u as NodeId, v as NodeId),
+        };
+        graph.add_edge(u, v);
+    }
+    Ok(graph)
+}
+
+fn parse_numeric(token: &str, base: u64, line: usize) -> Result<u64, VF3Error> {
+    let id: u64 = token
+        .parse()
+        .map_err(|_| parse_error(line, format!("invalid node id {token:?}")))?;
+    if id < base {
+        return Err(parse_error(
+            line,
+            format!("node id {id} is below the index base {base}"),
+        ));
+    }
+    Ok(id)
+}
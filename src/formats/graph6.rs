@@ -0,0 +1,357 @@
+//! nauty's graph6, sparse6 and digraph6 formats, one graph per line.
+//!
+//! The format of each line is detected from its first character (`:` for
+//! sparse6, `&` for digraph6, graph6 otherwise), and an optional
+//! `>>graph6<<`-style header is skipped. graph6 and sparse6 produce
+//! undirected graphs, digraph6 directed ones; all labels are `0`.
+//! Incremental sparse6 (`;`) is not supported.
+
+use std::io::{BufRead, Write};
+
+use super::parse_error;
+use crate::{Graph, NodeId, VF3Error};
+
+/// Output encoding for [`encode`] and [`write()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Graph6Format {
+    /// Dense upper-triangle encoding of undirected graphs.
+    Graph6,
+    /// Edge-list encoding of undirected graphs, compact for sparse graphs.
+    Sparse6,
+    /// Dense encoding of directed graphs.
+    Digraph6,
+}
+
+/// Decode a single graph6, sparse6 or digraph6 string.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Parse`] if the string is malformed or encodes a
+/// self-loop.
+pub fn decode(line: &str) -> Result<Graph, VF3Error> {
+    decode_line(line, 1)
+}
+
+/// Read a file of graph6, sparse6 or digraph6 strings, one graph per line.
+///
+/// Blank lines are skipped and the formats may be mixed.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if reading fails and [`VF3Error::Parse`] for
+/// malformed lines.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<Graph>, VF3Error> {
+    let mut graphs = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            graphs.push(decode_line(&line, idx + 1)?);
+        }
+    }
+    Ok(graphs)
+}
+
+/// Encode a graph, ignoring node and edge labels.
+///
+/// Undirected graphs may also be written as digraph6, with each edge in both
+/// directions.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::UnsupportedFormat`] when writing a directed graph as
+/// graph6 or sparse6.
+pub fn encode(graph: &Graph, format: Graph6Format) -> Result<String, VF3Error> {
+    if graph.is_directed() && format != Graph6Format::Digraph6 {
+        return Err(VF3Error::UnsupportedFormat {
+            format: format!("{format:?} for a directed graph"),
+        });
+    }
+    let n = graph.node_count();
+    let mut out = String::new();
+    let mut bits = BitWriter::default();
+    match format {
+        Graph6Format::Graph6 => {
+            encode_size(&mut out, n);
+            for j in 0..n as NodeId {
+                for i in 0..j {
+                    bits.push(graph.has_edge(i, j));
+                }
+            }
+            bits.finish(&mut out, false);
+        }
+        Graph6Format::Digraph6 => {
+            out.push('&');
+            encode_size(&mut out, n);
+            for i in 0..n as NodeId {
+                for j in 0..n as NodeId {
+                    bits.push(graph.has_edge(i, j));
+                }
+            }
+            bits.finish(&mut out, false);
+        }
+        Graph6Format::Sparse6 => {
+            out.push(':');
+            encode_size(&mut out, n);
+            let k = width(n);
+            let mut edges: Vec<(NodeId, NodeId)> = (0..n as NodeId)
+                .flat_map(|u| graph.successors(u).iter().map(move |&v| (u, v)))
+                .filter(|&(u, v)| u < v)
+                .collect();
+            edges.sort_unstable_by_key(|&(u, v)| (v, u));
+            let mut current = 0;
+            for (u, v) in edges {
+                if v == current {
+                    bits.push(false);
+                } else {
+                    bits.push(true);
+                    if v > current + 1 {
+                        bits.push_value(u64::from(v), k);
+                        bits.push(false);
+                    }
+                    current = v;
+                }
+                bits.push_value(u64::from(u), k);
+            }
+            // Padding of ones would decode as a spurious edge {n-1, n-1} in
+            // this corner case, so nauty starts it with a zero bit.
+            if bits.padding() > k && n >= 2 && current as usize == n - 2 && n == 1 << k {
+                bits.push(false);
+            }
+            bits.finish(&mut out, true);
+        }
+    }
+    Ok(out)
+}
+
+/// Write graphs one per line in the given format.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if writing fails and the errors of [`encode`].
+pub fn write<'a, W: Write>(
+    mut writer: W,
+    graphs: impl IntoIterator<Item = &'a Graph>,
+    format: Graph6Format,
+) -> Result<(), VF3Error> {
+    for graph in graphs {
+        writeln!(writer, "{}", encode(graph, format)?)?;
+    }
+    Ok(())
+}
+
+/// Read a file that must hold exactly one graph, for the path-based runners.
+pub(crate) fn read_single<R: BufRead>(reader: R) -> Result<Graph, VF3Error> {
+    let mut graphs = read(reader)?;
+    match graphs.len() {
+        1 => Ok(graphs.remove(0)),
+        0 => Err(parse_error(1, "file contains no graph")),
+        n => Err(parse_error(
+            1,
+            format!(
+                "file contains {n} graphs; read them with formats::graph6::read and run each one"
+            ),
+        )),
+    }
+}
+
+fn decode_line(line: &str, line_no: usize) -> Result<Graph, VF3Error> {
+    let mut text = line.trim();
+    for header in [">>graph6<<", ">>sparse6<<", ">>digraph6<<"] {
+        if let Some(rest) = text.strip_prefix(header) {
+            text = rest;
+        }
+    }
+    let (format, body) = match text.as_bytes().first() {
+        Some(b':') => (Graph6Format::Sparse6, &text[1..]),
+        Some(b';') => {
+            return Err(parse_error(line_no, "incremental sparse6 is not supported"));
+        }
+        Some(b'&') => (Graph6Format::Digraph6, &text[1..]),
+        _ => (Graph6Format::Graph6, text),
+    };
+
+    let mut data = Vec::with_capacity(body.len());
+    for byte in body.bytes() {
+        if !(63..=126).contains(&byte) {
+            return Err(parse_error(
+                line_no,
+                format!("invalid character {:?}", byte as char),
+            ));
+        }
+        data.push(byte - 63);
+    }
+    let (n, data) =
+        decode_size(&data).ok_or_else(|| parse_error(line_no, "missing or invalid node count"))?;
+
+    let needed = match format {
+        Graph6Format::Graph6 => n as u64 * n.saturating_sub(1) as u64 / 2,
+        Graph6Format::Digraph6 => n as u64 * n as u64,
+        Graph6Format::Sparse6 => 0,
+    };
+    if needed > data.len() as u64 * 6 {
+        return Err(parse_error(line_no, "data too short for the node count"));
+    }
+
+    let mut graph = if format == Graph6Format::Digraph6 {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    // Sparse6 data need not grow with the node count.
+    graph
+        .try_reserve_nodes(n)
+        .map_err(|_| parse_error(line_no, format!("cannot allocate {n} nodes")))?;
+    for _ in 0..n {
+        graph.add_node(0);
+    }
+    let mut bits = BitReader::new(data);
+    match format {
+        Graph6Format::Graph6 => {
+            for j in 0..n as NodeId {
+                for i in 0..j {
+                    if bits.bit() == Some(true) {
+                        graph.add_edge(i, j);
+                    }
+                }
+            }
+        }
+        Graph6Format::Digraph6 => {
+            for i in 0..n as NodeId {
+                for j in 0..n as NodeId {
+                    if bits.bit() == Some(true) {
+                        if i == j {
+                            return Err(parse_error(line_no, format!("self-loop on node {i}")));
+                        }
+                        graph.add_edge(i, j);
+                    }
+                }
+            }
+        }
+        Graph6Format::Sparse6 => {
+            let k = width(n);
+            let mut v = 0u64;
+            while let Some(b) = bits.bit() {
+                let Some(x) = bits.value(k) else {
+                    break;
+                };
+                if b {
+                    v += 1;
+                }
+                if x > v {
+                    v = x;
+                } else if v < n as u64 {
+                    if x == v {
+                        return Err(parse_error(line_no, format!("self-loop on node {v}")));
+                    }
+                    graph.add_edge(x as NodeId, v as NodeId);
+                }
+                if v >= n as u64 {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Number of bits needed to write `n - 1`.
+fn width(n: usize) -> u32 {
+    usize::BITS - n.saturating_sub(1).leading_zeros()
+}
+
+fn encode_size(out: &mut String, n: usize) {
+    let (prefix, digits): (&str, u32) = match n {
+        0..=62 => ("", 1),
+        63..=258_047 => ("~", 3),
+        _ => ("~~", 6),
+    };
+    if digits == 1 {
+        out.push((n as u8 + 63) as char);
+        return;
+    }
+    out.push_str(prefix);
+    for shift in (0..digits).rev() {
+        out.push((((n >> (6 * shift)) & 0x3f) as u8 + 63) as char);
+    }
+}
+
+fn decode_size(data: &[u8]) -> Option<(usize, &[u8])> {
+    let value = |digits: &[u8]| {
+        digits
+            .iter()
+            .fold(0usize, |acc, &d| (acc << 6) | usize::from(d))
+    };
+    match data {
+        [63, 63, rest @ ..] if rest.len() >= 6 => {
+            let n = value(&rest[..6]);
+            (n <= NodeId::MAX as usize).then_some((n, &rest[6..]))
+        }
+        // `~~` with fewer than six size bytes is truncated, not a short size.
+        [63, 63, ..] => None,
+        [63, rest @ ..] if rest.len() >= 3 => Some((value(&rest[..3]), &rest[3..])),
+        [first, rest @ ..] if *first < 63 => Some((usize::from(*first), rest)),
+        _ => None,
+    }
+}
+
+/// Six-bit groups, most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(6) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().expect("byte pushed above") |= 1 << (5 - self.len % 6);
+        }
+        self.len += 1;
+    }
+
+    fn push_value(&mut self, value: u64, width: u32) {
+        for shift in (0..width).rev() {
+            self.push((value >> shift) & 1 == 1);
+        }
+    }
+
+    /// Bits left in the last group.
+    fn padding(&self) -> u32 {
+        ((6 - self.len % 6) % 6) as u32
+    }
+
+    /// Append the groups to `out`, padding the last one with ones or zeros.
+    fn finish(mut self, out: &mut String, ones: bool) {
+        if ones {
+            for _ in 0..self.padding() {
+                self.push(true);
+            }
+        }
+        out.extend(self.bytes.iter().map(|&b| (b + 63) as char));
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bit(&mut self) -> Option<bool> {
+        let byte = self.data.get(self.pos / 6)?;
+        let bit = (byte >> (5 - self.pos % 6)) & 1 == 1;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    fn value(&mut self, width: u32) -> Option<u64> {
+        (0..width).try_fold(0u64, |acc, _| Some((acc << 1) | u64::from(self.bit()?)))
+    }
+}
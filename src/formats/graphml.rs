@@ -0,0 +1,548 @@
+//! GraphML reader and writer.
+//!
+//! Nodes, edges, the `edgedefault` and per-edge `directed` attributes, and one
+//! configurable `<data>` key each for node and edge labels are supported.
+//! Nested graphs, hyperedges and ports are not.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use quick_xml::{
+    Reader, XmlVersion,
+    escape::{escape, resolve_predefined_entity},
+    events::{BytesStart, Event},
+};
+
+use super::parse_error;
+use crate::{Graph, LabelDictionary, Mapping, NodeId, VF3Error, labels::Labelled};
+
+/// Options controlling which GraphML `<data>` keys become labels.
+///
+/// A key is selected by its `id` or its `attr.name`. Values of keys declared
+/// with `attr.type="int"` or `"long"` are used as integers; all other values
+/// are interned through a [`LabelDictionary`], as labels from `1` up.
+/// Elements without a value take the key's `<default>`, or label `0`. Graphs
+/// read with one dictionary must use the same kind of key for their node
+/// labels, and likewise for their edge labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GraphMlOptions {
+    /// Key supplying node labels (`None` labels every node `0`).
+    pub node_label_key: Option<String>,
+    /// Key supplying edge labels (`None` labels every edge `0`).
+    pub edge_label_key: Option<String>,
+}
+
+impl Default for GraphMlOptions {
+    fn default() -> Self {
+        Self {
+            node_label_key: Some("label".into()),
+            edge_label_key: Some("label".into()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct KeyDef {
+    line: usize,
+    domain: String,
+    name: Option<String>,
+    integer: bool,
+    default: Option<String>,
+}
+
+#[derive(Debug)]
+struct Element {
+    line: usize,
+    data: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct EdgeElement {
+    source: String,
+    target: String,
+    directed: Option<bool>,
+    element: Element,
+}
+
+/// What the text currently being read belongs to.
+enum TextSink {
+    None,
+    KeyDefault(String),
+    Data(String),
+}
+
+#[derive(Default)]
+struct Document {
+    keys: HashMap<String, KeyDef>,
+    directed: Option<bool>,
+    nodes: Vec<(String, Element)>,
+    edges: Vec<EdgeElement>,
+}
+
+/// Read the first graph of a GraphML document.
+///
+/// String labels are interned in `labels`; read a pattern and its target
+/// with the same dictionary so that equal strings compare equal.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if reading fails and [`VF3Error::Parse`] for
+/// malformed or unsupported documents.
+pub fn read<R: Read>(
+    mut reader: R,
+    opts: &GraphMlOptions,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let doc = parse_document(&text)?;
+    build_graph(doc, opts, labels)
+}
+
+fn parse_document(text: &str) -> Result<Document, VF3Error> {
+    let mut reader = Reader::from_str(text);
+    // Error positions need not fall on a character boundary.
+    let line_at = |pos: u64| {
+        let end = (pos as usize).min(text.len());
+        text.as_bytes()[..end]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1
+    };
+
+    let mut doc = Document::default();
+    let mut graph_depth = 0usize;
+    let mut current_key: Option<String> = None;
+    let mut current_node: Option<(String, Element)> = None;
+    let mut current_edge: Option<EdgeElement> = None;
+    let mut sink = TextSink::None;
+    let mut buffer = String::new();
+
+    loop {
+        let pos = reader.buffer_position();
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(line_at(reader.error_position()), e.to_string()))?;
+        let line = line_at(pos);
+        let empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                "key" => {
+                    let id = required_attr(&e, "id", line)?;
+                    let def = KeyDef {
+                        line,
+                        domain: attr(&e, "for", line)?.unwrap_or_else(|| "all".into()),
+                        name: attr(&e, "attr.name", line)?,
+                        integer: matches!(
+                            attr(&e, "attr.type", line)?.as_deref(),
+                            Some("int" | "long")
+                        ),
+                        default: None,
+                    };
+                    doc.keys.insert(id.clone(), def);
+                    if !empty {
+                        current_key = Some(id);
+                    }
+                }
+                "default" => {
+                    if let (Some(key), false) = (&current_key, empty) {
+                        sink = TextSink::KeyDefault(key.clone());
+                        buffer.clear();
+                    }
+                }
+                "graph" => {
+                    graph_depth += 1;
+                    if graph_depth > 1 || current_node.is_some() {
+                        return Err(parse_error(line, "nested graphs are not supported"));
+                    }
+                    if doc.directed.is_some() {
+                        // Only the first graph of a document is read.
+                        break;
+                    }
+                    doc.directed =
+                        Some(attr(&e, "edgedefault", line)?.as_deref() != Some("undirected"));
+                    if empty {
+                        graph_depth -= 1;
+                    }
+                }
+                "node" if graph_depth == 1 => {
+                    let node = (
+                        required_attr(&e, "id", line)?,
+                        Element {
+                            line,
+                            data: HashMap::new(),
+                        },
+                    );
+                    if empty {
+                        doc.nodes.push(node);
+                    } else {
+                        current_node = Some(node);
+                    }
+                }
+                "edge" if graph_depth == 1 => {
+                    let directed = match attr(&e, "directed", line)?.as_deref() {
+                        None => None,
+                        Some("true") => Some(true),
+                        Some("false") => Some(false),
+                        Some(other) => {
+                            return Err(parse_error(
+                                line,
+                                format!("invalid directed value {other:?}"),
+                            ));
+                        }
+                    };
+                    let edge = EdgeElement {
+                        source: required_attr(&e, "source", line)?,
+                        target: required_attr(&e, "target", line)?,
+                        directed,
+                        element: Element {
+                            line,
+                            data: HashMap::new(),
+                        },
+                    };
+                    if empty {
+                        doc.edges.push(edge);
+                    } else {
+                        current_edge = Some(edge);
+                    }
+                }
+                "data" if current_node.is_some() || current_edge.is_some() => {
+                    let key = required_attr(&e, "key", line)?;
+                    if empty {
+                        insert_data(&mut current_node, &mut current_edge, key, String::new());
+                    } else {
+                        sink = TextSink::Data(key);
+                        buffer.clear();
+                    }
+                }
+                "hyperedge" => {
+                    return Err(parse_error(line, "hyperedges are not supported"));
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                "key" => current_key = None,
+                "default" => {
+                    if let TextSink::KeyDefault(key) = std::mem::replace(&mut sink, TextSink::None)
+                        && let Some(def) = doc.keys.get_mut(&key)
+                    {
+                        def.default = Some(buffer.trim().to_owned());
+                    }
+                }
+                "graph" => graph_depth = graph_depth.saturating_sub(1),
+                "node" => doc.nodes.extend(current_node.take()),
+                "edge" => doc.edges.extend(current_edge.take()),
+                "data" => {
+                    if let TextSink::Data(key) = std::mem::replace(&mut sink, TextSink::None) {
+                        let value = buffer.trim().to_owned();
+                        insert_data(&mut current_node, &mut current_edge, key, value);
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(e) if !matches!(sink, TextSink::None) => {
+                buffer.push_str(&e.xml10_content());
+            }
+            Event::CData(e) if !matches!(sink, TextSink::None) => {
+                buffer.push_str(&e.xml10_content());
+            }
+            Event::GeneralRef(e) if !matches!(sink, TextSink::None) => match e.resolve_char_ref() {
+                Ok(Some(ch)) => buffer.push(ch),
+                _ => match resolve_predefined_entity(&e) {
+                    Some(value) => buffer.push_str(value),
+                    None => {
+                        return Err(parse_error(line, format!("unknown entity &{};", &*e)));
+                    }
+                },
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if doc.directed.is_none() {
+        return Err(parse_error(line_at(0), "no <graph> element found"));
+    }
+    Ok(doc)
+}
+
+fn insert_data(
+    node: &mut Option<(String, Element)>,
+    edge: &mut Option<EdgeElement>,
+    key: String,
+    value: String,
+) {
+    if let Some(edge) = edge {
+        edge.element.data.insert(key, value);
+    } else if let Some((_, node)) = node {
+        node.data.insert(key, value);
+    }
+}
+
+fn build_graph(
+    doc: Document,
+    opts: &GraphMlOptions,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    let directed = doc.directed.unwrap_or(true);
+    let node_key = find_key(&doc.keys, opts.node_label_key.as_deref(), "node");
+    let edge_key = find_key(&doc.keys, opts.edge_label_key.as_deref(), "edge");
+    for (key, labelled) in [(node_key, Labelled::Node), (edge_key, Labelled::Edge)] {
+        if let Some((_, def)) = key {
+            labels
+                .set_kind(labelled, def.integer)
+                .map_err(|message| parse_error(def.line, message))?;
+        }
+    }
+
+    let mut graph = if directed {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    let mut ids: HashMap<String, NodeId> = HashMap::with_capacity(doc.nodes.len());
+    for (id, element) in &doc.nodes {
+        if ids.contains_key(id) {
+            return Err(parse_error(
+                element.line,
+                format!("duplicate node id {id:?}"),
+            ));
+        }
+        let label = element_label(element, node_key, labels)?;
+        let node = graph.add_node(label);
+        graph.set_node_name(node, id.clone());
+        ids.insert(id.clone(), node);
+    }
+
+    for edge in &doc.edges {
+        let line = edge.element.line;
+        let endpoint = |id: &str| {
+            ids.get(id)
+                .copied()
+                .ok_or_else(|| parse_error(line, format!("edge refers to unknown node {id:?}")))
+        };
+        let (source, target) = (endpoint(&edge.source)?, endpoint(&edge.target)?);
+        if source == target {
+            return Err(parse_error(
+                line,
+                format!("self-loop on node {:?}", edge.source),
+            ));
+        }
+        let label = element_label(&edge.element, edge_key, labels)?;
+        match (directed, edge.directed.unwrap_or(directed)) {
+            (true, true) | (false, false) => {
+                graph.add_labeled_edge(source, target, label);
+            }
+            (true, false) => {
+                graph.add_labeled_edge(source, target, label);
+                graph.add_labeled_edge(target, source, label);
+            }
+            (false, true) => {
+                return Err(parse_error(
+                    line,
+                    "directed edges in an undirected graph are not supported",
+                ));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+fn find_key<'a>(
+    keys: &'a HashMap<String, KeyDef>,
+    wanted: Option<&str>,
+    domain: &str,
+) -> Option<(&'a str, &'a KeyDef)> {
+    let wanted = wanted?;
+    let applies = |def: &KeyDef| def.domain == domain || def.domain == "all";
+    keys.get_key_value(wanted)
+        .filter(|(_, def)| applies(def))
+        .or_else(|| {
+            keys.iter()
+                .find(|(_, def)| applies(def) && def.name.as_deref() == Some(wanted))
+        })
+        .map(|(id, def)| (id.as_str(), def))
+}
+
+fn element_label(
+    element: &Element,
+    key: Option<(&str, &KeyDef)>,
+    labels: &mut LabelDictionary,
+) -> Result<i32, VF3Error> {
+    let Some((id, def)) = key else {
+        return Ok(0);
+    };
+    let Some(value) = element.data.get(id).or(def.default.as_ref()) else {
+        return Ok(0);
+    };
+    if def.integer {
+        value.parse().map_err(|_| {
+            parse_error(
+                element.line,
+                format!("label {value:?} is not a 32-bit integer"),
+            )
+        })
+    } else {
+        Ok(labels.intern(value))
+    }
+}
+
+fn attr(e: &BytesStart<'_>, name: &str, line: usize) -> Result<Option<String>, VF3Error> {
+    for attribute in e.attributes() {
+        let attribute = attribute.map_err(|err| parse_error(line, err.to_string()))?;
+        if attribute.key.local_name().as_ref() == name || attribute.key.as_ref() == name {
+            let value = attribute
+                .normalized_value(XmlVersion::default())
+                .map_err(|err| parse_error(line, err.to_string()))?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn required_attr(e: &BytesStart<'_>, name: &str, line: usize) -> Result<String, VF3Error> {
+    attr(e, name, line)?.ok_or_else(|| {
+        parse_error(
+            line,
+            format!(
+                "<{}> is missing the {name:?} attribute",
+                e.local_name().as_ref()
+            ),
+        )
+    })
+}
+
+/// Write a graph as GraphML with integer `label` keys for nodes and edges.
+///
+/// Node ids are the graph's node names when present, otherwise `n<index>`.
+/// Undirected edges are written once.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+pub fn write<W: Write>(mut writer: W, graph: &Graph) -> io::Result<()> {
+    write_header(&mut writer, graph.is_directed(), false)?;
+    for node in 0..graph.node_count() as NodeId {
+        write_node(&mut writer, graph, node, None)?;
+    }
+    for from in 0..graph.node_count() as NodeId {
+        for (&to, &label) in graph
+            .successors(from)
+            .iter()
+            .zip(graph.successor_labels(from))
+        {
+            if graph.is_directed() || from < to {
+                write_edge(&mut writer, graph, from, to, label)?;
+            }
+        }
+    }
+    write_footer(&mut writer)
+}
+
+/// Write the part of `target` matched by `mapping` as GraphML.
+///
+/// Nodes are the mapped target nodes, each with a `pattern` data value naming
+/// its pattern counterpart; edges are the images of the pattern's edges.
+///
+/// # Errors
+///
+/// Returns any error from the underlying writer.
+pub fn write_match<W: Write>(
+    mut writer: W,
+    pattern: &Graph,
+    target: &Graph,
+    mapping: &Mapping,
+) -> io::Result<()> {
+    let image: HashMap<NodeId, NodeId> = mapping.iter().copied().collect();
+    write_header(&mut writer, target.is_directed(), true)?;
+    for &(p, t) in mapping {
+        write_node(&mut writer, target, t, Some(&node_id(pattern, p)))?;
+    }
+    for &(p, _) in mapping {
+        for &q in pattern.successors(p) {
+            let (Some(&from), Some(&to)) = (image.get(&p), image.get(&q)) else {
+                continue;
+            };
+            if !pattern.is_directed() && p > q {
+                continue;
+            }
+            if let Some(label) = target.edge_label(from, to) {
+                write_edge(&mut writer, target, from, to, label)?;
+            }
+        }
+    }
+    write_footer(&mut writer)
+}
+
+fn node_id(graph: &Graph, node: NodeId) -> String {
+    graph
+        .node_name(node)
+        .map_or_else(|| format!("n{node}"), str::to_owned)
+}
+
+fn write_header<W: Write>(writer: &mut W, directed: bool, pattern_key: bool) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="int"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="elabel" for="edge" attr.name="label" attr.type="int"/>"#
+    )?;
+    if pattern_key {
+        writeln!(
+            writer,
+            r#"  <key id="pattern" for="node" attr.name="pattern" attr.type="string"/>"#
+        )?;
+    }
+    let edgedefault = if directed { "directed" } else { "undirected" };
+    writeln!(writer, r#"  <graph id="G" edgedefault="{edgedefault}">"#)
+}
+
+fn write_node<W: Write>(
+    writer: &mut W,
+    graph: &Graph,
+    node: NodeId,
+    pattern: Option<&str>,
+) -> io::Result<()> {
+    write!(
+        writer,
+        r#"    <node id="{}"><data key="label">{}</data>"#,
+        escape(node_id(graph, node)),
+        graph.node_label(node)
+    )?;
+    if let Some(pattern) = pattern {
+        write!(writer, r#"<data key="pattern">{}</data>"#, escape(pattern))?;
+    }
+    writeln!(writer, "</node>")
+}
+
+fn write_edge<W: Write>(
+    writer: &mut W,
+    graph: &Graph,
+    from: NodeId,
+    to: NodeId,
+    label: i32,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        r#"    <edge source="{}" target="{}"><data key="elabel">{label}</data></edge>"#,
+        escape(node_id(graph, from)),
+        escape(node_id(graph, to)),
+    )
+}
+
+fn write_footer<W: Write>(writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
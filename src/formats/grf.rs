@@ -0,0 +1,96 @@
+//! VF legacy `.grf` format, as read by vf3lib's `FastStreamARGLoader`.
+//!
+//! ```text
+//! <node count>
+//! <node> <label>        one line per node, in order
+//! <out-degree>          then, for every node in order,
+//! <node> <successor>    one line per outgoing edge
+//! ```
+//!
+//! Lines starting with `#` before the node count are comments. Edges carry
+//! no attributes. Undirected graphs list each edge once and must be read back
+//! with [`RunOptions::undirected`](crate::RunOptions::undirected) set.
+
+use std::io::{BufRead, Write};
+
+use super::{Tokens, parse_error};
+use crate::{Graph, NodeId, VF3Error};
+
+/// Read a `.grf` graph with the same validation as `FastStreamARGLoader`.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if reading fails and [`VF3Error::Parse`] for
+/// malformed input.
+pub fn read<R: BufRead>(reader: R, undirected: bool) -> Result<Graph, VF3Error> {
+    let mut tokens = Tokens::after_comments(reader)?;
+    let count = tokens.next_number::<usize>("node count")?;
+    let mut graph = if undirected {
+        Graph::undirected()
+    } else {
+        Graph::directed()
+    };
+    for node in 0..count {
+        let line = tokens.line();
+        if tokens.next_number::<usize>("node id")? != node {
+            return Err(parse_error(line, format!("expected node {node}")));
+        }
+        graph.add_node(tokens.next_number("node label")?);
+    }
+    for node in 0..count as NodeId {
+        let degree = tokens.next_number::<usize>("out-degree")?;
+        for _ in 0..degree {
+            let line = tokens.line();
+            let from = tokens.next_number::<NodeId>("edge source")?;
+            let to = tokens.next_number::<NodeId>("edge target")?;
+            if from != node || to as usize >= count || from == to {
+                return Err(parse_error(
+                    line,
+                    format!("invalid edge {from} {to} in the block of node {node}"),
+                ));
+            }
+            graph.add_edge(from, to);
+        }
+    }
+    Ok(graph)
+}
+
+/// Write a graph as `.grf`.
+///
+/// Undirected graphs list each edge once, from its lower-numbered endpoint.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::InvalidGraph`] if an edge has a non-zero label, since
+/// `.grf` edges are read without attributes, and [`VF3Error::Io`] if writing
+/// fails.
+pub fn write<W: Write>(mut writer: W, graph: &Graph) -> Result<(), VF3Error> {
+    let nodes = 0..graph.node_count() as NodeId;
+    if let Some(node) = nodes
+        .clone()
+        .find(|&n| graph.successor_labels(n).iter().any(|&l| l != 0))
+    {
+        return Err(VF3Error::InvalidGraph {
+            message: format!("node {node} has labelled edges, which .grf cannot store"),
+        });
+    }
+
+    writeln!(writer, "{}", graph.node_count())?;
+    for node in nodes.clone() {
+        writeln!(writer, "{node} {}", graph.node_label(node))?;
+    }
+    for node in nodes {
+        let successors = graph.successors(node);
+        let successors = if graph.is_directed() {
+            successors
+        } else {
+            // Adjacency lists are sorted, so the higher neighbours are a suffix.
+            &successors[successors.partition_point(|&s| s < node)..]
+        };
+        writeln!(writer, "{}", successors.len())?;
+        for succ in successors {
+            writeln!(writer, "{node} {succ}")?;
+        }
+    }
+    Ok(())
+}
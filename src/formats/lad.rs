@@ -0,0 +1,76 @@
+//! LAD format used by the LAD solver and the SIP benchmark suites.
+//!
+//! The first number is the node count; each node then lists an optional label,
+//! its out-degree and its successors (0-based), each optionally followed by an
+//! edge label.
+
+use std::io::BufRead;
+
+use super::{Tokens, parse_error};
+use crate::{Graph, NodeId, VF3Error};
+
+/// Options selecting the labelled variants of the LAD format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LadOptions {
+    /// Each node line starts with the node label (vertex-labelled LAD).
+    pub node_labels: bool,
+    /// Each successor is followed by the label of the edge leading to it.
+    pub edge_labels: bool,
+}
+
+/// Parse a LAD graph. Successor lists are read as directed edges; undirected
+/// benchmark graphs list every edge from both ends, and `undirected` forces
+/// the result to be undirected regardless.
+pub(crate) fn parse<R: BufRead>(
+    reader: R,
+    undirected: bool,
+    opts: &LadOptions,
+) -> Result<Graph, VF3Error> {
+    let mut tokens = Tokens::new(reader)?;
+    let count = tokens.next_number::<usize>("node count")?;
+
+    let mut graph = Graph::directed();
+    let mut edges = Vec::new();
+    for node in 0..count {
+        let label = if opts.node_labels {
+            tokens.next_number("node label")?
+        } else {
+            0
+        };
+        graph.add_node(label);
+        let degree = tokens.next_number::<usize>("degree")?;
+        for _ in 0..degree {
+            let line = tokens.line();
+            let succ = tokens.next_number::<NodeId>("successor")?;
+            if succ as usize >= count {
+                return Err(parse_error(
+                    line,
+                    format!("successor {succ} out of range for {count} nodes"),
+                ));
+            }
+            if succ as usize == node {
+                return Err(parse_error(line, format!("self-loop on node {node}")));
+            }
+            let label = if opts.edge_labels {
+                tokens.next_number("edge label")?
+            } else {
+                0
+            };
+            edges.push((node as NodeId, succ, label));
+        }
+    }
+    if let Some(line) = tokens.remaining() {
+        return Err(parse_error(line, "unexpected data after the last node"));
+    }
+
+    for (from, to, label) in edges {
+        graph.add_labeled_edge(from, to, label);
+    }
+    Ok(if undirected {
+        graph.to_undirected()
+    } else {
+        graph
+    })
+}
@@ -0,0 +1,194 @@
+//! Readers and writers for graph formats not handled by the vf3lib loaders.
+
+pub(crate) mod compression;
+pub mod csr;
+pub(crate) mod dimacs;
+pub mod dot;
+pub(crate) mod edge_list;
+pub mod graph6;
+pub mod graphml;
+pub mod grf;
+pub(crate) mod lad;
+pub mod ri;
+pub mod solutions;
+
+use std::{io::BufRead, path::Path};
+
+use self::compression::Compression;
+use crate::{Graph, GraphFormat, LabelDictionary, RunOptions, VF3Error};
+
+/// Load both graphs on the Rust side, or return `None` for uncompressed
+/// files the vf3lib loaders read and match themselves.
+pub(crate) fn load_pair(
+    pattern: &str,
+    target: &str,
+    opts: &RunOptions,
+) -> Result<Option<(Graph, Graph)>, VF3Error> {
+    let (pattern_undirected, target_undirected) =
+        (opts.pattern_is_undirected(), opts.target_is_undirected());
+    if opts.format == GraphFormat::VFLegacy
+        && pattern_undirected == target_undirected
+        && !opts.rust_search()
+        && !opts.distinct_occurrences
+        && !is_compressed(pattern, target)
+    {
+        return Ok(None);
+    }
+    // One dictionary for both files so equal string labels match.
+    let mut labels = LabelDictionary::new();
+    let pattern_opts = RunOptions {
+        undirected: pattern_undirected,
+        ..opts.clone()
+    };
+    let target_opts = RunOptions {
+        undirected: target_undirected,
+        ..opts.clone()
+    };
+    Ok(Some((
+        load_graph(Path::new(pattern), &pattern_opts, &mut labels)?,
+        load_graph(Path::new(target), &target_opts, &mut labels)?,
+    )))
+}
+
+/// Memory-map both graphs when they are binary CSR files that can be used as
+/// they are.
+pub(crate) fn map_pair(
+    pattern: &str,
+    target: &str,
+    opts: &RunOptions,
+) -> Result<Option<(csr::MappedGraph, csr::MappedGraph)>, VF3Error> {
+    if opts.format != GraphFormat::Csr || is_compressed(pattern, target) {
+        return Ok(None);
+    }
+    let (pattern, target) = (
+        csr::MappedGraph::open(pattern)?,
+        csr::MappedGraph::open(target)?,
+    );
+    if (opts.pattern_is_undirected() && pattern.is_directed())
+        || (opts.target_is_undirected() && target.is_directed())
+    {
+        // Forcing undirected input needs a modified copy.
+        return Ok(None);
+    }
+    Ok(Some((pattern, target)))
+}
+
+/// Whether either file needs decompressing, which the vf3lib loaders and
+/// memory mapping cannot do.
+fn is_compressed(pattern: &str, target: &str) -> bool {
+    compression::detect(Path::new(pattern)) != Compression::None
+        || compression::detect(Path::new(target)) != Compression::None
+}
+
+/// Load a single graph in any supported format on the Rust side.
+pub(crate) fn load_graph(
+    path: &Path,
+    opts: &RunOptions,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    read_graph(compression::open(path)?, opts, labels)
+}
+
+/// Read a single graph in any supported format from decompressed input.
+pub(crate) fn read_graph<R: BufRead>(
+    reader: R,
+    opts: &RunOptions,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    match opts.format {
+        GraphFormat::VFLegacy => grf::read(reader, opts.undirected),
+        GraphFormat::EdgeList => edge_list::parse(reader, opts.undirected, &opts.edge_list),
+        GraphFormat::GraphML => {
+            let graph = graphml::read(reader, &opts.graphml, labels)?;
+            Ok(force_undirected(graph, opts))
+        }
+        GraphFormat::Lad => lad::parse(reader, opts.undirected, &opts.lad),
+        GraphFormat::Dimacs => dimacs::parse(reader),
+        GraphFormat::Graph6 => Ok(force_undirected(graph6::read_single(reader)?, opts)),
+        GraphFormat::Gfu | GraphFormat::Gfd => {
+            let directed = opts.format == GraphFormat::Gfd && !opts.undirected;
+            ri::read_single(reader, directed, labels)
+        }
+        GraphFormat::Csr => Ok(force_undirected(csr::read(reader)?, opts)),
+    }
+}
+
+/// Apply [`RunOptions::undirected`] to a graph whose file chose its own
+/// directedness.
+fn force_undirected(graph: Graph, opts: &RunOptions) -> Graph {
+    if opts.undirected && graph.is_directed() {
+        graph.to_undirected()
+    } else {
+        graph
+    }
+}
+
+/// Whitespace-separated tokens with the line each one came from.
+struct Tokens {
+    tokens: std::vec::IntoIter<(usize, String)>,
+    last_line: usize,
+}
+
+impl Tokens {
+    fn new<R: BufRead>(reader: R) -> Result<Self, VF3Error> {
+        Self::read(reader, false)
+    }
+
+    /// Tokens after the `#` comment lines vf3lib's loaders skip at the start
+    /// of a file.
+    fn after_comments<R: BufRead>(reader: R) -> Result<Self, VF3Error> {
+        Self::read(reader, true)
+    }
+
+    fn read<R: BufRead>(reader: R, skip_comments: bool) -> Result<Self, VF3Error> {
+        let mut tokens = Vec::new();
+        let mut last_line = 1;
+        let mut header = skip_comments;
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            last_line = idx + 1;
+            header = header && line.starts_with('#');
+            if header {
+                continue;
+            }
+            tokens.extend(
+                line.split_whitespace()
+                    .map(|token| (idx + 1, token.to_owned())),
+            );
+        }
+        Ok(Self {
+            tokens: tokens.into_iter(),
+            last_line,
+        })
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .as_slice()
+            .first()
+            .map_or(self.last_line, |(line, _)| *line)
+    }
+
+    fn next_number<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, VF3Error> {
+        let Some((line, token)) = self.tokens.next() else {
+            return Err(parse_error(
+                self.last_line,
+                format!("unexpected end of file, expected {what}"),
+            ));
+        };
+        token
+            .parse()
+            .map_err(|_| parse_error(line, format!("invalid {what} {token:?}")))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        self.tokens.as_slice().first().map(|(line, _)| *line)
+    }
+}
+
+pub(super) fn parse_error(line: usize, message: impl Into<String>) -> VF3Error {
+    VF3Error::Parse {
+        line,
+        message: message.into(),
+    }
+}
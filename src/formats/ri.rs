@@ -0,0 +1,158 @@
+//! `.gfu`/`.gfd` formats used by the RI and RI-DS tools.
+//!
+//! A file holds one or more graphs, each written as:
+//!
+//! ```text
+//! #name
+//! <node count>
+//! <label of node 0>
+//! ...
+//! <edge count>
+//! <u> <v>
+//! ...
+//! ```
+//!
+//! Node ids are 0-based. `.gfu` graphs are undirected and `.gfd` graphs
+//! directed.
+
+use std::io::BufRead;
+
+use super::parse_error;
+use crate::{Graph, LabelDictionary, NodeId, VF3Error, labels::Labelled};
+
+/// A graph read from a multi-graph file, together with its `#name` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedGraph {
+    /// Name given on the graph's `#` header line.
+    pub name: String,
+    /// The graph itself.
+    pub graph: Graph,
+}
+
+/// Read every graph of a `.gfu` (`directed == false`) or `.gfd` file.
+///
+/// String node labels are interned in `labels`; read a pattern and its target
+/// with the same dictionary so that equal strings receive equal labels.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::Io`] if reading fails and [`VF3Error::Parse`] for
+/// malformed input.
+pub fn read<R: BufRead>(
+    reader: R,
+    directed: bool,
+    labels: &mut LabelDictionary,
+) -> Result<Vec<NamedGraph>, VF3Error> {
+    labels
+        .set_kind(Labelled::Node, false)
+        .map_err(|message| parse_error(1, message))?;
+    let mut lines = Lines::new(reader);
+    let mut graphs = Vec::new();
+    while let Some((line, header)) = lines.next()? {
+        let Some(name) = header.strip_prefix('#') else {
+            return Err(parse_error(line, "expected a `#name` graph header"));
+        };
+        let mut graph = if directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+
+        let count = lines.count("node count")?;
+        for _ in 0..count {
+            let (_, label) = lines.expect("node label")?;
+            graph.add_node(labels.intern(&label));
+        }
+
+        let edges = lines.count("edge count")?;
+        for _ in 0..edges {
+            let (line, edge) = lines.expect("edge")?;
+            let mut tokens = edge.split_whitespace();
+            let (Some(u), Some(v)) = (tokens.next(), tokens.next()) else {
+                return Err(parse_error(line, "expected two node ids"));
+            };
+            let u = parse_node(u, count, line)?;
+            let v = parse_node(v, count, line)?;
+            if u == v {
+                return Err(parse_error(line, format!("self-loop on node {u}")));
+            }
+            graph.add_edge(u, v);
+        }
+
+        graphs.push(NamedGraph {
+            name: name.trim().to_owned(),
+            graph,
+        });
+    }
+    Ok(graphs)
+}
+
+/// Read a file that must hold exactly one graph, for the path-based runners.
+pub(crate) fn read_single<R: BufRead>(
+    reader: R,
+    directed: bool,
+    labels: &mut LabelDictionary,
+) -> Result<Graph, VF3Error> {
+    let mut graphs = read(reader, directed, labels)?;
+    match graphs.len() {
+        1 => Ok(graphs.remove(0).graph),
+        0 => Err(parse_error(1, "file contains no graph")),
+        n => Err(parse_error(
+            1,
+            format!("file contains {n} graphs; read them with formats::ri::read and run each one"),
+        )),
+    }
+}
+
+/// Non-blank, trimmed lines with their line numbers.
+struct Lines<R> {
+    lines: std::io::Lines<R>,
+    line_no: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_no: 0,
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<(usize, String)>, VF3Error> {
+        for line in self.lines.by_ref() {
+            let line = line?;
+            self.line_no += 1;
+            let line = line.trim();
+            if !line.is_empty() {
+                return Ok(Some((self.line_no, line.to_owned())));
+            }
+        }
+        Ok(None)
+    }
+
+    fn expect(&mut self, what: &str) -> Result<(usize, String), VF3Error> {
+        self.next()?.ok_or_else(|| {
+            parse_error(
+                self.line_no,
+                format!("unexpected end of file, expected {what}"),
+            )
+        })
+    }
+
+    fn count(&mut self, what: &str) -> Result<usize, VF3Error> {
+        let (line, token) = self.expect(what)?;
+        token
+            .parse()
+            .map_err(|_| parse_error(line, format!("invalid {what} {token:?}")))
+    }
+}
+
+fn parse_node(token: &str, count: usize, line: usize) -> Result<NodeId, VF3Error> {
+    match token.parse::<usize>() {
+        Ok(id) if id < count => Ok(id as NodeId),
+        _ => Err(parse_error(
+            line,
+            format!("node {token:?} out of range for {count} nodes"),
+        )),
+    }
+}
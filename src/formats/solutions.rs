@@ -0,0 +1,166 @@
+//! Streaming solutions out as JSON Lines or CSV while the search runs.
+//!
+//! A [`SolutionWriter`] is fed by the `run_*_graphs_with` runners, so no
+//! mapping is kept in memory after it is written:
+//!
+//! ```no_run
+//! use std::{fs::File, io::BufWriter};
+//! use vf3lib_rs::{Graph, GraphFormat, RunOptions, SolutionFormat, SolutionWriter};
+//!
+//! let pattern = Graph::from_reader(File::open("pattern.grf")?, GraphFormat::VFLegacy)?;
+//! let target = Graph::from_reader(File::open("target.grf")?, GraphFormat::VFLegacy)?;
+//! let file = BufWriter::new(File::create("matches.csv")?);
+//! let mut out = SolutionWriter::new(file, SolutionFormat::Csv).with_labels(&pattern, &target);
+//! vf3lib_rs::run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+//!     out.visit(mapping)
+//! })?;
+//! out.finish()?;
+//! # Ok::<(), vf3lib_rs::VF3Error>(())
+//! ```
+
+use std::{
+    io::{self, Write},
+    ops::ControlFlow,
+};
+
+use crate::{Graph, NodeId};
+
+/// Row layout of a [`SolutionWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionFormat {
+    /// One JSON object per solution: `{"solution":0,"mapping":[[0,3],[1,4]]}`,
+    /// with the mapping as `[pattern, target]` pairs and, when labels are
+    /// written, `"labels"` as `[pattern label, target label]` pairs.
+    JsonLines,
+    /// A `solution,pattern,target` header, or
+    /// `solution,pattern,target,pattern_label,target_label` with labels,
+    /// then one row per mapped pattern node.
+    Csv,
+}
+
+/// Writes each solution as it is handed over.
+///
+/// Solutions are numbered from `0` in the order they arrive. Nothing is
+/// flushed until [`SolutionWriter::finish`], so wrap files in a
+/// [`BufWriter`](std::io::BufWriter).
+pub struct SolutionWriter<'g, W: Write> {
+    writer: W,
+    format: SolutionFormat,
+    labels: Option<(&'g Graph, &'g Graph)>,
+    count: u64,
+    header: bool,
+    error: Option<io::Error>,
+}
+
+impl<'g, W: Write> SolutionWriter<'g, W> {
+    /// Write solutions to `writer` as node ids only.
+    pub fn new(writer: W, format: SolutionFormat) -> Self {
+        Self {
+            writer,
+            format,
+            labels: None,
+            count: 0,
+            header: false,
+            error: None,
+        }
+    }
+
+    /// Also write the label of each pattern node and of its target node,
+    /// taken from these graphs.
+    pub fn with_labels(mut self, pattern: &'g Graph, target: &'g Graph) -> Self {
+        self.labels = Some((pattern, target));
+        self
+    }
+
+    /// Number of solutions written so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Write one solution, given as `(pattern, target)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the underlying writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if labels are written and a node is not in its graph.
+    pub fn write(&mut self, mapping: &[(NodeId, NodeId)]) -> io::Result<()> {
+        let solution = self.count;
+        match self.format {
+            SolutionFormat::JsonLines => {
+                write!(self.writer, r#"{{"solution":{solution},"mapping":["#)?;
+                for (idx, &(p, t)) in mapping.iter().enumerate() {
+                    let sep = if idx == 0 { "" } else { "," };
+                    write!(self.writer, "{sep}[{p},{t}]")?;
+                }
+                write!(self.writer, "]")?;
+                if let Some((pattern, target)) = self.labels {
+                    write!(self.writer, r#","labels":["#)?;
+                    for (idx, &(p, t)) in mapping.iter().enumerate() {
+                        let sep = if idx == 0 { "" } else { "," };
+                        let (p, t) = (pattern.node_label(p), target.node_label(t));
+                        write!(self.writer, "{sep}[{p},{t}]")?;
+                    }
+                    write!(self.writer, "]")?;
+                }
+                writeln!(self.writer, "}}")?;
+            }
+            SolutionFormat::Csv => {
+                self.write_header()?;
+                for &(p, t) in mapping {
+                    write!(self.writer, "{solution},{p},{t}")?;
+                    if let Some((pattern, target)) = self.labels {
+                        let (p, t) = (pattern.node_label(p), target.node_label(t));
+                        write!(self.writer, ",{p},{t}")?;
+                    }
+                    writeln!(self.writer)?;
+                }
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// [`SolutionWriter::write`] as a solution callback: a write error is
+    /// kept for [`SolutionWriter::finish`] and stops the search.
+    pub fn visit(&mut self, mapping: &[(NodeId, NodeId)]) -> ControlFlow<()> {
+        match self.write(mapping) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(err) => {
+                self.error = Some(err);
+                ControlFlow::Break(())
+            }
+        }
+    }
+
+    /// Flush the output and return the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that stopped [`SolutionWriter::visit`], or any
+    /// error from writing the CSV header of an empty result or flushing.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if self.format == SolutionFormat::Csv {
+            self.write_header()?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if !self.header {
+            self.header = true;
+            write!(self.writer, "solution,pattern,target")?;
+            if self.labels.is_some() {
+                write!(self.writer, ",pattern_label,target_label")?;
+            }
+            writeln!(self.writer)?;
+        }
+        Ok(())
+    }
+}
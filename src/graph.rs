@@ -0,0 +1,357 @@
+//! In-memory graph representation handed to the matching algorithms.
+
+use std::{
+    borrow::Cow,
+    collections::TryReserveError,
+    io::{BufReader, Read},
+};
+
+pub(crate) use self::sealed::{Csr, Sealed};
+use crate::{GraphFormat, LabelDictionary, MixedEdges, RunOptions, VF3Error, formats};
+
+/// Index of a node within a [`Graph`].
+pub type NodeId = u32;
+
+/// Node- and edge-labelled graph with sorted, de-duplicated adjacency lists.
+///
+/// Undirected graphs store every edge in both directions, mirroring how the
+/// vf3lib loaders expand undirected input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::serde_support::GraphData",
+        try_from = "crate::serde_support::GraphData"
+    )
+)]
+pub struct Graph {
+    directed: bool,
+    labels: Vec<i32>,
+    adjacency: Vec<Vec<NodeId>>,
+    edge_labels: Vec<Vec<i32>>,
+    edge_count: usize,
+    names: Option<Vec<String>>,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::directed()
+    }
+}
+
+impl Graph {
+    /// Create an empty directed graph.
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            labels: Vec::new(),
+            adjacency: Vec::new(),
+            edge_labels: Vec::new(),
+            edge_count: 0,
+            names: None,
+        }
+    }
+
+    /// Create an empty undirected graph.
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    /// Read a graph in `format` from any reader, such as a network stream or
+    /// an archive entry.
+    ///
+    /// Format options take their defaults; see [`Graph::from_reader_with`].
+    /// Input compressed with gzip or zstd is decompressed when the matching
+    /// feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VF3Error::Io`] if reading fails, [`VF3Error::Parse`] for
+    /// malformed input and [`VF3Error::UnsupportedFormat`] for compressed input
+    /// without the matching feature.
+    pub fn from_reader(reader: impl Read, format: GraphFormat) -> Result<Self, VF3Error> {
+        let opts = RunOptions {
+            format,
+            ..Default::default()
+        };
+        Self::from_reader_with(reader, &opts, &mut LabelDictionary::new())
+    }
+
+    /// Read a graph in `format` from a byte buffer.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Graph::from_reader`].
+    pub fn from_bytes(bytes: &[u8], format: GraphFormat) -> Result<Self, VF3Error> {
+        Self::from_reader(bytes, format)
+    }
+
+    /// Read a graph as a path-based run would, honouring `opts.format`, the
+    /// format-specific options and [`RunOptions::undirected`].
+    ///
+    /// String labels are interned into `labels`; read the pattern and the
+    /// target with the same dictionary so that equal labels match.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Graph::from_reader`].
+    pub fn from_reader_with(
+        reader: impl Read,
+        opts: &RunOptions,
+        labels: &mut LabelDictionary,
+    ) -> Result<Self, VF3Error> {
+        let reader = formats::compression::wrap(BufReader::new(reader))?;
+        formats::read_graph(reader, opts, labels)
+    }
+
+    /// Whether edges are directed.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Number of edges; an undirected edge counts once.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Add a node with the given label and return its id.
+    pub fn add_node(&mut self, label: i32) -> NodeId {
+        let id = self.labels.len() as NodeId;
+        self.labels.push(label);
+        self.adjacency.push(Vec::new());
+        self.edge_labels.push(Vec::new());
+        if let Some(names) = &mut self.names {
+            names.push(id.to_string());
+        }
+        id
+    }
+
+    /// Reserve room for `additional` nodes, so that a file declaring more
+    /// nodes than can be allocated fails to read instead of aborting.
+    pub(crate) fn try_reserve_nodes(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.labels.try_reserve(additional)?;
+        self.adjacency.try_reserve(additional)?;
+        self.edge_labels.try_reserve(additional)
+    }
+
+    /// Add an edge labelled `0` and return `true` if it was not already present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either endpoint is out of range or if `from == to`, since the
+    /// matching algorithms do not support self-loops.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        self.add_labeled_edge(from, to, 0)
+    }
+
+    /// Add a labelled edge and return `true` if it was not already present.
+    ///
+    /// An existing edge keeps its original label.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Graph::add_edge`].
+    pub fn add_labeled_edge(&mut self, from: NodeId, to: NodeId, label: i32) -> bool {
+        assert!(
+            (from as usize) < self.node_count() && (to as usize) < self.node_count(),
+            "edge ({from}, {to}) out of range for graph with {} nodes",
+            self.node_count()
+        );
+        assert_ne!(from, to, "self-loops are not supported");
+        let inserted = self.insert_arc(from, to, label);
+        if !self.directed {
+            self.insert_arc(to, from, label);
+        }
+        if inserted {
+            self.edge_count += 1;
+        }
+        inserted
+    }
+
+    /// Label of a node.
+    pub fn node_label(&self, node: NodeId) -> i32 {
+        self.labels[node as usize]
+    }
+
+    /// Change the label of an existing node.
+    pub fn set_node_label(&mut self, node: NodeId, label: i32) {
+        self.labels[node as usize] = label;
+    }
+
+    /// Nodes reachable through an outgoing edge, in ascending order.
+    ///
+    /// For undirected graphs these are all neighbours of `node`.
+    pub fn successors(&self, node: NodeId) -> &[NodeId] {
+        &self.adjacency[node as usize]
+    }
+
+    /// Labels of the edges returned by [`Graph::successors`], in the same order.
+    pub fn successor_labels(&self, node: NodeId) -> &[i32] {
+        &self.edge_labels[node as usize]
+    }
+
+    /// Label of the edge `from -> to`, if it exists.
+    pub fn edge_label(&self, from: NodeId, to: NodeId) -> Option<i32> {
+        let succ = self.adjacency.get(from as usize)?;
+        let pos = succ.binary_search(&to).ok()?;
+        Some(self.edge_labels[from as usize][pos])
+    }
+
+    /// Whether the edge `from -> to` exists.
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.adjacency
+            .get(from as usize)
+            .is_some_and(|succ| succ.binary_search(&to).is_ok())
+    }
+
+    /// External name of a node, if the graph carries names.
+    pub fn node_name(&self, node: NodeId) -> Option<&str> {
+        self.names
+            .as_ref()
+            .and_then(|names| names.get(node as usize))
+            .map(String::as_str)
+    }
+
+    /// External names of all nodes, indexed by [`NodeId`].
+    pub fn node_names(&self) -> Option<&[String]> {
+        self.names.as_deref()
+    }
+
+    /// Set the external name of a node.
+    ///
+    /// The first call on an unnamed graph names every other node after its id.
+    pub fn set_node_name(&mut self, node: NodeId, name: impl Into<String>) {
+        let count = self.node_count();
+        let names = self
+            .names
+            .get_or_insert_with(|| (0..count).map(|id| id.to_string()).collect());
+        names[node as usize] = name.into();
+    }
+
+    /// Copy of this graph with every edge also present in the reverse direction.
+    ///
+    /// When both directions of a directed edge exist with different labels, the
+    /// label of the edge leaving the lower-numbered node wins.
+    pub fn to_undirected(&self) -> Graph {
+        let mut graph = Graph {
+            directed: false,
+            labels: self.labels.clone(),
+            adjacency: vec![Vec::new(); self.node_count()],
+            edge_labels: vec![Vec::new(); self.node_count()],
+            edge_count: 0,
+            names: self.names.clone(),
+        };
+        for (from, succ) in self.adjacency.iter().enumerate() {
+            for (&to, &label) in succ.iter().zip(&self.edge_labels[from]) {
+                graph.add_labeled_edge(from as NodeId, to, label);
+            }
+        }
+        graph
+    }
+
+    fn insert_arc(&mut self, from: NodeId, to: NodeId, label: i32) -> bool {
+        let list = &mut self.adjacency[from as usize];
+        match list.binary_search(&to) {
+            Ok(_) => false,
+            Err(pos) => {
+                list.insert(pos, to);
+                self.edge_labels[from as usize].insert(pos, label);
+                true
+            }
+        }
+    }
+
+    /// Compressed sparse row form of the graph.
+    pub(crate) fn to_csr(&self) -> Csr<'static> {
+        let mut offsets = Vec::with_capacity(self.adjacency.len() + 1);
+        let mut targets = Vec::new();
+        let mut edge_labels = Vec::new();
+        offsets.push(0);
+        for (succ, labels) in self.adjacency.iter().zip(&self.edge_labels) {
+            targets.extend_from_slice(succ);
+            edge_labels.extend_from_slice(labels);
+            offsets.push(targets.len() as u32);
+        }
+        Csr {
+            directed: self.directed,
+            node_labels: Cow::Owned(self.labels.clone()),
+            offsets: Cow::Owned(offsets),
+            targets: Cow::Owned(targets),
+            edge_labels: Cow::Owned(edge_labels),
+        }
+    }
+}
+
+/// Reconcile a pattern and a target of different directedness as described
+/// by [`MixedEdges`].
+pub(crate) fn orient<'p, 't>(
+    pattern: Csr<'p>,
+    target: Csr<'t>,
+    mixed_edges: MixedEdges,
+) -> (Csr<'p>, Csr<'t>) {
+    match (pattern.directed, target.directed) {
+        (true, false) => (pattern.to_graph().to_undirected().to_csr(), target),
+        (false, true) if mixed_edges == MixedEdges::Either => {
+            (pattern, target.to_graph().to_undirected().to_csr())
+        }
+        _ => (pattern, target),
+    }
+}
+
+/// Graph representations accepted by [`run_vf3_graphs`](crate::run_vf3_graphs)
+/// and friends: [`Graph`] and
+/// [`MappedGraph`](crate::formats::csr::MappedGraph).
+pub trait GraphInput: Sealed {}
+
+impl<T: Sealed> GraphInput for T {}
+
+impl Sealed for Graph {
+    fn csr(&self) -> Csr<'_> {
+        self.to_csr()
+    }
+
+    fn names(&self) -> Option<&[String]> {
+        self.node_names()
+    }
+}
+
+mod sealed {
+    use std::borrow::Cow;
+
+    /// Compressed sparse row arrays, owned or borrowed from a mapped file.
+    pub struct Csr<'a> {
+        pub directed: bool,
+        pub node_labels: Cow<'a, [i32]>,
+        /// Start of each node's successors in `targets`, plus a final end offset.
+        pub offsets: Cow<'a, [u32]>,
+        /// Concatenated, sorted successor lists.
+        pub targets: Cow<'a, [u32]>,
+        /// Label of each edge, aligned with `targets`.
+        pub edge_labels: Cow<'a, [i32]>,
+    }
+
+    impl Csr<'_> {
+        pub fn ffi(&self) -> crate::vf3ffi::GraphData<'_> {
+            crate::vf3ffi::GraphData {
+                node_labels: &self.node_labels,
+                offsets: &self.offsets,
+                targets: &self.targets,
+                edge_labels: &self.edge_labels,
+            }
+        }
+    }
+
+    pub trait Sealed {
+        fn csr(&self) -> Csr<'_>;
+        fn names(&self) -> Option<&[String]>;
+    }
+}
@@ -0,0 +1,473 @@
+//! Backtracking search for the matchings the injective, exact vf3lib state
+//! space cannot express: pattern nodes sharing a target node, and matches
+//! tolerating missing edges or mismatched labels.
+
+use std::time::Instant;
+
+use crate::{
+    Mapping, NodeId, ResultData, RunOptions, Tolerance, VF3Error, Violations, graph::Csr,
+    occurrences, visit::SolutionSink,
+};
+
+const UNMAPPED: NodeId = NodeId::MAX;
+
+/// Find all matchings allowed by [`RunOptions::homomorphism`],
+/// [`RunOptions::may_share`] and [`RunOptions::tolerance`], repeating the
+/// search like the vf3lib engine until `opts.repetition_time_limit` has
+/// elapsed and handing each solution to `sink`.
+pub(crate) fn run(
+    pattern: &Csr<'_>,
+    target: &Csr<'_>,
+    opts: &RunOptions,
+    sink: &mut SolutionSink<'_>,
+) -> Result<ResultData, VF3Error> {
+    let count = pattern.node_labels.len();
+    let mut shared = vec![opts.homomorphism; count];
+    for &node in &opts.may_share {
+        *shared
+            .get_mut(node as usize)
+            .ok_or_else(|| VF3Error::InvalidGraph {
+                message: format!("may_share node {node} is not in the {count}-node pattern"),
+            })? = true;
+    }
+
+    let undirected = !pattern.directed;
+    let pattern = Adjacency::new(pattern);
+    let target = Adjacency::new(target);
+    let mut order = order(&pattern);
+    if opts.tolerance.edges > 0 {
+        // A node's image need not be adjacent to its parent's image.
+        for (_, parent) in &mut order {
+            *parent = None;
+        }
+    }
+    let search = Search {
+        order,
+        pattern: &pattern,
+        target: &target,
+        undirected,
+        shared,
+        induced: !opts.edge_induced && !opts.homomorphism,
+        tolerance: opts.tolerance,
+        first_only: opts.first_only,
+        store_solutions: opts.store_solutions || opts.distinct_occurrences,
+    };
+
+    let (mut total, mut first, mut rep) = (0.0, 0.0, 0);
+    let (solutions, mappings, violations) = loop {
+        rep += 1;
+        let mut state = State::new(count, target.len(), sink);
+        search.extend(&mut state, 0);
+        let elapsed = state.start.elapsed().as_secs_f64();
+        total += elapsed;
+        first += state.first.unwrap_or(elapsed);
+        if total >= f64::from(opts.repetition_time_limit) {
+            break (state.solutions, state.mappings, state.violations);
+        }
+    };
+
+    let time_all = total / f64::from(rep);
+    let mut res = ResultData {
+        solutions,
+        time_first: if opts.first_only {
+            time_all
+        } else {
+            first / f64::from(rep)
+        },
+        time_all,
+        mappings,
+        violations,
+        pattern_names: None,
+        target_names: None,
+    };
+    if opts.distinct_occurrences {
+        occurrences::dedup(&mut res, search.pattern.csr);
+        res.solutions = res.mappings.len() as u64;
+        if !opts.store_solutions {
+            res.mappings.clear();
+            res.violations.clear();
+        }
+    }
+    Ok(res)
+}
+
+/// Successor lists of a CSR graph together with its predecessor lists.
+pub(crate) struct Adjacency<'c> {
+    csr: &'c Csr<'c>,
+    pred_offsets: Vec<u32>,
+    pred_sources: Vec<NodeId>,
+    pred_labels: Vec<i32>,
+}
+
+impl<'c> Adjacency<'c> {
+    pub(crate) fn new(csr: &'c Csr<'c>) -> Self {
+        let count = csr.node_labels.len();
+        let mut pred_offsets = vec![0u32; count + 1];
+        for &to in csr.targets.iter() {
+            pred_offsets[to as usize + 1] += 1;
+        }
+        for node in 0..count {
+            pred_offsets[node + 1] += pred_offsets[node];
+        }
+        let mut next = pred_offsets.clone();
+        let mut pred_sources = vec![0; csr.targets.len()];
+        let mut pred_labels = vec![0; csr.targets.len()];
+        for from in 0..count {
+            let range = csr.offsets[from] as usize..csr.offsets[from + 1] as usize;
+            for (&to, &label) in csr.targets[range.clone()]
+                .iter()
+                .zip(&csr.edge_labels[range])
+            {
+                let slot = &mut next[to as usize];
+                pred_sources[*slot as usize] = from as NodeId;
+                pred_labels[*slot as usize] = label;
+                *slot += 1;
+            }
+        }
+        Self {
+            csr,
+            pred_offsets,
+            pred_sources,
+            pred_labels,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.csr.node_labels.len()
+    }
+
+    pub(crate) fn label(&self, node: NodeId) -> i32 {
+        self.csr.node_labels[node as usize]
+    }
+
+    pub(crate) fn successors(&self, node: NodeId) -> (&[NodeId], &[i32]) {
+        let range =
+            self.csr.offsets[node as usize] as usize..self.csr.offsets[node as usize + 1] as usize;
+        (
+            &self.csr.targets[range.clone()],
+            &self.csr.edge_labels[range],
+        )
+    }
+
+    pub(crate) fn predecessors(&self, node: NodeId) -> (&[NodeId], &[i32]) {
+        let range = self.pred_offsets[node as usize] as usize
+            ..self.pred_offsets[node as usize + 1] as usize;
+        (&self.pred_sources[range.clone()], &self.pred_labels[range])
+    }
+
+    /// Number of arcs entering or leaving `node`.
+    pub(crate) fn degree(&self, node: NodeId) -> usize {
+        self.successors(node).0.len() + self.predecessors(node).0.len()
+    }
+
+    /// Label of the arc `from -> to`, if it exists.
+    pub(crate) fn arc(&self, from: NodeId, to: NodeId) -> Option<i32> {
+        let (succ, labels) = self.successors(from);
+        succ.binary_search(&to).ok().map(|idx| labels[idx])
+    }
+}
+
+/// A pattern node in search order, with an earlier neighbour whose image
+/// narrows the candidates: `(parent, true)` for an arc `parent -> node` and
+/// `(parent, false)` for an arc `node -> parent`.
+pub(crate) type Step = (NodeId, Option<(NodeId, bool)>);
+
+/// Order pattern nodes so that each one is as connected as possible to the
+/// nodes before it, starting from the highest degree.
+pub(crate) fn order(pattern: &Adjacency<'_>) -> Vec<Step> {
+    let count = pattern.len() as NodeId;
+    let mut placed = vec![false; count as usize];
+    let mut links = vec![0usize; count as usize];
+    let mut steps = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let node = (0..count)
+            .filter(|&n| !placed[n as usize])
+            .max_by_key(|&n| (links[n as usize], pattern.degree(n), std::cmp::Reverse(n)))
+            .expect("an unplaced node remains");
+        let (succ, _) = pattern.successors(node);
+        let (pred, _) = pattern.predecessors(node);
+        let parent = pred
+            .iter()
+            .find(|&&p| placed[p as usize])
+            .map(|&p| (p, true))
+            .or_else(|| {
+                succ.iter()
+                    .find(|&&p| placed[p as usize])
+                    .map(|&p| (p, false))
+            });
+        placed[node as usize] = true;
+        for &n in succ.iter().chain(pred) {
+            links[n as usize] += 1;
+        }
+        steps.push((node, parent));
+    }
+    steps
+}
+
+struct Search<'s, 'c> {
+    order: Vec<Step>,
+    pattern: &'s Adjacency<'c>,
+    target: &'s Adjacency<'c>,
+    /// The pattern stores each edge as two arcs that count as one edge.
+    undirected: bool,
+    /// Pattern nodes allowed to share their target node.
+    shared: Vec<bool>,
+    /// Non-adjacent pattern nodes with distinct images must map to
+    /// non-adjacent target nodes.
+    induced: bool,
+    tolerance: Tolerance,
+    first_only: bool,
+    store_solutions: bool,
+}
+
+/// Edge and label errors of a partial mapping.
+#[derive(Debug, Clone, Copy, Default)]
+struct Errors {
+    edges: usize,
+    labels: usize,
+}
+
+/// How a pattern edge fails to carry over to the target.
+enum EdgeError {
+    Missing,
+    Label,
+}
+
+struct State<'s, 'v> {
+    mapping: Vec<NodeId>,
+    /// Number of pattern nodes mapped to each target node.
+    hosted: Vec<u32>,
+    /// Target nodes hosting a pattern node that may not share.
+    exclusive: Vec<bool>,
+    errors: Errors,
+    solutions: u64,
+    mappings: Vec<Mapping>,
+    violations: Vec<Violations>,
+    start: Instant,
+    first: Option<f64>,
+    done: bool,
+    sink: &'s mut SolutionSink<'v>,
+}
+
+impl<'s, 'v> State<'s, 'v> {
+    fn new(pattern: usize, target: usize, sink: &'s mut SolutionSink<'v>) -> Self {
+        Self {
+            mapping: vec![UNMAPPED; pattern],
+            hosted: vec![0; target],
+            exclusive: vec![false; target],
+            errors: Errors::default(),
+            solutions: 0,
+            mappings: Vec::new(),
+            violations: Vec::new(),
+            start: Instant::now(),
+            first: None,
+            done: false,
+            sink,
+        }
+    }
+}
+
+impl Search<'_, '_> {
+    fn extend(&self, state: &mut State, depth: usize) {
+        let Some(&(node, parent)) = self.order.get(depth) else {
+            self.record(state);
+            return;
+        };
+        let candidates = match parent {
+            Some((parent, true)) => self.target.successors(state.mapping[parent as usize]).0,
+            Some((parent, false)) => self.target.predecessors(state.mapping[parent as usize]).0,
+            None => {
+                for image in 0..self.target.len() as NodeId {
+                    if self.try_map(state, depth, node, image) {
+                        return;
+                    }
+                }
+                return;
+            }
+        };
+        for &image in candidates {
+            if self.try_map(state, depth, node, image) {
+                return;
+            }
+        }
+    }
+
+    /// Map `node` to `image` if feasible and search on; returns `true` once
+    /// the search is done.
+    fn try_map(&self, state: &mut State, depth: usize, node: NodeId, image: NodeId) -> bool {
+        let Some(errors) = self.errors(state, depth, node, image) else {
+            return false;
+        };
+        let shared = self.shared[node as usize];
+        let before = state.errors;
+        state.errors = errors;
+        state.mapping[node as usize] = image;
+        state.hosted[image as usize] += 1;
+        state.exclusive[image as usize] |= !shared;
+        self.extend(state, depth + 1);
+        state.mapping[node as usize] = UNMAPPED;
+        state.hosted[image as usize] -= 1;
+        if !shared {
+            state.exclusive[image as usize] = false;
+        }
+        state.errors = before;
+        state.done
+    }
+
+    /// Errors of the mapping extended with `node -> image`, or `None` if
+    /// that is not allowed or exceeds the tolerance.
+    fn errors(&self, state: &State, depth: usize, node: NodeId, image: NodeId) -> Option<Errors> {
+        let sharing_ok = if self.shared[node as usize] {
+            !state.exclusive[image as usize]
+        } else {
+            state.hosted[image as usize] == 0
+        };
+        if !sharing_ok {
+            return None;
+        }
+
+        let mut errors = state.errors;
+        let mut add = |error: Option<EdgeError>| {
+            match error {
+                Some(EdgeError::Missing) => errors.edges += 1,
+                Some(EdgeError::Label) => errors.labels += 1,
+                None => return true,
+            }
+            errors.edges <= self.tolerance.edges && errors.labels <= self.tolerance.labels
+        };
+        if self.pattern.label(node) != self.target.label(image) && !add(Some(EdgeError::Label)) {
+            return None;
+        }
+
+        let mapped = |n: NodeId| Some(state.mapping[n as usize]).filter(|&m| m != UNMAPPED);
+        let (succ, succ_labels) = self.pattern.successors(node);
+        for (&to, &label) in succ.iter().zip(succ_labels) {
+            if let Some(to) = mapped(to)
+                && !add(self.edge_error(image, to, label))
+            {
+                return None;
+            }
+        }
+        if !self.undirected {
+            let (pred, pred_labels) = self.pattern.predecessors(node);
+            for (&from, &label) in pred.iter().zip(pred_labels) {
+                if let Some(from) = mapped(from)
+                    && !add(self.edge_error(from, image, label))
+                {
+                    return None;
+                }
+            }
+        }
+
+        if self.induced {
+            for &(other, _) in &self.order[..depth] {
+                let other_image = state.mapping[other as usize];
+                if other_image == image {
+                    continue;
+                }
+                let extra = self.extra_edges(node, other, image, other_image);
+                for _ in 0..extra {
+                    if !add(Some(EdgeError::Missing)) {
+                        return None;
+                    }
+                }
+            }
+        }
+        Some(errors)
+    }
+
+    /// How the pattern edge `from -> to` labelled `label` fails to map onto
+    /// the images `from` and `to`, in both directions for an undirected
+    /// pattern.
+    fn edge_error(&self, from: NodeId, to: NodeId, label: i32) -> Option<EdgeError> {
+        let forward = self.target.arc(from, to);
+        let backward = if self.undirected {
+            self.target.arc(to, from)
+        } else {
+            forward
+        };
+        match (forward, backward) {
+            (Some(a), Some(b)) if a == label && b == label => None,
+            (Some(_), Some(_)) => Some(EdgeError::Label),
+            _ => Some(EdgeError::Missing),
+        }
+    }
+
+    /// Target edges between the distinct images of pattern nodes `a` and
+    /// `b` that the pattern lacks, at most one for an undirected pattern.
+    fn extra_edges(&self, a: NodeId, b: NodeId, image_a: NodeId, image_b: NodeId) -> usize {
+        let forward =
+            self.pattern.arc(a, b).is_none() && self.target.arc(image_a, image_b).is_some();
+        let backward =
+            self.pattern.arc(b, a).is_none() && self.target.arc(image_b, image_a).is_some();
+        if self.undirected {
+            usize::from(forward || backward)
+        } else {
+            usize::from(forward) + usize::from(backward)
+        }
+    }
+
+    /// Pattern edges and labels the complete `mapping` violates.
+    fn violations(&self, mapping: &[NodeId]) -> Violations {
+        let mut violations = Violations::default();
+        for from in 0..self.pattern.len() as NodeId {
+            let image = mapping[from as usize];
+            if self.pattern.label(from) != self.target.label(image) {
+                violations.node_labels.push(from);
+            }
+            let (succ, labels) = self.pattern.successors(from);
+            for (&to, &label) in succ.iter().zip(labels) {
+                if self.undirected && to < from {
+                    continue;
+                }
+                match self.edge_error(image, mapping[to as usize], label) {
+                    Some(EdgeError::Missing) => violations.missing_edges.push((from, to)),
+                    Some(EdgeError::Label) => violations.edge_labels.push((from, to)),
+                    None => {}
+                }
+            }
+        }
+        if self.induced {
+            for a in 0..self.pattern.len() as NodeId {
+                for b in 0..self.pattern.len() as NodeId {
+                    let (image_a, image_b) = (mapping[a as usize], mapping[b as usize]);
+                    if a == b || image_a == image_b || (self.undirected && b < a) {
+                        continue;
+                    }
+                    let extra = if self.undirected {
+                        self.extra_edges(a, b, image_a, image_b) > 0
+                    } else {
+                        self.pattern.arc(a, b).is_none()
+                            && self.target.arc(image_a, image_b).is_some()
+                    };
+                    if extra {
+                        violations.extra_edges.push((a, b));
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    fn record(&self, state: &mut State) {
+        state.solutions += 1;
+        if state.first.is_none() {
+            state.first = Some(state.start.elapsed().as_secs_f64());
+        }
+        if self.store_solutions {
+            state.mappings.push(
+                state
+                    .mapping
+                    .iter()
+                    .enumerate()
+                    .map(|(p, &t)| (p as NodeId, t))
+                    .collect(),
+            );
+            if self.tolerance != Tolerance::default() {
+                state.violations.push(self.violations(&state.mapping));
+            }
+        }
+        let stop = state.sink.visit(&state.mapping);
+        state.done = self.first_only || stop;
+    }
+}
@@ -0,0 +1,106 @@
+//! Mapping between textual labels and the integer labels used by the matcher.
+
+use std::collections::HashMap;
+
+/// Interns string labels as integer node/edge classes.
+///
+/// The matcher compares labels as integers, the same way vf3lib's
+/// `NodeClassifier` groups integer attributes into classes. A pattern and its
+/// target must be read with the same dictionary so that equal strings receive
+/// equal labels. Strings are numbered from `1`, leaving `0` for elements
+/// without a label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::serde_support::LabelDictionaryData",
+        try_from = "crate::serde_support::LabelDictionaryData"
+    )
+)]
+pub struct LabelDictionary {
+    ids: HashMap<String, i32>,
+    names: Vec<String>,
+    /// Whether the node and edge labels read through this dictionary were
+    /// integers taken as written (`Some(true)`) or interned strings.
+    kinds: [Option<bool>; 2],
+}
+
+/// Elements whose labels are read through a [`LabelDictionary`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Labelled {
+    Node,
+    Edge,
+}
+
+impl LabelDictionary {
+    /// Create an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Integer label for `name`, assigning the next free one on first use.
+    pub fn intern(&mut self, name: &str) -> i32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as i32 + 1;
+        self.ids.insert(name.to_owned(), id);
+        self.names.push(name.to_owned());
+        id
+    }
+
+    /// Integer label previously assigned to `name`.
+    pub fn get(&self, name: &str) -> Option<i32> {
+        self.ids.get(name).copied()
+    }
+
+    /// String that was interned as `label`.
+    pub fn name(&self, label: i32) -> Option<&str> {
+        usize::try_from(label)
+            .ok()
+            .and_then(|label| self.names.get(label.checked_sub(1)?))
+            .map(String::as_str)
+    }
+
+    /// Number of distinct labels.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether no label has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Whether `labelled` elements read through this dictionary took integer
+    /// labels as written (`Some(true)`) or interned strings.
+    #[cfg(feature = "serde")]
+    pub(crate) fn kind(&self, labelled: Labelled) -> Option<bool> {
+        self.kinds[labelled as usize]
+    }
+
+    /// Record whether `labelled` elements take integer labels as written or
+    /// interned strings, failing if the other kind was recorded before: the
+    /// two would share one range of label values.
+    pub(crate) fn set_kind(&mut self, labelled: Labelled, integer: bool) -> Result<(), String> {
+        let kind = &mut self.kinds[labelled as usize];
+        if kind.is_some_and(|previous| previous != integer) {
+            let element = match labelled {
+                Labelled::Node => "node",
+                Labelled::Edge => "edge",
+            };
+            let (this, other) = if integer {
+                ("integer", "string")
+            } else {
+                ("string", "integer")
+            };
+            return Err(format!(
+                "{this} {element} labels cannot be compared with the {other} {element} labels \
+                 read before"
+            ));
+        }
+        *kind = Some(integer);
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 //! Rust bindings to VF3/VF3L/VF3P subgraph isomorphism algorithms via CXX.
 //!
 //! This crate provides efficient subgraph isomorphism detection using the VF3 family of algorithms.
-//! The underlying implementation is a C++11 library from MIVIA Lab.
+//! The underlying implementation is a C++11 library from MIVIA Lab, built with the default `cpp`
+//! feature. Without it, a pure-Rust port of VF3 and VF3L runs behind the same API and finds the
+//! same solutions.
 //!
 //! # Examples
 //!
@@ -19,9 +21,62 @@
 //! let result = run_vf3("pattern.grf", "target.grf", opts)?;
 //! # Ok::<(), vf3lib_rs::VF3Error>(())
 //! ```
+//!
+//! Edge lists with string node ids, reporting mappings by name:
+//! ```no_run
+//! use vf3lib_rs::{run_vf3, EdgeListOptions, GraphFormat, RunOptions};
+//! let opts = RunOptions {
+//!     format: GraphFormat::EdgeList,
+//!     edge_list: EdgeListOptions { string_ids: true, ..Default::default() },
+//!     store_solutions: true,
+//!     ..Default::default()
+//! };
+//! let result = run_vf3("pattern.edges", "target.edges", opts)?;
+//! for mapping in result.named_mappings().unwrap_or_default() {
+//!     println!("{mapping:?}");
+//! }
+//! # Ok::<(), vf3lib_rs::VF3Error>(())
+//! ```
+
+mod canonical;
+pub mod formats;
+mod graph;
+mod homomorphism;
+mod labels;
+mod mcs;
+mod occurrences;
+#[cfg(feature = "petgraph")]
+mod petgraph_support;
+mod region;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod symmetry;
+mod visit;
+
+use std::ops::ControlFlow;
 
 use thiserror::Error;
 
+#[cfg(feature = "petgraph")]
+pub use crate::petgraph_support::{
+    PetgraphInput, PetgraphResult, run_vf3_petgraph, run_vf3l_petgraph, run_vf3p_petgraph,
+};
+use crate::visit::{SolutionSink, Visit};
+pub use crate::{
+    canonical::{canonical_form, canonical_labeling},
+    formats::{
+        edge_list::EdgeListOptions,
+        graphml::GraphMlOptions,
+        lad::LadOptions,
+        solutions::{SolutionFormat, SolutionWriter},
+    },
+    graph::{Graph, GraphInput, NodeId},
+    labels::LabelDictionary,
+    mcs::{CommonSubgraph, McsOptions, maximum_common_subgraph},
+    region::MatchRegion,
+    symmetry::{Automorphisms, automorphisms},
+};
+
 /// Errors that can occur during VF3 algorithm execution.
 #[derive(Error, Debug)]
 pub enum VF3Error {
@@ -45,93 +100,89 @@ pub enum VF3Error {
         /// The format string that was provided.
         format: String,
     },
-}
 
-// Skip C++ compilation on docs.rs to avoid build failures.
-#[cfg(not(docsrs))]
-#[cxx::bridge(namespace = "vf3ffi")]
-#[allow(clippy::too_many_arguments)]
-mod vf3ffi {
-    /// Execution result from C++ VF3 algorithms.
-    #[derive(Debug, Clone)]
-    pub struct VF3Result {
-        /// Status code: 0 on success, non-zero on error.
-        pub status: i32,
-        /// Number of isomorphic mappings found.
-        pub solutions: u64,
-        /// Time to first solution in seconds.
-        pub time_first: f64,
-        /// Average total execution time in seconds.
-        pub time_all: f64,
-    }
-
-    unsafe extern "C++" {
-        include!("vf3_bridge.hpp");
-
-        /// VF3 algorithm with all heuristics (best for medium/large dense graphs).
-        fn run_vf3(
-            pattern: &str,
-            target: &str,
-            format: &str,
-            undirected: bool,
-            store_solutions: bool,
-            first_only: bool,
-            verbose: bool,
-            repetition_time_limit: f32,
-            edge_induced: bool,
-        ) -> VF3Result;
-
-        /// VF3L lightweight variant without look-ahead (best for small/sparse graphs).
-        fn run_vf3l(
-            pattern: &str,
-            target: &str,
-            format: &str,
-            undirected: bool,
-            store_solutions: bool,
-            first_only: bool,
-            verbose: bool,
-            repetition_time_limit: f32,
-            edge_induced: bool,
-        ) -> VF3Result;
-
-        /// VF3P parallel variant for multi-threaded execution.
-        fn run_vf3p(
-            pattern: &str,
-            target: &str,
-            format: &str,
-            undirected: bool,
-            store_solutions: bool,
-            verbose: bool,
-            repetition_time_limit: f32,
-            edge_induced: bool,
-            algo: i8,
-            cpu: i16,
-            num_threads: i16,
-            lock_free: bool,
-            ssr_high_limit: i16,
-            ssr_local_stack_limit: i16,
-        ) -> VF3Result;
-    }
-}
+    /// Reading a graph file failed.
+    #[error("I/O error: {source}")]
+    Io {
+        /// The underlying I/O error.
+        #[from]
+        source: std::io::Error,
+    },
 
-#[cfg(docsrs)]
-mod vf3ffi {
-    #[derive(Debug, Clone)]
-    pub struct VF3Result {
-        pub status: i32,
-        pub solutions: u64,
-        pub time_first: f64,
-        pub time_all: f64,
-    }
+    /// A graph file could not be parsed.
+    #[error("Parse error on line {line}: {message}")]
+    Parse {
+        /// One-based line number where parsing failed (`0` for binary formats).
+        line: usize,
+        /// Description of the problem.
+        message: String,
+    },
+
+    /// An in-memory graph cannot be matched, e.g. because it has a self-loop.
+    #[error("Invalid graph: {message}")]
+    InvalidGraph {
+        /// Description of the problem.
+        message: String,
+    },
 }
 
+// The vendored C++ library, compiled by build.rs unless the `cpp` feature is
+// off or the crate is built on docs.rs.
+#[cfg(vf3_cxx)]
+mod bridge;
+#[cfg(vf3_cxx)]
+use bridge::vf3ffi;
+
+// The pure-Rust port of the same matchers, called through the same names.
+// The common subgraph search builds on its state in either build.
+mod native;
+#[cfg(not(vf3_cxx))]
+use native as vf3ffi;
+
 /// Graph file format for loading.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GraphFormat {
     /// VF text/binary format used by MIVIA datasets (.grf files).
+    #[cfg_attr(feature = "serde", serde(rename = "vf"))]
     VFLegacy,
     /// Simple edge list format (one edge per line as "u v").
+    ///
+    /// Node ids are interpreted according to [`RunOptions::edge_list`].
+    #[cfg_attr(feature = "serde", serde(rename = "edge"))]
     EdgeList,
+    /// GraphML documents (.graphml files).
+    ///
+    /// Labels are read according to [`RunOptions::graphml`], and the
+    /// `edgedefault` of each document decides directedness unless
+    /// [`RunOptions::undirected`] is set.
+    #[cfg_attr(feature = "serde", serde(rename = "graphml"))]
+    GraphML,
+    /// LAD format from the LAD solver and SIP benchmark suites.
+    ///
+    /// Labelled variants are selected through [`RunOptions::lad`].
+    #[cfg_attr(feature = "serde", serde(rename = "lad"))]
+    Lad,
+    /// DIMACS undirected graph format (`p edge`, `e u v`, optional `n v label`).
+    #[cfg_attr(feature = "serde", serde(rename = "dimacs"))]
+    Dimacs,
+    /// RI undirected format with string node labels (.gfu files).
+    ///
+    /// Files with several graphs must be read with [`formats::ri::read`].
+    #[cfg_attr(feature = "serde", serde(rename = "gfu"))]
+    Gfu,
+    /// RI directed format with string node labels (.gfd files).
+    #[cfg_attr(feature = "serde", serde(rename = "gfd"))]
+    Gfd,
+    /// nauty graph6, sparse6 or digraph6 strings, detected per line.
+    ///
+    /// Files with several graphs must be read with [`formats::graph6::read`].
+    #[cfg_attr(feature = "serde", serde(rename = "graph6"))]
+    Graph6,
+    /// Binary CSR files written by [`formats::csr::write`], memory-mapped
+    /// rather than parsed.
+    #[cfg_attr(feature = "serde", serde(rename = "csr"))]
+    Csr,
 }
 
 impl GraphFormat {
@@ -139,17 +190,74 @@ impl GraphFormat {
         match self {
             GraphFormat::VFLegacy => "vf",
             GraphFormat::EdgeList => "edge",
+            GraphFormat::GraphML => "graphml",
+            GraphFormat::Lad => "lad",
+            GraphFormat::Dimacs => "dimacs",
+            GraphFormat::Gfu => "gfu",
+            GraphFormat::Gfd => "gfd",
+            GraphFormat::Graph6 => "graph6",
+            GraphFormat::Csr => "csr",
         }
     }
 }
 
+/// How an undirected pattern is matched against a directed target.
+///
+/// An undirected pattern edge `{u, v}` needs, in the target:
+///
+/// * [`MixedEdges::Reciprocal`]: both arcs `u' -> v'` and `v' -> u'`;
+/// * [`MixedEdges::Either`]: at least one of them. The target is matched as
+///   the undirected graph of [`Graph::to_undirected`], so a pair joined in
+///   both directions takes the label of the arc leaving the lower-numbered
+///   node.
+///
+/// Node-induced matching also requires non-adjacent pattern nodes to have no
+/// arc between them in either direction. A directed pattern against an
+/// undirected target is always matched as if the pattern were undirected,
+/// since an undirected edge can be traversed both ways.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MixedEdges {
+    /// Every pattern edge must be reciprocated in the target (default).
+    #[default]
+    Reciprocal,
+    /// Every pattern edge may map to a target arc in either orientation.
+    Either,
+}
+
+/// Errors an approximate match may contain.
+///
+/// Each budget is counted separately; a match is reported when neither is
+/// exceeded. An undirected edge counts once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Tolerance {
+    /// Pattern edges with no target edge between their images, plus, in
+    /// node-induced matching, target edges between the distinct images of
+    /// non-adjacent pattern nodes.
+    pub edges: usize,
+    /// Pattern nodes and edges mapped onto a target node or edge with a
+    /// different label.
+    pub labels: usize,
+}
+
 /// Configuration options for VF3 algorithm execution.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct RunOptions {
     /// Graph file format.
     pub format: GraphFormat,
     /// Treat graphs as undirected.
     pub undirected: bool,
+    /// Treat the pattern as undirected; `None` follows [`RunOptions::undirected`].
+    pub pattern_undirected: Option<bool>,
+    /// Treat the target as undirected; `None` follows [`RunOptions::undirected`].
+    pub target_undirected: Option<bool>,
+    /// How an undirected pattern matches a directed target.
+    pub mixed_edges: MixedEdges,
     /// Store all solution mappings in memory (may use significant memory for large result sets).
     pub store_solutions: bool,
     /// Stop after finding the first solution (sequential algorithms only).
@@ -160,6 +268,44 @@ pub struct RunOptions {
     pub repetition_time_limit: f32,
     /// Use edge-induced isomorphism (monomorphism) instead of node-induced.
     pub edge_induced: bool,
+    /// Find homomorphisms: every pattern node may share its target node with
+    /// others, and only pattern edges are checked, as with `edge_induced`.
+    ///
+    /// vf3lib only searches injective mappings, so this and
+    /// [`RunOptions::may_share`] switch to a single-threaded backtracking
+    /// search in Rust, for VF3P too.
+    pub homomorphism: bool,
+    /// Pattern nodes that may share a target node, for partially injective
+    /// matching.
+    ///
+    /// A target node can host several pattern nodes only if all of them are
+    /// listed here (or [`RunOptions::homomorphism`] is set). Adjacent pattern
+    /// nodes never share, since graphs have no self-loops. Node-induced
+    /// matching still requires non-adjacent pattern nodes with distinct
+    /// images to map to non-adjacent target nodes.
+    pub may_share: Vec<NodeId>,
+    /// Count distinct occurrences rather than mappings.
+    ///
+    /// Mappings that cover the same target nodes and edges are one
+    /// occurrence, so a triangle found in a triangle counts once instead of
+    /// once per automorphism. Injective runs divide the count by the number
+    /// of label-preserving pattern automorphisms, found with one extra VF3L
+    /// run of the pattern against itself, and keep one stored mapping per
+    /// occurrence. Runs where nodes may share store every mapping and
+    /// deduplicate them.
+    pub distinct_occurrences: bool,
+    /// Errors a match may contain, for matching noisy targets.
+    ///
+    /// Any tolerance switches to the Rust search, like
+    /// [`RunOptions::homomorphism`], and stored mappings come with their
+    /// [`ResultData::violations`].
+    pub tolerance: Tolerance,
+    /// How node ids are read from [`GraphFormat::EdgeList`] files.
+    pub edge_list: EdgeListOptions,
+    /// Which `<data>` keys supply labels in [`GraphFormat::GraphML`] files.
+    pub graphml: GraphMlOptions,
+    /// Which labels are present in [`GraphFormat::Lad`] files.
+    pub lad: LadOptions,
 }
 
 impl Default for RunOptions {
@@ -167,17 +313,47 @@ impl Default for RunOptions {
         Self {
             format: GraphFormat::VFLegacy,
             undirected: false,
+            pattern_undirected: None,
+            target_undirected: None,
+            mixed_edges: MixedEdges::Reciprocal,
             store_solutions: false,
             first_only: false,
             verbose: false,
             repetition_time_limit: 1.0,
             edge_induced: false,
+            homomorphism: false,
+            may_share: Vec::new(),
+            distinct_occurrences: false,
+            tolerance: Tolerance::default(),
+            edge_list: EdgeListOptions::default(),
+            graphml: GraphMlOptions::default(),
+            lad: LadOptions::default(),
         }
     }
 }
 
+impl RunOptions {
+    /// Whether the pattern file is read as undirected.
+    fn pattern_is_undirected(&self) -> bool {
+        self.pattern_undirected.unwrap_or(self.undirected)
+    }
+
+    /// Whether the target file is read as undirected.
+    fn target_is_undirected(&self) -> bool {
+        self.target_undirected.unwrap_or(self.undirected)
+    }
+
+    /// Whether pattern nodes may share target nodes or matches may contain
+    /// errors, which needs the Rust search instead of vf3lib.
+    fn rust_search(&self) -> bool {
+        self.homomorphism || !self.may_share.is_empty() || self.tolerance != Tolerance::default()
+    }
+}
+
 /// Configuration options for parallel VF3P execution.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ParallelOptions {
     /// Algorithm variant: 1 = GSS (Global State Stack), 2 = WLS (Work-stealing with Local Stack).
     pub algo: i8,
@@ -206,8 +382,32 @@ impl Default for ParallelOptions {
     }
 }
 
+/// Pattern-to-target node correspondences of one solution, ordered by pattern node.
+pub type Mapping = Vec<(NodeId, NodeId)>;
+
+/// Errors of one approximate match, in pattern node ids.
+///
+/// Edges of a directed pattern are `(from, to)` arcs; those of an undirected
+/// pattern have the smaller node first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Violations {
+    /// Pattern edges with no target edge between their images.
+    pub missing_edges: Vec<(NodeId, NodeId)>,
+    /// Non-adjacent pattern node pairs whose images are adjacent, in
+    /// node-induced matching.
+    pub extra_edges: Vec<(NodeId, NodeId)>,
+    /// Pattern nodes mapped onto a node with a different label.
+    pub node_labels: Vec<NodeId>,
+    /// Pattern edges mapped onto an edge with a different label.
+    pub edge_labels: Vec<(NodeId, NodeId)>,
+}
+
 /// Results from VF3 algorithm execution.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ResultData {
     /// Number of isomorphic mappings found.
     pub solutions: u64,
@@ -215,15 +415,63 @@ pub struct ResultData {
     pub time_first: f64,
     /// Average total execution time in seconds.
     pub time_all: f64,
+    /// Solutions from the last repetition (empty unless `store_solutions` is set).
+    pub mappings: Vec<Mapping>,
+    /// Errors of each stored mapping, when [`RunOptions::tolerance`] allows
+    /// any (empty otherwise).
+    pub violations: Vec<Violations>,
+    /// External names of the pattern nodes, when the input carried them.
+    pub pattern_names: Option<Vec<String>>,
+    /// External names of the target nodes, when the input carried them.
+    pub target_names: Option<Vec<String>>,
+}
+
+impl ResultData {
+    /// Stored mappings expressed with the input's node names.
+    ///
+    /// Returns `None` unless both graphs carry names, as edge lists always do.
+    pub fn named_mappings(&self) -> Option<Vec<Vec<(&str, &str)>>> {
+        let (pattern, target) = (self.pattern_names.as_ref()?, self.target_names.as_ref()?);
+        Some(
+            self.mappings
+                .iter()
+                .map(|mapping| {
+                    mapping
+                        .iter()
+                        .map(|&(p, t)| (pattern[p as usize].as_str(), target[t as usize].as_str()))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    fn with_names(mut self, pattern: Option<&[String]>, target: Option<&[String]>) -> Self {
+        self.pattern_names = pattern.map(<[String]>::to_vec);
+        self.target_names = target.map(<[String]>::to_vec);
+        self
+    }
 }
 
-#[cfg(not(docsrs))]
 fn convert_result(res: vf3ffi::VF3Result) -> Result<ResultData, VF3Error> {
     if res.status == 0 {
         Ok(ResultData {
             solutions: res.solutions,
             time_first: res.time_first,
             time_all: res.time_all,
+            mappings: res
+                .mappings
+                .into_iter()
+                .map(|sol| {
+                    sol.targets
+                        .into_iter()
+                        .enumerate()
+                        .map(|(p, t)| (p as NodeId, t))
+                        .collect()
+                })
+                .collect(),
+            violations: Vec::new(),
+            pattern_names: None,
+            target_names: None,
         })
     } else {
         Err(VF3Error::ExecutionFailed { code: res.status })
@@ -236,30 +484,27 @@ fn convert_result(res: vf3ffi::VF3Result) -> Result<ResultData, VF3Error> {
 ///
 /// # Errors
 ///
-/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails.
+/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails, or an
+/// [`VF3Error::Io`]/[`VF3Error::Parse`] error for graph files read on the Rust side.
 pub fn run_vf3(pattern: &str, target: &str, opts: RunOptions) -> Result<ResultData, VF3Error> {
-    #[cfg(not(docsrs))]
-    {
-        let res = vf3ffi::run_vf3(
-            pattern,
-            target,
-            opts.format.as_str(),
-            opts.undirected,
-            opts.store_solutions,
-            opts.first_only,
-            opts.verbose,
-            opts.repetition_time_limit,
-            opts.edge_induced,
-        );
-        convert_result(res)
-    }
-    #[cfg(docsrs)]
-    {
-        let _ = (pattern, target, opts);
-        Err(VF3Error::FfiError {
-            message: "VF3 not available in docs.rs build".into(),
-        })
+    if let Some((pattern, target)) = formats::map_pair(pattern, target, &opts)? {
+        return run_vf3_graphs(&pattern, &target, opts);
     }
+    if let Some((pattern, target)) = formats::load_pair(pattern, target, &opts)? {
+        return run_vf3_graphs(&pattern, &target, opts);
+    }
+    let res = vf3ffi::run_vf3(
+        pattern,
+        target,
+        opts.format.as_str(),
+        opts.pattern_is_undirected(),
+        opts.store_solutions,
+        opts.first_only,
+        opts.verbose,
+        opts.repetition_time_limit,
+        opts.edge_induced,
+    );
+    convert_result(res)
 }
 
 /// Run VF3L lightweight variant without look-ahead heuristic.
@@ -268,30 +513,27 @@ pub fn run_vf3(pattern: &str, target: &str, opts: RunOptions) -> Result<ResultDa
 ///
 /// # Errors
 ///
-/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails.
+/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails, or an
+/// [`VF3Error::Io`]/[`VF3Error::Parse`] error for graph files read on the Rust side.
 pub fn run_vf3l(pattern: &str, target: &str, opts: RunOptions) -> Result<ResultData, VF3Error> {
-    #[cfg(not(docsrs))]
-    {
-        let res = vf3ffi::run_vf3l(
-            pattern,
-            target,
-            opts.format.as_str(),
-            opts.undirected,
-            opts.store_solutions,
-            opts.first_only,
-            opts.verbose,
-            opts.repetition_time_limit,
-            opts.edge_induced,
-        );
-        convert_result(res)
-    }
-    #[cfg(docsrs)]
-    {
-        let _ = (pattern, target, opts);
-        Err(VF3Error::FfiError {
-            message: "VF3L not available in docs.rs build".into(),
-        })
+    if let Some((pattern, target)) = formats::map_pair(pattern, target, &opts)? {
+        return run_vf3l_graphs(&pattern, &target, opts);
+    }
+    if let Some((pattern, target)) = formats::load_pair(pattern, target, &opts)? {
+        return run_vf3l_graphs(&pattern, &target, opts);
     }
+    let res = vf3ffi::run_vf3l(
+        pattern,
+        target,
+        opts.format.as_str(),
+        opts.pattern_is_undirected(),
+        opts.store_solutions,
+        opts.first_only,
+        opts.verbose,
+        opts.repetition_time_limit,
+        opts.edge_induced,
+    );
+    convert_result(res)
 }
 
 /// Run VF3P parallel variant with multi-threading support.
@@ -300,20 +542,198 @@ pub fn run_vf3l(pattern: &str, target: &str, opts: RunOptions) -> Result<ResultD
 ///
 /// # Errors
 ///
-/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails.
+/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails, or an
+/// [`VF3Error::Io`]/[`VF3Error::Parse`] error for graph files read on the Rust side.
 pub fn run_vf3p(
     pattern: &str,
     target: &str,
     opts: RunOptions,
     par: ParallelOptions,
 ) -> Result<ResultData, VF3Error> {
-    #[cfg(not(docsrs))]
-    {
-        let res = vf3ffi::run_vf3p(
-            pattern,
-            target,
-            opts.format.as_str(),
-            opts.undirected,
+    if let Some((pattern, target)) = formats::map_pair(pattern, target, &opts)? {
+        return run_vf3p_graphs(&pattern, &target, opts, par);
+    }
+    if let Some((pattern, target)) = formats::load_pair(pattern, target, &opts)? {
+        return run_vf3p_graphs(&pattern, &target, opts, par);
+    }
+    let res = vf3ffi::run_vf3p(
+        pattern,
+        target,
+        opts.format.as_str(),
+        opts.pattern_is_undirected(),
+        opts.store_solutions,
+        opts.verbose,
+        opts.repetition_time_limit,
+        opts.edge_induced,
+        par.algo,
+        par.cpu,
+        par.num_threads,
+        par.lock_free,
+        par.ssr_high_limit,
+        par.ssr_local_stack_limit,
+    );
+    convert_result(res)
+}
+
+/// Run VF3 on in-memory graphs.
+///
+/// Directedness is taken from the graphs themselves, with
+/// `opts.mixed_edges` deciding how an undirected pattern matches a directed
+/// target; `opts.format`, the `undirected` flags and the per-format reader
+/// options are ignored.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails, or
+/// [`VF3Error::InvalidGraph`] if `opts.may_share` names a node the pattern
+/// does not have.
+pub fn run_vf3_graphs(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3, None)
+}
+
+/// Run VF3L on in-memory graphs, as [`run_vf3_graphs`] runs VF3.
+///
+/// # Errors
+///
+/// As for [`run_vf3_graphs`].
+pub fn run_vf3l_graphs(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3l, None)
+}
+
+/// Run VF3P on in-memory graphs, as [`run_vf3_graphs`] runs VF3.
+///
+/// # Errors
+///
+/// As for [`run_vf3_graphs`].
+pub fn run_vf3p_graphs(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+    par: ParallelOptions,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3p(par), None)
+}
+
+/// Run VF3 on in-memory graphs, handing each solution to `visit` as the
+/// search finds it.
+///
+/// `visit` gets the `(pattern, target)` pairs of each mapping and stops the
+/// search by returning [`ControlFlow::Break`]. Nothing is
+/// buffered for it, so `opts.store_solutions` can stay off however many
+/// solutions there are, and the search runs once, ignoring
+/// `opts.repetition_time_limit`, so no solution is visited twice. With
+/// `opts.distinct_occurrences`, only the first mapping of each occurrence is
+/// visited; to recognise repeats, every occurrence visited so far is kept in
+/// memory, so memory grows with the number of occurrences.
+/// [`SolutionWriter`] writes the mappings out as JSON Lines or CSV.
+///
+/// # Errors
+///
+/// As for [`run_vf3_graphs`].
+pub fn run_vf3_graphs_with(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+    mut visit: impl FnMut(&[(NodeId, NodeId)]) -> ControlFlow<()>,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3, Some(&mut visit))
+}
+
+/// Run VF3L on in-memory graphs, handing each solution to `visit` as
+/// [`run_vf3_graphs_with`] does.
+///
+/// # Errors
+///
+/// As for [`run_vf3_graphs`].
+pub fn run_vf3l_graphs_with(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+    mut visit: impl FnMut(&[(NodeId, NodeId)]) -> ControlFlow<()>,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3l, Some(&mut visit))
+}
+
+/// Run VF3P on in-memory graphs, handing each solution to `visit` as
+/// [`run_vf3_graphs_with`] does.
+///
+/// Worker threads take turns calling `visit`. vf3lib's parallel engines
+/// search on after a break, but `visit` is not called again.
+///
+/// # Errors
+///
+/// As for [`run_vf3_graphs`].
+pub fn run_vf3p_graphs_with(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: RunOptions,
+    par: ParallelOptions,
+    mut visit: impl FnMut(&[(NodeId, NodeId)]) -> ControlFlow<()> + Send,
+) -> Result<ResultData, VF3Error> {
+    match_graphs(pattern, target, opts, Engine::Vf3p(par), Some(&mut visit))
+}
+
+/// Matcher run by [`match_graphs`].
+enum Engine {
+    Vf3,
+    Vf3l,
+    Vf3p(ParallelOptions),
+}
+
+/// Body of the `run_*_graphs` runners. With a `visit` callback the search
+/// runs once.
+fn match_graphs(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    mut opts: RunOptions,
+    engine: Engine,
+    visit: Option<Visit<'_, '_>>,
+) -> Result<ResultData, VF3Error> {
+    if visit.is_some() {
+        opts.repetition_time_limit = 0.0;
+    }
+    let (pattern_csr, target_csr) = graph::orient(pattern.csr(), target.csr(), opts.mixed_edges);
+    let mut sink = SolutionSink::new(visit, &pattern_csr, opts.distinct_occurrences);
+    if opts.rust_search() {
+        let res = homomorphism::run(&pattern_csr, &target_csr, &opts, &mut sink);
+        sink.finish();
+        return res.map(|res| res.with_names(pattern.names(), target.names()));
+    }
+    let (pattern_ffi, target_ffi) = (pattern_csr.ffi(), target_csr.ffi());
+    // VF3P has no first-only mode.
+    let first_only = opts.first_only && !matches!(engine, Engine::Vf3p(_));
+    let res = match engine {
+        Engine::Vf3 => vf3ffi::run_vf3_graph(
+            &pattern_ffi,
+            &target_ffi,
+            opts.store_solutions,
+            opts.first_only,
+            opts.verbose,
+            opts.repetition_time_limit,
+            opts.edge_induced,
+            &mut sink,
+        ),
+        Engine::Vf3l => vf3ffi::run_vf3l_graph(
+            &pattern_ffi,
+            &target_ffi,
+            opts.store_solutions,
+            opts.first_only,
+            opts.verbose,
+            opts.repetition_time_limit,
+            opts.edge_induced,
+            &mut sink,
+        ),
+        Engine::Vf3p(par) => vf3ffi::run_vf3p_graph(
+            &pattern_ffi,
+            &target_ffi,
             opts.store_solutions,
             opts.verbose,
             opts.repetition_time_limit,
@@ -324,16 +744,15 @@ pub fn run_vf3p(
             par.lock_free,
             par.ssr_high_limit,
             par.ssr_local_stack_limit,
-        );
-        convert_result(res)
-    }
-    #[cfg(docsrs)]
-    {
-        let _ = (pattern, target, opts, par);
-        Err(VF3Error::FfiError {
-            message: "VF3P not available in docs.rs build".into(),
-        })
+            &mut sink,
+        ),
+    };
+    sink.finish();
+    let mut res = convert_result(res)?;
+    if opts.distinct_occurrences {
+        res = occurrences::distinct(res, &pattern_csr, first_only)?;
     }
+    Ok(res.with_names(pattern.names(), target.names()))
 }
 
 /// Builder for configuring and executing VF3 subgraph isomorphism queries.
@@ -385,6 +804,24 @@ impl<'a> VF3Query<'a> {
         self
     }
 
+    /// Set how node ids are read from edge-list files.
+    pub fn edge_list(mut self, edge_list: EdgeListOptions) -> Self {
+        self.options.edge_list = edge_list;
+        self
+    }
+
+    /// Set which GraphML keys supply node and edge labels.
+    pub fn graphml(mut self, graphml: GraphMlOptions) -> Self {
+        self.options.graphml = graphml;
+        self
+    }
+
+    /// Set which labels are present in LAD files.
+    pub fn lad(mut self, lad: LadOptions) -> Self {
+        self.options.lad = lad;
+        self
+    }
+
     /// Treat graphs as undirected.
     pub fn undirected(mut self) -> Self {
         self.options.undirected = true;
@@ -397,6 +834,24 @@ impl<'a> VF3Query<'a> {
         self
     }
 
+    /// Treat the pattern as undirected or directed, whatever the target is.
+    pub fn pattern_undirected(mut self, undirected: bool) -> Self {
+        self.options.pattern_undirected = Some(undirected);
+        self
+    }
+
+    /// Treat the target as undirected or directed, whatever the pattern is.
+    pub fn target_undirected(mut self, undirected: bool) -> Self {
+        self.options.target_undirected = Some(undirected);
+        self
+    }
+
+    /// Set how an undirected pattern matches a directed target.
+    pub fn mixed_edges(mut self, mixed_edges: MixedEdges) -> Self {
+        self.options.mixed_edges = mixed_edges;
+        self
+    }
+
     /// Use edge-induced isomorphism (monomorphism) instead of node-induced.
     pub fn edge_induced(mut self) -> Self {
         self.options.edge_induced = true;
@@ -409,6 +864,30 @@ impl<'a> VF3Query<'a> {
         self
     }
 
+    /// Find homomorphisms, where pattern nodes may share target nodes.
+    pub fn homomorphism(mut self) -> Self {
+        self.options.homomorphism = true;
+        self
+    }
+
+    /// Allow the given pattern nodes to share target nodes with each other.
+    pub fn may_share(mut self, nodes: impl IntoIterator<Item = NodeId>) -> Self {
+        self.options.may_share.extend(nodes);
+        self
+    }
+
+    /// Count distinct occurrences instead of every mapping.
+    pub fn distinct_occurrences(mut self) -> Self {
+        self.options.distinct_occurrences = true;
+        self
+    }
+
+    /// Allow matches with up to the given edge and label errors.
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.options.tolerance = tolerance;
+        self
+    }
+
     /// Store all solution mappings in memory.
     ///
     /// Warning: This may use significant memory for large result sets.
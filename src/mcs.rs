@@ -0,0 +1,276 @@
+//! Maximum common subgraph search, for measuring how close a pattern comes to
+//! matching a target it does not embed in.
+//!
+//! The search runs on the VF3L state of the native matcher: pattern nodes are
+//! taken in the `VF3NodeSorter` order, target candidates come from the same
+//! label classes, and a node joins the state only through the state's own
+//! consistency check. Unlike subgraph matching, each pattern node may also be
+//! left out, and a branch and bound keeps the largest mapping reached.
+
+use std::{cmp::Reverse, time::Instant};
+
+use crate::{
+    GraphInput, Mapping, MixedEdges, NodeId, graph,
+    homomorphism::Adjacency,
+    native::{
+        classify, sorter,
+        state::{NULL, Search, State},
+    },
+};
+
+/// Options for [`maximum_common_subgraph`].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct McsOptions {
+    /// Maximise the pattern edges preserved by an injective mapping, as in
+    /// edge-induced matching, instead of the nodes of a common induced
+    /// subgraph.
+    pub edge_induced: bool,
+    /// How an undirected pattern matches a directed target.
+    pub mixed_edges: MixedEdges,
+    /// Stop as soon as a common subgraph of this size is found: nodes for
+    /// induced search, edges for edge-induced search.
+    pub size_limit: Option<usize>,
+    /// Stop after this many seconds and return the best mapping found.
+    pub time_limit: Option<f32>,
+}
+
+/// Largest common subgraph found by [`maximum_common_subgraph`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommonSubgraph {
+    /// Pattern-to-target correspondences of the common subgraph, ordered by
+    /// pattern node. Pattern nodes outside it are left out.
+    pub mapping: Mapping,
+    /// Number of pattern edges preserved by the mapping.
+    pub edges: usize,
+    /// Whether the search ran to completion, proving no larger common
+    /// subgraph exists, rather than stopping at a limit.
+    pub optimal: bool,
+    /// Search time in seconds.
+    pub time: f64,
+}
+
+/// Find a largest common subgraph of `pattern` and `target`.
+///
+/// The node-induced search maximises the number of mapped pattern nodes such
+/// that node labels agree and every pair of mapped nodes has the same arcs,
+/// with the same labels, in both graphs. With
+/// [`McsOptions::edge_induced`] the mapping only needs to be injective and
+/// label-preserving, and the number of pattern edges it carries onto equally
+/// labelled target edges is maximised, then the number of mapped nodes. A
+/// pattern that embeds in the target yields the whole pattern either way.
+/// The common subgraph need not be connected.
+///
+/// Both searches extend the same state as the VF3L matcher and are exact
+/// branch and bound searches, so they can take exponential time: set
+/// [`McsOptions::time_limit`] or [`McsOptions::size_limit`] for large graphs;
+/// the best mapping found so far is returned when a limit is hit.
+pub fn maximum_common_subgraph(
+    pattern: &impl GraphInput,
+    target: &impl GraphInput,
+    opts: &McsOptions,
+) -> CommonSubgraph {
+    let start = Instant::now();
+    let (pattern, target) = graph::orient(pattern.csr(), target.csr(), opts.mixed_edges);
+    let undirected = !pattern.directed;
+    let (pattern, target) = (Adjacency::new(&pattern), Adjacency::new(&target));
+    let (pattern_classes, target_classes, classes) = classify(&pattern, &target);
+    // The node-induced light state: its consistency check is exactly what
+    // the induced search needs, and the edge-induced search only pairs nodes
+    // of one class.
+    let search = Search::new(
+        &pattern,
+        &target,
+        &pattern_classes,
+        &target_classes,
+        classes,
+        sorter::sort_nodes(&pattern, &target),
+        false,
+        false,
+    );
+
+    let mut pattern_left = vec![0; classes];
+    for &class in &pattern_classes {
+        pattern_left[class as usize] += 1;
+    }
+    let mut target_free = vec![0; classes];
+    for &class in &target_classes {
+        target_free[class as usize] += 1;
+    }
+    let mut common = Common {
+        search: &search,
+        pattern_classes: &pattern_classes,
+        targets: target.len(),
+        undirected,
+        edge_induced: opts.edge_induced,
+        remaining: remaining_edges(&pattern, search.order(), undirected),
+        state: search.state(),
+        pattern_left,
+        target_free,
+        limits: Limits {
+            start,
+            time_limit: opts.time_limit.map(f64::from),
+            size_limit: opts.size_limit,
+            stopped: false,
+        },
+        best: vec![NULL; pattern.len()],
+        best_edges: 0,
+        best_score: (0, 0),
+    };
+    common.extend(0, 0);
+
+    CommonSubgraph {
+        mapping: common
+            .best
+            .iter()
+            .enumerate()
+            .filter(|&(_, &image)| image != NULL)
+            .map(|(node, &image)| (node as NodeId, image))
+            .collect(),
+        edges: common.best_edges,
+        optimal: !common.limits.stopped,
+        time: start.elapsed().as_secs_f64(),
+    }
+}
+
+struct Limits {
+    start: Instant,
+    time_limit: Option<f64>,
+    size_limit: Option<usize>,
+    stopped: bool,
+}
+
+impl Limits {
+    /// Whether the search must stop, checking the clock.
+    fn expired(&mut self) -> bool {
+        if !self.stopped
+            && let Some(limit) = self.time_limit
+        {
+            self.stopped = self.start.elapsed().as_secs_f64() >= limit;
+        }
+        self.stopped
+    }
+
+    /// Record that a common subgraph of `size` was found.
+    fn found(&mut self, size: usize) {
+        if self.size_limit.is_some_and(|limit| size >= limit) {
+            self.stopped = true;
+        }
+    }
+}
+
+/// Pattern edges whose later endpoint in `order` is at or after each
+/// position, which bounds the edges still to gain.
+fn remaining_edges(pattern: &Adjacency<'_>, order: &[NodeId], undirected: bool) -> Vec<usize> {
+    let mut position = vec![0; order.len()];
+    for (pos, &node) in order.iter().enumerate() {
+        position[node as usize] = pos;
+    }
+    let mut remaining = vec![0; order.len() + 1];
+    for from in 0..pattern.len() as NodeId {
+        for &to in pattern.successors(from).0 {
+            if !undirected || from < to {
+                remaining[position[from as usize].max(position[to as usize])] += 1;
+            }
+        }
+    }
+    for pos in (0..order.len()).rev() {
+        remaining[pos] += remaining[pos + 1];
+    }
+    remaining
+}
+
+/// Branch and bound over the VF3L state: the pattern node at each position
+/// of the order is paired with each consistent candidate in turn, then left
+/// out.
+struct Common<'s, 'a> {
+    search: &'s Search<'a>,
+    pattern_classes: &'s [u32],
+    targets: usize,
+    undirected: bool,
+    edge_induced: bool,
+    remaining: Vec<usize>,
+    state: State,
+    /// Pattern nodes of each class not yet paired or left out.
+    pattern_left: Vec<usize>,
+    /// Unmapped target nodes of each class.
+    target_free: Vec<usize>,
+    limits: Limits,
+    best: Vec<NodeId>,
+    best_edges: usize,
+    best_score: (usize, usize),
+}
+
+impl Common<'_, '_> {
+    /// What the search maximises: mapped nodes, or preserved edges and then
+    /// mapped nodes.
+    fn score(&self, edges: usize, nodes: usize) -> (usize, usize) {
+        if self.edge_induced {
+            (edges, nodes)
+        } else {
+            (nodes, 0)
+        }
+    }
+
+    fn extend(&mut self, pos: usize, edges: usize) {
+        if self.limits.expired() {
+            return;
+        }
+        // Every state is a common subgraph, so a size limit stops at the
+        // first one that reaches it.
+        let nodes = self.state.depth;
+        let score = self.score(edges, nodes);
+        if score > self.best_score {
+            self.best_score = score;
+            self.best.clone_from(&self.state.core_1);
+            self.best_edges = edges;
+            self.limits.found(score.0);
+            if self.limits.stopped {
+                return;
+            }
+        }
+        let Some(&node) = self.search.order().get(pos) else {
+            return;
+        };
+        // Each class can still contribute at most its smaller side.
+        let fits: usize = self
+            .pattern_left
+            .iter()
+            .zip(&self.target_free)
+            .map(|(&left, &free)| left.min(free))
+            .sum();
+        if self.score(edges + self.remaining[pos], nodes + fits) <= self.best_score {
+            return;
+        }
+
+        let (search, state) = (self.search, &self.state);
+        let mut candidates: Vec<_> = (0..self.targets as NodeId)
+            .filter(|&n2| {
+                search.is_candidate(state, node, n2)
+                    && (self.edge_induced || search.is_consistent(state, node, n2))
+            })
+            .map(|n2| (search.kept_edges(state, node, n2, self.undirected), n2))
+            .collect();
+        candidates.sort_unstable_by_key(|&(gain, n2)| (Reverse(gain), n2));
+
+        let class = self.pattern_classes[node as usize] as usize;
+        self.pattern_left[class] -= 1;
+        for (gain, n2) in candidates {
+            search.add_pair(&mut self.state, node, n2);
+            self.target_free[class] -= 1;
+            self.extend(pos + 1, edges + gain);
+            self.target_free[class] += 1;
+            search.back_track(&mut self.state, node, n2);
+            if self.limits.stopped {
+                break;
+            }
+        }
+        // Leave `node` out of the common subgraph.
+        if !self.limits.stopped {
+            self.extend(pos + 1, edges);
+        }
+        self.pattern_left[class] += 1;
+    }
+}
@@ -0,0 +1,472 @@
+//! Pure-Rust port of the vf3lib matchers, used when the crate is built
+//! without the `cpp` feature. Its node order and states are compiled in
+//! either build, since the maximum common subgraph search runs on them.
+//!
+//! The module mirrors the surface of the cxx bridge so the runners call it
+//! the same way: graphs arrive as borrowed CSR arrays, results come back as
+//! [`VF3Result`] with the bridge's status codes. The search follows vf3lib
+//! step for step (the `FastCheck` pre-test, label classes, the
+//! `VF3NodeSorter` order and the VF3/VF3L states), so both backends report
+//! the same solutions in the same order.
+
+// With the C++ backend only the pieces used by `mcs` are live.
+#![cfg_attr(vf3_cxx, allow(dead_code))]
+
+pub(crate) mod sorter;
+pub(crate) mod state;
+
+use std::{borrow::Cow, fs::File, io::BufReader, time::Instant};
+
+use self::state::Search;
+use crate::{formats::grf, graph::Csr, homomorphism::Adjacency, visit::SolutionSink};
+
+/// Execution result, laid out like the one returned by the cxx bridge.
+#[derive(Debug, Clone)]
+pub struct VF3Result {
+    /// Status code: 0 on success, non-zero on error.
+    pub status: i32,
+    /// Number of isomorphic mappings found.
+    pub solutions: u64,
+    /// Time to first solution in seconds.
+    pub time_first: f64,
+    /// Average total execution time in seconds.
+    pub time_all: f64,
+    /// Stored solutions (empty unless solutions are stored).
+    pub mappings: Vec<SolutionData>,
+}
+
+/// Target node matched to each pattern node, indexed by pattern node.
+#[derive(Debug, Clone)]
+pub struct SolutionData {
+    pub targets: Vec<u32>,
+}
+
+/// Borrowed graph in compressed sparse row form.
+#[derive(Debug, Clone, Copy)]
+pub struct GraphData<'a> {
+    /// Label of each node.
+    pub node_labels: &'a [i32],
+    /// Start of each node's successors in `targets`, plus a final end offset.
+    pub offsets: &'a [u32],
+    /// Concatenated, sorted successor lists.
+    pub targets: &'a [u32],
+    /// Label of each edge, aligned with `targets`.
+    pub edge_labels: &'a [i32],
+}
+
+impl<'a> From<&'a Csr<'_>> for GraphData<'a> {
+    fn from(csr: &'a Csr<'_>) -> Self {
+        Self {
+            node_labels: &csr.node_labels,
+            offsets: &csr.offsets,
+            targets: &csr.targets,
+            edge_labels: &csr.edge_labels,
+        }
+    }
+}
+
+impl GraphData<'_> {
+    fn csr(&self) -> Csr<'_> {
+        Csr {
+            directed: true,
+            node_labels: Cow::Borrowed(self.node_labels),
+            offsets: Cow::Borrowed(self.offsets),
+            targets: Cow::Borrowed(self.targets),
+            edge_labels: Cow::Borrowed(self.edge_labels),
+        }
+    }
+}
+
+/// Which state the search runs with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variant {
+    /// `VF3SubState`, with the terminal-set look-ahead.
+    Full,
+    /// `VF3LightSubState`, without it.
+    Light,
+}
+
+const FAILED: VF3Result = VF3Result {
+    status: 1,
+    solutions: 0,
+    time_first: 0.0,
+    time_all: 0.0,
+    mappings: Vec::new(),
+};
+
+/// Counts compared by vf3lib's `FastCheck` before any search.
+struct Summary {
+    nodes: usize,
+    arcs: usize,
+    max_degree: usize,
+    max_out: usize,
+    max_in: usize,
+    node_labels: usize,
+    edge_labels: usize,
+}
+
+impl Summary {
+    fn new(graph: &Adjacency<'_>) -> Self {
+        let count = graph.len() as u32;
+        let degrees = |f: &dyn Fn(u32) -> usize| (0..count).map(f).max().unwrap_or(0);
+        let mut node_labels: Vec<i32> = (0..count).map(|n| graph.label(n)).collect();
+        node_labels.sort_unstable();
+        node_labels.dedup();
+        let mut edge_labels: Vec<i32> = (0..count)
+            .flat_map(|n| graph.successors(n).1.iter().copied())
+            .collect();
+        edge_labels.sort_unstable();
+        edge_labels.dedup();
+        Self {
+            nodes: graph.len(),
+            arcs: (0..count).map(|n| graph.successors(n).0.len()).sum(),
+            max_degree: degrees(&|n| graph.degree(n)),
+            max_out: degrees(&|n| graph.successors(n).0.len()),
+            max_in: degrees(&|n| graph.predecessors(n).0.len()),
+            node_labels: node_labels.len(),
+            edge_labels: edge_labels.len(),
+        }
+    }
+
+    /// `FastCheck::CheckSubgraphIsomorphism`: no count of the pattern may
+    /// exceed the target's.
+    fn fits(&self, target: &Self) -> bool {
+        self.nodes <= target.nodes
+            && self.arcs <= target.arcs
+            && self.max_degree <= target.max_degree
+            && self.max_out <= target.max_out
+            && self.max_in <= target.max_in
+            && self.node_labels <= target.node_labels
+            && self.edge_labels <= target.edge_labels
+    }
+}
+
+/// Number each label, target labels first in node order as vf3lib's
+/// `NodeClassifier` does, and return the pattern classes, target classes
+/// and class count.
+pub(crate) fn classify(
+    pattern: &Adjacency<'_>,
+    target: &Adjacency<'_>,
+) -> (Vec<u32>, Vec<u32>, usize) {
+    let mut classes = std::collections::HashMap::new();
+    let mut assign = |graph: &Adjacency<'_>| -> Vec<u32> {
+        (0..graph.len() as u32)
+            .map(|n| {
+                let next = classes.len() as u32;
+                *classes.entry(graph.label(n)).or_insert(next)
+            })
+            .collect()
+    };
+    let target_classes = assign(target);
+    let pattern_classes = assign(pattern);
+    (pattern_classes, target_classes, classes.len())
+}
+
+/// The bridge's `match_seq`: repeat the search until
+/// `repetition_time_limit` has elapsed and report the last repetition.
+#[allow(clippy::too_many_arguments)]
+fn match_seq(
+    pattern: &GraphData<'_>,
+    target: &GraphData<'_>,
+    variant: Variant,
+    store_solutions: bool,
+    first_only: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    sink: &mut SolutionSink<'_>,
+) -> (VF3Result, f64) {
+    let (pattern, target) = (pattern.csr(), target.csr());
+    let (pattern, target) = (Adjacency::new(&pattern), Adjacency::new(&target));
+    let fits = Summary::new(&pattern).fits(&Summary::new(&target));
+    let (pattern_classes, target_classes, classes) = if fits {
+        classify(&pattern, &target)
+    } else {
+        Default::default()
+    };
+
+    let (mut total, mut first, mut rep) = (0.0, 0.0, 0);
+    let (outcome, last) = loop {
+        rep += 1;
+        let start = Instant::now();
+        let mut outcome = state::Outcome::default();
+        if fits {
+            let order = sorter::sort_nodes(&pattern, &target);
+            let search = Search::new(
+                &pattern,
+                &target,
+                &pattern_classes,
+                &target_classes,
+                classes,
+                order,
+                variant == Variant::Full,
+                edge_induced,
+            );
+            outcome = search.run(start, store_solutions, first_only, sink);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        total += elapsed;
+        first += outcome.first.unwrap_or(elapsed);
+        if total >= f64::from(repetition_time_limit) {
+            break (outcome, elapsed);
+        }
+    };
+
+    let time_all = total / f64::from(rep);
+    let res = VF3Result {
+        status: 0,
+        solutions: outcome.solutions,
+        time_first: if first_only {
+            time_all
+        } else {
+            first / f64::from(rep)
+        },
+        time_all,
+        mappings: outcome
+            .mappings
+            .into_iter()
+            .map(|targets| SolutionData { targets })
+            .collect(),
+    };
+    (res, last)
+}
+
+/// Read a `.grf` file the way the bridge's `FastStreamARGLoader` does, or
+/// `None` where the bridge reports status 1.
+fn load(path: &str, format: &str, undirected: bool) -> Option<crate::Graph> {
+    if format != "vf" {
+        return None;
+    }
+    let file = File::open(path).ok()?;
+    grf::read(BufReader::new(file), undirected).ok()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_seq(
+    pattern: &str,
+    target: &str,
+    format: &str,
+    undirected: bool,
+    variant: Variant,
+    store_solutions: bool,
+    first_only: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+) -> VF3Result {
+    let (Some(pattern), Some(target)) = (
+        load(pattern, format, undirected),
+        load(target, format, undirected),
+    ) else {
+        return FAILED;
+    };
+    let (pattern, target) = (pattern.to_csr(), target.to_csr());
+    match_seq(
+        &GraphData::from(&pattern),
+        &GraphData::from(&target),
+        variant,
+        store_solutions,
+        first_only,
+        repetition_time_limit,
+        edge_induced,
+        &mut SolutionSink::none(),
+    )
+    .0
+}
+
+/// VF3 algorithm with all heuristics (best for medium/large dense graphs).
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3(
+    pattern: &str,
+    target: &str,
+    format: &str,
+    undirected: bool,
+    store_solutions: bool,
+    first_only: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+) -> VF3Result {
+    run_seq(
+        pattern,
+        target,
+        format,
+        undirected,
+        Variant::Full,
+        store_solutions,
+        first_only,
+        repetition_time_limit,
+        edge_induced,
+    )
+}
+
+/// VF3L lightweight variant without look-ahead (best for small/sparse graphs).
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3l(
+    pattern: &str,
+    target: &str,
+    format: &str,
+    undirected: bool,
+    store_solutions: bool,
+    first_only: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+) -> VF3Result {
+    run_seq(
+        pattern,
+        target,
+        format,
+        undirected,
+        Variant::Light,
+        store_solutions,
+        first_only,
+        repetition_time_limit,
+        edge_induced,
+    )
+}
+
+/// VF3P parallel variant, run here as a single VF3 search.
+///
+/// The thread options are accepted for parity with the bridge; only `algo`
+/// is validated.
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3p(
+    pattern: &str,
+    target: &str,
+    format: &str,
+    undirected: bool,
+    store_solutions: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    algo: i8,
+    _cpu: i16,
+    _num_threads: i16,
+    _lock_free: bool,
+    _ssr_high_limit: i16,
+    _ssr_local_stack_limit: i16,
+) -> VF3Result {
+    let (Some(pattern), Some(target)) = (
+        load(pattern, format, undirected),
+        load(target, format, undirected),
+    ) else {
+        return FAILED;
+    };
+    let (pattern, target) = (pattern.to_csr(), target.to_csr());
+    match_par(
+        &GraphData::from(&pattern),
+        &GraphData::from(&target),
+        store_solutions,
+        repetition_time_limit,
+        edge_induced,
+        algo,
+        &mut SolutionSink::none(),
+    )
+}
+
+/// VF3 on in-memory graphs.
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3_graph(
+    pattern: &GraphData<'_>,
+    target: &GraphData<'_>,
+    store_solutions: bool,
+    first_only: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    sink: &mut SolutionSink<'_>,
+) -> VF3Result {
+    match_seq(
+        pattern,
+        target,
+        Variant::Full,
+        store_solutions,
+        first_only,
+        repetition_time_limit,
+        edge_induced,
+        sink,
+    )
+    .0
+}
+
+/// VF3L on in-memory graphs.
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3l_graph(
+    pattern: &GraphData<'_>,
+    target: &GraphData<'_>,
+    store_solutions: bool,
+    first_only: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    sink: &mut SolutionSink<'_>,
+) -> VF3Result {
+    match_seq(
+        pattern,
+        target,
+        Variant::Light,
+        store_solutions,
+        first_only,
+        repetition_time_limit,
+        edge_induced,
+        sink,
+    )
+    .0
+}
+
+/// VF3P on in-memory graphs.
+#[allow(clippy::too_many_arguments)]
+pub fn run_vf3p_graph(
+    pattern: &GraphData<'_>,
+    target: &GraphData<'_>,
+    store_solutions: bool,
+    _verbose: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    algo: i8,
+    _cpu: i16,
+    _num_threads: i16,
+    _lock_free: bool,
+    _ssr_high_limit: i16,
+    _ssr_local_stack_limit: i16,
+    sink: &mut SolutionSink<'_>,
+) -> VF3Result {
+    match_par(
+        pattern,
+        target,
+        store_solutions,
+        repetition_time_limit,
+        edge_induced,
+        algo,
+        sink,
+    )
+}
+
+/// The bridge's `match_par`, which reports the last repetition's time as the
+/// time to the first solution.
+fn match_par(
+    pattern: &GraphData<'_>,
+    target: &GraphData<'_>,
+    store_solutions: bool,
+    repetition_time_limit: f32,
+    edge_induced: bool,
+    algo: i8,
+    sink: &mut SolutionSink<'_>,
+) -> VF3Result {
+    if !matches!(algo, 1 | 2) {
+        return FAILED;
+    }
+    let (res, last) = match_seq(
+        pattern,
+        target,
+        Variant::Full,
+        store_solutions,
+        false,
+        repetition_time_limit,
+        edge_induced,
+        sink,
+    );
+    VF3Result {
+        time_first: last,
+        ..res
+    }
+}
@@ -0,0 +1,193 @@
+//! Pattern node order of vf3lib's `VF3NodeSorter` with the
+//! `SubIsoNodeProbability` strategy.
+
+use std::collections::HashMap;
+
+use crate::{NodeId, homomorphism::Adjacency};
+
+/// How likely a pattern node is to find a match in the target, from the
+/// target's degree and label frequencies.
+struct Probability {
+    out_degrees: Vec<f64>,
+    in_degrees: Vec<f64>,
+    degrees: Vec<f64>,
+    labels: HashMap<i32, f64>,
+}
+
+impl Probability {
+    fn new(target: &Adjacency<'_>) -> Self {
+        let count = target.len() as NodeId;
+        let histogram = |degree: &dyn Fn(NodeId) -> usize| {
+            let mut counts = vec![0u32; (0..count).map(degree).max().unwrap_or(0) + 1];
+            for node in 0..count {
+                counts[degree(node)] += 1;
+            }
+            counts
+                .into_iter()
+                .map(|c| f64::from(c) / f64::from(count))
+                .collect()
+        };
+        let mut labels = HashMap::new();
+        for node in 0..count {
+            *labels.entry(target.label(node)).or_insert(0u32) += 1;
+        }
+        Self {
+            out_degrees: histogram(&|n| target.successors(n).0.len()),
+            in_degrees: histogram(&|n| target.predecessors(n).0.len()),
+            degrees: histogram(&|n| target.degree(n)),
+            labels: labels
+                .into_iter()
+                .map(|(label, c)| (label, f64::from(c) / f64::from(count)))
+                .collect(),
+        }
+    }
+
+    /// Probability that a random target node has at least the out-, in- and
+    /// total degree of `node` and carries its label.
+    fn of(&self, pattern: &Adjacency<'_>, node: NodeId) -> f64 {
+        let at_least = |probabilities: &[f64], degree: usize| -> f64 {
+            probabilities.iter().skip(degree).sum()
+        };
+        at_least(&self.out_degrees, pattern.successors(node).0.len())
+            * at_least(&self.in_degrees, pattern.predecessors(node).0.len())
+            * at_least(&self.degrees, pattern.degree(node))
+            * self
+                .labels
+                .get(&pattern.label(node))
+                .copied()
+                .unwrap_or(0.0)
+    }
+}
+
+/// `VF3SortingNode`.
+struct Entry {
+    probability: f64,
+    degree: usize,
+    /// Arcs to already ordered nodes.
+    matched: u32,
+    used: bool,
+    candidate: bool,
+}
+
+impl Entry {
+    /// `VF3SortingNode::operator<`: most arcs to ordered nodes, then least
+    /// probable, then highest degree.
+    fn precedes(&self, other: &Self) -> bool {
+        self.matched > other.matched
+            || (self.matched == other.matched && self.probability < other.probability)
+            || (self.matched == other.matched
+                && self.probability == other.probability
+                && self.degree > other.degree)
+    }
+}
+
+/// Order the pattern nodes for the VF3 states.
+///
+/// Patterns with an isolated node and at least two nodes are ordered like the
+/// bridge does, since `VF3NodeSorter` cannot handle them: each step takes the
+/// node with the most already ordered neighbours, then the highest degree.
+pub(crate) fn sort_nodes(pattern: &Adjacency<'_>, target: &Adjacency<'_>) -> Vec<NodeId> {
+    let count = pattern.len() as NodeId;
+    if count == 0 {
+        return Vec::new();
+    }
+    if count >= 2 && (0..count).any(|n| pattern.degree(n) == 0) {
+        return greedy(pattern);
+    }
+
+    let probability = Probability::new(target);
+    let mut nodes: Vec<Entry> = (0..count)
+        .map(|n| Entry {
+            probability: probability.of(pattern, n),
+            degree: pattern.degree(n),
+            matched: 0,
+            used: false,
+            candidate: false,
+        })
+        .collect();
+    let mut order = Vec::with_capacity(count as usize);
+    let mut candidates = Vec::new();
+
+    let mut top = 0;
+    for node in 1..count {
+        let (a, b) = (&nodes[node as usize], &nodes[top as usize]);
+        if a.probability < b.probability || (a.probability == b.probability && a.degree > b.degree)
+        {
+            top = node;
+        }
+    }
+    add(pattern, top, &mut nodes, &mut candidates, &mut order);
+    for _ in 1..count {
+        let mut best = candidates[0];
+        for &node in &candidates[1..] {
+            if nodes[node as usize].precedes(&nodes[best as usize]) {
+                best = node;
+            }
+        }
+        if nodes[best as usize].used {
+            best = (0..count)
+                .find(|&n| !nodes[n as usize].used)
+                .expect("an unordered node remains");
+        }
+        add(pattern, best, &mut nodes, &mut candidates, &mut order);
+    }
+    order
+}
+
+/// `VF3NodeSorter::AddNodeToSortedSet`.
+fn add(
+    pattern: &Adjacency<'_>,
+    node: NodeId,
+    nodes: &mut [Entry],
+    candidates: &mut Vec<NodeId>,
+    order: &mut Vec<NodeId>,
+) {
+    order.push(node);
+    let entry = &mut nodes[node as usize];
+    entry.used = true;
+    entry.candidate = true;
+    entry.matched = 0;
+    let neighbours = pattern.predecessors(node).0.iter();
+    for &neighbour in neighbours.chain(pattern.successors(node).0) {
+        let entry = &mut nodes[neighbour as usize];
+        if !entry.used {
+            entry.matched += 1;
+        }
+        if !entry.candidate {
+            entry.candidate = true;
+            candidates.push(neighbour);
+        }
+    }
+}
+
+/// Order for patterns with isolated nodes, which come last.
+fn greedy(pattern: &Adjacency<'_>) -> Vec<NodeId> {
+    let count = pattern.len() as NodeId;
+    let mut used = vec![false; count as usize];
+    let mut links = vec![0u32; count as usize];
+    let mut order = Vec::with_capacity(count as usize);
+    while order.len() < count as usize {
+        let mut best = None;
+        for node in (0..count).filter(|&n| !used[n as usize]) {
+            let better = best.is_none_or(|b: NodeId| {
+                (links[node as usize], pattern.degree(node))
+                    > (links[b as usize], pattern.degree(b))
+            });
+            if better {
+                best = Some(node);
+            }
+        }
+        let best = best.expect("an unordered node remains");
+        used[best as usize] = true;
+        order.push(best);
+        for &neighbour in pattern
+            .successors(best)
+            .0
+            .iter()
+            .chain(pattern.predecessors(best).0)
+        {
+            links[neighbour as usize] += 1;
+        }
+    }
+    order
+}
@@ -0,0 +1,539 @@
+//! Search states of vf3lib's `VF3SubState` and `VF3LightSubState`, with the
+//! depth-first traversal of its `MatchingEngine`.
+//!
+//! The light state, without the look-ahead, also drives the maximum common
+//! subgraph search in `mcs`, which maps pattern nodes in the same order
+//! through the same consistency checks but may leave nodes out.
+
+use std::time::Instant;
+
+use crate::{NodeId, homomorphism::Adjacency, visit::SolutionSink};
+
+/// Image of an unmapped node.
+pub(crate) const NULL: NodeId = NodeId::MAX;
+
+/// Which adjacency list of its predecessor's image a node draws candidates
+/// from.
+#[derive(Debug, Clone, Copy)]
+enum Dir {
+    /// The predecessor has an arc from the node: scan the image's
+    /// predecessors.
+    In,
+    /// The predecessor has an arc to the node: scan the image's successors.
+    Out,
+}
+
+/// Per-class counts of pattern nodes, one row per depth.
+struct Table {
+    classes: usize,
+    cells: Vec<u32>,
+}
+
+impl Table {
+    fn new(rows: usize, classes: usize) -> Self {
+        Self {
+            classes,
+            cells: vec![0; rows * classes],
+        }
+    }
+
+    fn row(&self, depth: usize) -> &[u32] {
+        &self.cells[depth * self.classes..(depth + 1) * self.classes]
+    }
+
+    fn row_mut(&mut self, depth: usize) -> &mut [u32] {
+        &mut self.cells[depth * self.classes..(depth + 1) * self.classes]
+    }
+}
+
+/// Pattern terminal-set sizes along the node order, precomputed by
+/// `VF3SubState::ComputeFirstGraphTraversing`.
+struct LookAhead {
+    /// Unordered neighbours of the node at each depth that are in the
+    /// in-terminal set, out-terminal set, or neither, by class.
+    term_in: Table,
+    term_out: Table,
+    new: Table,
+    /// Terminal set sizes once each depth has been reached, by class.
+    in_len: Table,
+    out_len: Table,
+    both_len: Table,
+}
+
+/// Solutions found by one search.
+#[derive(Default)]
+pub(super) struct Outcome {
+    pub(super) solutions: u64,
+    /// Target node of each pattern node, for every stored solution.
+    pub(super) mappings: Vec<Vec<NodeId>>,
+    /// Seconds from the start to the first solution.
+    pub(super) first: Option<f64>,
+}
+
+/// Fixed inputs of a search.
+pub(crate) struct Search<'a> {
+    pattern: &'a Adjacency<'a>,
+    target: &'a Adjacency<'a>,
+    pattern_classes: &'a [u32],
+    target_classes: &'a [u32],
+    order: Vec<NodeId>,
+    predecessors: Vec<Option<(NodeId, Dir)>>,
+    look_ahead: Option<LookAhead>,
+    edge_induced: bool,
+}
+
+/// The current partial mapping and the target terminal sets.
+pub(crate) struct State {
+    /// Target node of each pattern node, or `NULL`.
+    pub(crate) core_1: Vec<NodeId>,
+    core_2: Vec<NodeId>,
+    /// Number of mapped pairs.
+    pub(crate) depth: usize,
+    /// Depth at which each target node joined the in- and out-terminal
+    /// sets, or 0.
+    in_2: Vec<u32>,
+    out_2: Vec<u32>,
+    in_len: Vec<u32>,
+    out_len: Vec<u32>,
+    both_len: Vec<u32>,
+}
+
+/// What a run does with the solutions it reaches.
+struct Run<'s, 'v> {
+    start: Instant,
+    store_solutions: bool,
+    first_only: bool,
+    sink: &'s mut SolutionSink<'v>,
+    outcome: Outcome,
+}
+
+impl<'a> Search<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        pattern: &'a Adjacency<'a>,
+        target: &'a Adjacency<'a>,
+        pattern_classes: &'a [u32],
+        target_classes: &'a [u32],
+        classes: usize,
+        order: Vec<NodeId>,
+        look_ahead: bool,
+        edge_induced: bool,
+    ) -> Self {
+        let count = pattern.len();
+        let mut inserted = vec![false; count];
+        let (mut term_in, mut term_out) = (vec![false; count], vec![false; count]);
+        let mut predecessors = vec![None; count];
+        let mut tables = look_ahead.then(|| LookAhead {
+            term_in: Table::new(count, classes),
+            term_out: Table::new(count, classes),
+            new: Table::new(count, classes),
+            in_len: Table::new(count + 1, classes),
+            out_len: Table::new(count + 1, classes),
+            both_len: Table::new(count + 1, classes),
+        });
+
+        for (depth, &node) in order.iter().enumerate() {
+            inserted[node as usize] = true;
+            if let Some(t) = &mut tables {
+                let neighbours = pattern.predecessors(node).0.iter();
+                for &other in neighbours.chain(pattern.successors(node).0) {
+                    let (o, c) = (other as usize, pattern_classes[other as usize] as usize);
+                    if inserted[o] {
+                        continue;
+                    }
+                    if term_in[o] {
+                        t.term_in.row_mut(depth)[c] += 1;
+                    }
+                    if term_out[o] {
+                        t.term_out.row_mut(depth)[c] += 1;
+                    }
+                    if !term_in[o] && !term_out[o] {
+                        t.new.row_mut(depth)[c] += 1;
+                    }
+                }
+                for table in [&mut t.in_len, &mut t.out_len, &mut t.both_len] {
+                    table.cells.copy_within(
+                        depth * classes..(depth + 1) * classes,
+                        (depth + 1) * classes,
+                    );
+                }
+            }
+            let mut join = |other: NodeId, into_in: bool| {
+                let o = other as usize;
+                let (set, rest) = if into_in {
+                    (&mut term_in, &term_out)
+                } else {
+                    (&mut term_out, &term_in)
+                };
+                if set[o] {
+                    return false;
+                }
+                set[o] = true;
+                if let Some(t) = &mut tables {
+                    let c = pattern_classes[o] as usize;
+                    let len = if into_in {
+                        &mut t.in_len
+                    } else {
+                        &mut t.out_len
+                    };
+                    len.row_mut(depth + 1)[c] += 1;
+                    if rest[o] {
+                        t.both_len.row_mut(depth + 1)[c] += 1;
+                    }
+                }
+                true
+            };
+            join(node, true);
+            join(node, false);
+            for &other in pattern.predecessors(node).0 {
+                if join(other, true)
+                    && !inserted[other as usize]
+                    && predecessors[other as usize].is_none()
+                {
+                    predecessors[other as usize] = Some((node, Dir::In));
+                }
+            }
+            for &other in pattern.successors(node).0 {
+                if join(other, false)
+                    && !inserted[other as usize]
+                    && predecessors[other as usize].is_none()
+                {
+                    predecessors[other as usize] = Some((node, Dir::Out));
+                }
+            }
+        }
+
+        Self {
+            pattern,
+            target,
+            pattern_classes,
+            target_classes,
+            order,
+            predecessors,
+            look_ahead: tables,
+            edge_induced,
+        }
+    }
+
+    /// Find every solution, or only the first one, like
+    /// `MatchingEngine::FindAllMatchings` and `FindFirstMatching`, handing
+    /// each to `sink` as its visitor.
+    pub(super) fn run(
+        &self,
+        start: Instant,
+        store_solutions: bool,
+        first_only: bool,
+        sink: &mut SolutionSink<'_>,
+    ) -> Outcome {
+        let mut run = Run {
+            start,
+            store_solutions,
+            first_only,
+            sink,
+            outcome: Outcome::default(),
+        };
+        self.extend(&mut self.state(), &mut run);
+        run.outcome
+    }
+
+    /// The empty state, with no pair mapped.
+    pub(crate) fn state(&self) -> State {
+        let classes = self.look_ahead.as_ref().map_or(0, |t| t.in_len.classes);
+        State {
+            core_1: vec![NULL; self.pattern.len()],
+            core_2: vec![NULL; self.target.len()],
+            depth: 0,
+            in_2: vec![0; self.target.len()],
+            out_2: vec![0; self.target.len()],
+            in_len: vec![0; classes],
+            out_len: vec![0; classes],
+            both_len: vec![0; classes],
+        }
+    }
+
+    /// Pattern nodes in the order they are mapped.
+    pub(crate) fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// Explore the children of `state`; true once the search must stop.
+    fn extend(&self, state: &mut State, run: &mut Run) -> bool {
+        if state.depth == self.order.len() {
+            let outcome = &mut run.outcome;
+            if outcome.solutions == 0 {
+                outcome.first = Some(run.start.elapsed().as_secs_f64());
+            }
+            outcome.solutions += 1;
+            if run.store_solutions {
+                outcome.mappings.push(state.core_1.clone());
+            }
+            let stop = run.sink.visit(&state.core_1);
+            return run.first_only || stop;
+        }
+        if self.is_dead(state) {
+            return false;
+        }
+
+        let node = self.order[state.depth];
+        match self.predecessors[node as usize] {
+            Some((pred, dir)) => {
+                let image = state.core_1[pred as usize];
+                let candidates = match dir {
+                    Dir::In => self.target.predecessors(image).0,
+                    Dir::Out => self.target.successors(image).0,
+                };
+                for &n2 in candidates {
+                    if self.is_candidate(state, node, n2) && self.try_pair(state, run, node, n2) {
+                        return true;
+                    }
+                }
+            }
+            None => {
+                for n2 in 0..self.target.len() as NodeId {
+                    if self.is_candidate(state, node, n2) && self.try_pair(state, run, node, n2) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Extend `state` with `node1 -> node2` if feasible; true once the
+    /// search must stop.
+    fn try_pair(&self, state: &mut State, run: &mut Run, node1: NodeId, node2: NodeId) -> bool {
+        if !self.is_feasible(state, node1, node2) {
+            return false;
+        }
+        self.add_pair(state, node1, node2);
+        let stop = self.extend(state, run);
+        self.back_track(state, node1, node2);
+        stop
+    }
+
+    /// `VF3SubState::IsDead`: fewer target than pattern nodes in a
+    /// terminal set of some class.
+    fn is_dead(&self, state: &State) -> bool {
+        let Some(t) = &self.look_ahead else {
+            return false;
+        };
+        let depth = state.depth;
+        let exceeds =
+            |pattern: &[u32], target: &[u32]| pattern.iter().zip(target).any(|(p, t)| p > t);
+        exceeds(t.both_len.row(depth), &state.both_len)
+            || exceeds(t.out_len.row(depth), &state.out_len)
+            || exceeds(t.in_len.row(depth), &state.in_len)
+    }
+
+    /// Whether `node2` is an unmapped target node in the class of `node1`.
+    pub(crate) fn is_candidate(&self, state: &State, node1: NodeId, node2: NodeId) -> bool {
+        state.core_2[node2 as usize] == NULL
+            && self.target_classes[node2 as usize] == self.pattern_classes[node1 as usize]
+    }
+
+    fn is_feasible(&self, state: &State, node1: NodeId, node2: NodeId) -> bool {
+        let (pattern, target) = (self.pattern, self.target);
+        if pattern.predecessors(node1).0.len() > target.predecessors(node2).0.len()
+            || pattern.successors(node1).0.len() > target.successors(node2).0.len()
+            || !self.is_consistent(state, node1, node2)
+        {
+            return false;
+        }
+        let Some(t) = &self.look_ahead else {
+            return true;
+        };
+
+        let classes = t.in_len.classes;
+        let (mut term_in, mut term_out, mut new) = (
+            vec![0u32; classes],
+            vec![0u32; classes],
+            vec![0u32; classes],
+        );
+        for neighbours in [target.successors(node2).0, target.predecessors(node2).0] {
+            for &other2 in neighbours {
+                if state.core_2[other2 as usize] != NULL {
+                    continue;
+                }
+                let (o, c) = (
+                    other2 as usize,
+                    self.target_classes[other2 as usize] as usize,
+                );
+                if state.in_2[o] != 0 {
+                    term_in[c] += 1;
+                }
+                if state.out_2[o] != 0 {
+                    term_out[c] += 1;
+                }
+                if state.in_2[o] == 0 && state.out_2[o] == 0 {
+                    new[c] += 1;
+                }
+            }
+        }
+
+        let depth = state.depth;
+        let (term_in_1, term_out_1, new_1) = (
+            t.term_in.row(depth),
+            t.term_out.row(depth),
+            t.new.row(depth),
+        );
+        // The class counts add up to vf3lib's totals, so comparing them per
+        // class covers both of its checks.
+        if (0..classes).any(|c| term_in_1[c] > term_in[c] || term_out_1[c] > term_out[c]) {
+            return false;
+        }
+        if self.edge_induced {
+            (0..classes).all(|c| {
+                term_in_1[c] + term_out_1[c] + new_1[c] <= term_in[c] + term_out[c] + new[c]
+            })
+        } else {
+            (0..classes).all(|c| new_1[c] <= new[c])
+        }
+    }
+
+    /// Whether `node1 -> node2` agrees with the pairs mapped so far: equal
+    /// labels, every pattern arc between `node1` and a mapped node carried
+    /// onto an equally labelled target arc and, unless edge-induced, no
+    /// target arc between `node2` and a mapped node without a pattern arc.
+    pub(crate) fn is_consistent(&self, state: &State, node1: NodeId, node2: NodeId) -> bool {
+        let (pattern, target) = (self.pattern, self.target);
+        if pattern.label(node1) != target.label(node2)
+            || self.kept_arcs(state, node1, node2).any(|kept| !kept)
+        {
+            return false;
+        }
+        if self.edge_induced {
+            return true;
+        }
+        let outgoing = target.successors(node2).0.iter().all(|&other2| {
+            let other1 = state.core_2[other2 as usize];
+            other1 == NULL || pattern.arc(node1, other1).is_some()
+        });
+        let incoming = target.predecessors(node2).0.iter().all(|&other2| {
+            let other1 = state.core_2[other2 as usize];
+            other1 == NULL || pattern.arc(other1, node1).is_some()
+        });
+        outgoing && incoming
+    }
+
+    /// Pattern edges between `node1` and mapped nodes that `node1 -> node2`
+    /// carries onto equally labelled target edges. An undirected edge counts
+    /// once and needs both target arcs.
+    pub(crate) fn kept_edges(
+        &self,
+        state: &State,
+        node1: NodeId,
+        node2: NodeId,
+        undirected: bool,
+    ) -> usize {
+        if !undirected {
+            return self
+                .kept_arcs(state, node1, node2)
+                .filter(|&kept| kept)
+                .count();
+        }
+        let (succ, labels) = self.pattern.successors(node1);
+        succ.iter()
+            .zip(labels)
+            .filter(|&(&other1, &label)| {
+                let other2 = state.core_1[other1 as usize];
+                other2 != NULL
+                    && self.target.arc(node2, other2) == Some(label)
+                    && self.target.arc(other2, node2) == Some(label)
+            })
+            .count()
+    }
+
+    /// For each pattern arc between `node1` and a mapped node, whether
+    /// `node1 -> node2` carries it onto an equally labelled target arc.
+    fn kept_arcs<'s>(
+        &'s self,
+        state: &'s State,
+        node1: NodeId,
+        node2: NodeId,
+    ) -> impl Iterator<Item = bool> + 's {
+        let (pattern, target) = (self.pattern, self.target);
+        let image = move |other1: &NodeId| {
+            Some(state.core_1[*other1 as usize]).filter(|&other2| other2 != NULL)
+        };
+        let (succ, succ_labels) = pattern.successors(node1);
+        let (pred, pred_labels) = pattern.predecessors(node1);
+        let outgoing = succ
+            .iter()
+            .zip(succ_labels)
+            .filter_map(move |(other1, &label)| {
+                image(other1).map(|other2| target.arc(node2, other2) == Some(label))
+            });
+        let incoming = pred
+            .iter()
+            .zip(pred_labels)
+            .filter_map(move |(other1, &label)| {
+                image(other1).map(|other2| target.arc(other2, node2) == Some(label))
+            });
+        outgoing.chain(incoming)
+    }
+
+    pub(crate) fn add_pair(&self, state: &mut State, node1: NodeId, node2: NodeId) {
+        state.depth += 1;
+        state.core_1[node1 as usize] = node2;
+        state.core_2[node2 as usize] = node1;
+        if self.look_ahead.is_none() {
+            return;
+        }
+        let level = state.depth as u32;
+        let join = |state: &mut State, other: NodeId, into_in: bool| {
+            let (o, c) = (other as usize, self.target_classes[other as usize] as usize);
+            let (set, rest, len) = if into_in {
+                (&mut state.in_2, &state.out_2, &mut state.in_len)
+            } else {
+                (&mut state.out_2, &state.in_2, &mut state.out_len)
+            };
+            if set[o] == 0 {
+                set[o] = level;
+                len[c] += 1;
+                if rest[o] != 0 {
+                    state.both_len[c] += 1;
+                }
+            }
+        };
+        join(state, node2, true);
+        join(state, node2, false);
+        for &other in self.target.predecessors(node2).0 {
+            join(state, other, true);
+        }
+        for &other in self.target.successors(node2).0 {
+            join(state, other, false);
+        }
+    }
+
+    pub(crate) fn back_track(&self, state: &mut State, node1: NodeId, node2: NodeId) {
+        if self.look_ahead.is_some() {
+            let level = state.depth as u32;
+            let leave = |state: &mut State, other: NodeId, from_in: bool| {
+                let (o, c) = (other as usize, self.target_classes[other as usize] as usize);
+                let (set, rest, len) = if from_in {
+                    (&mut state.in_2, &state.out_2, &mut state.in_len)
+                } else {
+                    (&mut state.out_2, &state.in_2, &mut state.out_len)
+                };
+                if set[o] == level {
+                    set[o] = 0;
+                    len[c] -= 1;
+                    if rest[o] != 0 {
+                        state.both_len[c] -= 1;
+                    }
+                }
+            };
+            leave(state, node2, true);
+            leave(state, node2, false);
+            for &other in self.target.predecessors(node2).0 {
+                leave(state, other, true);
+            }
+            for &other in self.target.successors(node2).0 {
+                leave(state, other, false);
+            }
+        }
+        state.core_1[node1 as usize] = NULL;
+        state.core_2[node2 as usize] = NULL;
+        state.depth -= 1;
+    }
+}
@@ -0,0 +1,84 @@
+//! Counting occurrences instead of mappings for
+//! [`RunOptions::distinct_occurrences`](crate::RunOptions::distinct_occurrences).
+
+use std::collections::HashSet;
+
+use crate::{NodeId, ResultData, VF3Error, graph::Csr};
+
+/// Target nodes and arcs covered by a mapping.
+type Occurrence = (Vec<NodeId>, Vec<(NodeId, NodeId)>);
+
+fn occurrence(mapping: &[(NodeId, NodeId)], pattern: &Csr<'_>) -> Occurrence {
+    let image = |node: u32| mapping[node as usize].1;
+    let mut nodes: Vec<_> = mapping.iter().map(|&(_, t)| t).collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    let mut arcs = Vec::with_capacity(pattern.targets.len());
+    for from in 0..pattern.node_labels.len() {
+        let range = pattern.offsets[from] as usize..pattern.offsets[from + 1] as usize;
+        arcs.extend(
+            pattern.targets[range]
+                .iter()
+                .map(|&to| (image(from as u32), image(to))),
+        );
+    }
+    arcs.sort_unstable();
+    arcs.dedup();
+    (nodes, arcs)
+}
+
+/// Occurrences of the pattern reported so far.
+pub(crate) struct Seen<'p> {
+    pattern: &'p Csr<'p>,
+    occurrences: HashSet<Occurrence>,
+}
+
+impl<'p> Seen<'p> {
+    pub(crate) fn new(pattern: &'p Csr<'p>) -> Self {
+        Self {
+            pattern,
+            occurrences: HashSet::new(),
+        }
+    }
+
+    /// Record the occurrence of `mapping`; true if it is a new one.
+    pub(crate) fn insert(&mut self, mapping: &[(NodeId, NodeId)]) -> bool {
+        self.occurrences.insert(occurrence(mapping, self.pattern))
+    }
+}
+
+/// Keep the first mapping of every occurrence, with its violations.
+pub(crate) fn dedup(res: &mut ResultData, pattern: &Csr<'_>) {
+    let mut seen = Seen::new(pattern);
+    let keep: Vec<_> = res
+        .mappings
+        .iter()
+        .map(|mapping| seen.insert(mapping))
+        .collect();
+    let mut flags = keep.iter();
+    res.mappings
+        .retain(|_| *flags.next().expect("one flag per mapping"));
+    if !res.violations.is_empty() {
+        let mut flags = keep.iter();
+        res.violations
+            .retain(|_| *flags.next().expect("one flag per mapping"));
+    }
+}
+
+/// Turn an injective run's mapping count into an occurrence count.
+///
+/// Every occurrence is reached once per label-preserving automorphism of the
+/// pattern, so the count is divided by the number of automorphisms. A
+/// `first_only` run already stopped at a single occurrence.
+pub(crate) fn distinct(
+    mut res: ResultData,
+    pattern: &Csr<'_>,
+    first_only: bool,
+) -> Result<ResultData, VF3Error> {
+    if !first_only {
+        let order = crate::symmetry::group(pattern)?.order;
+        res.solutions /= u64::try_from(order).unwrap_or(u64::MAX);
+    }
+    dedup(&mut res, pattern);
+    Ok(res)
+}
@@ -0,0 +1,234 @@
+//! Running the matchers directly on petgraph graphs.
+
+use petgraph::visit::{
+    EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef,
+};
+
+use crate::{
+    Graph, ParallelOptions, ResultData, RunOptions, VF3Error, run_vf3_graphs, run_vf3l_graphs,
+    run_vf3p_graphs,
+};
+
+/// petgraph graph references accepted by [`run_vf3_petgraph`] and friends,
+/// such as `&Graph`, `&DiGraph`, `&UnGraph` and `&StableGraph`.
+pub trait PetgraphInput:
+    IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp
+{
+}
+
+impl<G> PetgraphInput for G where
+    G: IntoNodeReferences + IntoEdgeReferences + NodeIndexable + GraphProp
+{
+}
+
+/// Result of a run on petgraph graphs.
+#[derive(Debug, Clone)]
+pub struct PetgraphResult<P, T> {
+    /// Counts and timings. Its `mappings` use the compacted node ids of the
+    /// converted graphs; prefer [`PetgraphResult::mappings`].
+    pub result: ResultData,
+    /// Stored solutions as `(pattern, target)` petgraph node ids.
+    pub mappings: Vec<Vec<(P, T)>>,
+}
+
+/// Run VF3 on petgraph graphs.
+///
+/// Node and edge weights are compared with `node_eq` and `edge_eq`, which
+/// must be equivalence relations: weights are grouped into classes up front,
+/// the same way vf3lib's `NodeClassifier` groups node attributes, so the
+/// closures are called a few times per node and edge rather than during the
+/// search. Directedness is taken from each graph, as in [`run_vf3_graphs`];
+/// `opts.format`, the `undirected` flags and the per-format reader options
+/// are ignored.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::InvalidGraph`] if either graph has a self-loop, plus
+/// the errors of [`run_vf3_graphs`].
+pub fn run_vf3_petgraph<G1, G2, N, E>(
+    pattern: G1,
+    target: G2,
+    node_eq: impl FnMut(&N, &N) -> bool,
+    edge_eq: impl FnMut(&E, &E) -> bool,
+    opts: RunOptions,
+) -> Result<PetgraphResult<G1::NodeId, G2::NodeId>, VF3Error>
+where
+    G1: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+    G2: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+{
+    run_with(pattern, target, node_eq, edge_eq, |p, t| {
+        run_vf3_graphs(p, t, opts)
+    })
+}
+
+/// Run VF3L on petgraph graphs.
+///
+/// See [`run_vf3_petgraph`] for how weights are compared.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::InvalidGraph`] if either graph has a self-loop, plus
+/// the errors of [`run_vf3l_graphs`].
+pub fn run_vf3l_petgraph<G1, G2, N, E>(
+    pattern: G1,
+    target: G2,
+    node_eq: impl FnMut(&N, &N) -> bool,
+    edge_eq: impl FnMut(&E, &E) -> bool,
+    opts: RunOptions,
+) -> Result<PetgraphResult<G1::NodeId, G2::NodeId>, VF3Error>
+where
+    G1: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+    G2: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+{
+    run_with(pattern, target, node_eq, edge_eq, |p, t| {
+        run_vf3l_graphs(p, t, opts)
+    })
+}
+
+/// Run VF3P on petgraph graphs (Linux only).
+///
+/// See [`run_vf3_petgraph`] for how weights are compared.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::InvalidGraph`] if either graph has a self-loop, plus
+/// the errors of [`run_vf3p_graphs`].
+pub fn run_vf3p_petgraph<G1, G2, N, E>(
+    pattern: G1,
+    target: G2,
+    node_eq: impl FnMut(&N, &N) -> bool,
+    edge_eq: impl FnMut(&E, &E) -> bool,
+    opts: RunOptions,
+    par: ParallelOptions,
+) -> Result<PetgraphResult<G1::NodeId, G2::NodeId>, VF3Error>
+where
+    G1: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+    G2: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+{
+    run_with(pattern, target, node_eq, edge_eq, |p, t| {
+        run_vf3p_graphs(p, t, opts, par)
+    })
+}
+
+fn run_with<G1, G2, N, E>(
+    pattern: G1,
+    target: G2,
+    node_eq: impl FnMut(&N, &N) -> bool,
+    edge_eq: impl FnMut(&E, &E) -> bool,
+    run: impl FnOnce(&Graph, &Graph) -> Result<ResultData, VF3Error>,
+) -> Result<PetgraphResult<G1::NodeId, G2::NodeId>, VF3Error>
+where
+    G1: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+    G2: PetgraphInput<NodeWeight = N, EdgeWeight = E>,
+{
+    let (pattern_nodes, pattern_edges) = collect(pattern);
+    let (target_nodes, target_edges) = collect(target);
+    let mut node_classes = Classes::new(node_eq);
+    let mut edge_classes = Classes::new(edge_eq);
+    let (p, pattern_ids) = convert(
+        pattern,
+        &pattern_nodes,
+        &pattern_edges,
+        &mut node_classes,
+        &mut edge_classes,
+    )?;
+    let (t, target_ids) = convert(
+        target,
+        &target_nodes,
+        &target_edges,
+        &mut node_classes,
+        &mut edge_classes,
+    )?;
+
+    let result = run(&p, &t)?;
+    let mappings = result
+        .mappings
+        .iter()
+        .map(|mapping| {
+            mapping
+                .iter()
+                .map(|&(p, t)| (pattern_ids[p as usize], target_ids[t as usize]))
+                .collect()
+        })
+        .collect();
+    Ok(PetgraphResult { result, mappings })
+}
+
+type Collected<G> = (
+    Vec<<G as IntoNodeReferences>::NodeRef>,
+    Vec<<G as IntoEdgeReferences>::EdgeRef>,
+);
+
+fn collect<G: PetgraphInput>(graph: G) -> Collected<G> {
+    (
+        graph.node_references().collect(),
+        graph.edge_references().collect(),
+    )
+}
+
+fn convert<'w, G: PetgraphInput>(
+    graph: G,
+    nodes: &'w [G::NodeRef],
+    edges: &'w [G::EdgeRef],
+    node_classes: &mut Classes<
+        'w,
+        G::NodeWeight,
+        impl FnMut(&G::NodeWeight, &G::NodeWeight) -> bool,
+    >,
+    edge_classes: &mut Classes<
+        'w,
+        G::EdgeWeight,
+        impl FnMut(&G::EdgeWeight, &G::EdgeWeight) -> bool,
+    >,
+) -> Result<(Graph, Vec<G::NodeId>), VF3Error> {
+    let mut converted = if graph.is_directed() {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    let mut index = vec![None; graph.node_bound()];
+    let mut ids = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let id = converted.add_node(node_classes.class(node.weight()));
+        index[graph.to_index(node.id())] = Some(id);
+        ids.push(node.id());
+    }
+    for edge in edges {
+        let endpoint = |node| index[graph.to_index(node)].expect("edge endpoints are graph nodes");
+        let (from, to) = (endpoint(edge.source()), endpoint(edge.target()));
+        if from == to {
+            return Err(VF3Error::InvalidGraph {
+                message: format!("self-loop on node {}", graph.to_index(edge.source())),
+            });
+        }
+        converted.add_labeled_edge(from, to, edge_classes.class(edge.weight()));
+    }
+    Ok((converted, ids))
+}
+
+/// Groups weights into integer classes with a user-supplied equality.
+struct Classes<'w, T, F> {
+    representatives: Vec<&'w T>,
+    eq: F,
+}
+
+impl<'w, T, F: FnMut(&T, &T) -> bool> Classes<'w, T, F> {
+    fn new(eq: F) -> Self {
+        Self {
+            representatives: Vec::new(),
+            eq,
+        }
+    }
+
+    fn class(&mut self, weight: &'w T) -> i32 {
+        if let Some(pos) = self
+            .representatives
+            .iter()
+            .position(|rep| (self.eq)(rep, weight))
+        {
+            return pos as i32;
+        }
+        self.representatives.push(weight);
+        (self.representatives.len() - 1) as i32
+    }
+}
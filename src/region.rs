@@ -0,0 +1,169 @@
+//! Target regions covered by matches, for extracting matched subgraphs and
+//! highlighting motif regions.
+
+use std::collections::BTreeMap;
+
+use crate::{Graph, Mapping, NodeId};
+
+/// Target nodes and edges covered by one or more matches, with the number of
+/// matches covering each.
+///
+/// A node-induced match covers every target edge between its image nodes;
+/// an edge-induced match only the images of the pattern's edges. Edges of an
+/// undirected target are keyed once, as `(from, to)` with `from < to`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchRegion {
+    /// Covered target nodes and the number of matches covering each.
+    pub nodes: BTreeMap<NodeId, u64>,
+    /// Covered target edges and the number of matches covering each.
+    pub edges: BTreeMap<(NodeId, NodeId), u64>,
+}
+
+impl MatchRegion {
+    /// Create an empty region.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The region covered by a single match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mapping` names a node outside `pattern` or `target`.
+    pub fn of_match(
+        pattern: &Graph,
+        target: &Graph,
+        mapping: &[(NodeId, NodeId)],
+        edge_induced: bool,
+    ) -> Self {
+        let mut region = Self::new();
+        region.add(pattern, target, mapping, edge_induced);
+        region
+    }
+
+    /// The union of the regions covered by `mappings`, such as the stored
+    /// mappings of a [`ResultData`](crate::ResultData).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mapping names a node outside `pattern` or `target`.
+    pub fn union<'m>(
+        pattern: &Graph,
+        target: &Graph,
+        mappings: impl IntoIterator<Item = &'m Mapping>,
+        edge_induced: bool,
+    ) -> Self {
+        let mut region = Self::new();
+        for mapping in mappings {
+            region.add(pattern, target, mapping, edge_induced);
+        }
+        region
+    }
+
+    /// Add the region covered by one more match, for instance from a
+    /// solution callback.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mapping` names a node outside `pattern` or `target`.
+    pub fn add(
+        &mut self,
+        pattern: &Graph,
+        target: &Graph,
+        mapping: &[(NodeId, NodeId)],
+        edge_induced: bool,
+    ) {
+        let mut images = vec![None; pattern.node_count()];
+        for &(p, t) in mapping {
+            assert!((t as usize) < target.node_count(), "node {t} out of range");
+            images[p as usize] = Some(t);
+        }
+        let mut nodes: Vec<NodeId> = mapping.iter().map(|&(_, t)| t).collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+
+        let mut edges = Vec::new();
+        if edge_induced {
+            for &(p, from) in mapping {
+                for &q in pattern.successors(p) {
+                    if let Some(to) = images[q as usize]
+                        && target.has_edge(from, to)
+                    {
+                        edges.push(key(target, from, to));
+                    }
+                }
+            }
+        } else {
+            for &from in &nodes {
+                for &to in target.successors(from) {
+                    if nodes.binary_search(&to).is_ok() {
+                        edges.push(key(target, from, to));
+                    }
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        for node in nodes {
+            *self.nodes.entry(node).or_default() += 1;
+        }
+        for edge in edges {
+            *self.edges.entry(edge).or_default() += 1;
+        }
+    }
+
+    /// Whether `node` is covered.
+    pub fn contains_node(&self, node: NodeId) -> bool {
+        self.nodes.contains_key(&node)
+    }
+
+    /// Whether the target edge from `from` to `to` is covered, in either
+    /// direction if `target` is undirected.
+    pub fn contains_edge(&self, target: &Graph, from: NodeId, to: NodeId) -> bool {
+        self.edges.contains_key(&key(target, from, to))
+    }
+
+    /// The covered part of `target` as a graph of its own.
+    ///
+    /// Node `i` is the `i`-th covered target node in id order and keeps its
+    /// label; covered edges keep theirs. Every node is named after its target
+    /// node, by the target's node name or else by its id, so the subgraph can
+    /// be written out and traced back to the target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region was built from a different target.
+    pub fn to_graph(&self, target: &Graph) -> Graph {
+        let mut graph = if target.is_directed() {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        let mut index = BTreeMap::new();
+        for &node in self.nodes.keys() {
+            let id = graph.add_node(target.node_label(node));
+            match target.node_name(node) {
+                Some(name) => graph.set_node_name(id, name),
+                None => graph.set_node_name(id, node.to_string()),
+            }
+            index.insert(node, id);
+        }
+        for &(from, to) in self.edges.keys() {
+            let label = target
+                .edge_label(from, to)
+                .expect("covered edge is in the target");
+            graph.add_labeled_edge(index[&from], index[&to], label);
+        }
+        graph
+    }
+}
+
+/// Key of a target edge: as given when directed, lower node first otherwise.
+fn key(target: &Graph, from: NodeId, to: NodeId) -> (NodeId, NodeId) {
+    if target.is_directed() || from < to {
+        (from, to)
+    } else {
+        (to, from)
+    }
+}
@@ -0,0 +1,147 @@
+//! Serialized forms of types whose fields are not their public interface.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Graph, LabelDictionary, NodeId, VF3Error, labels::Labelled};
+
+/// A [`Graph`] as it is serialized: node labels by id, every edge once as
+/// `[from, to, label]` (with `from < to` when undirected) and the node names
+/// if the graph has them.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GraphData {
+    directed: bool,
+    labels: Vec<i32>,
+    edges: Vec<(NodeId, NodeId, i32)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    names: Option<Vec<String>>,
+}
+
+impl From<Graph> for GraphData {
+    fn from(graph: Graph) -> Self {
+        let nodes = 0..graph.node_count() as NodeId;
+        let directed = graph.is_directed();
+        let graph = &graph;
+        Self {
+            directed,
+            labels: nodes.clone().map(|node| graph.node_label(node)).collect(),
+            edges: nodes
+                .flat_map(|from| {
+                    graph
+                        .successors(from)
+                        .iter()
+                        .zip(graph.successor_labels(from))
+                        .filter(move |&(&to, _)| directed || from < to)
+                        .map(move |(&to, &label)| (from, to, label))
+                })
+                .collect(),
+            names: graph.node_names().map(<[String]>::to_vec),
+        }
+    }
+}
+
+impl TryFrom<GraphData> for Graph {
+    type Error = VF3Error;
+
+    /// Rebuild the graph, rejecting the edges [`Graph::add_labeled_edge`]
+    /// would panic on instead of trusting the input.
+    fn try_from(data: GraphData) -> Result<Self, VF3Error> {
+        let invalid = |message: String| VF3Error::InvalidGraph { message };
+        if data.labels.len() > NodeId::MAX as usize {
+            return Err(invalid(format!("{} nodes is too many", data.labels.len())));
+        }
+        let mut graph = if data.directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for label in data.labels {
+            graph.add_node(label);
+        }
+        let nodes = graph.node_count();
+        for (from, to, label) in data.edges {
+            if from as usize >= nodes || to as usize >= nodes {
+                return Err(invalid(format!(
+                    "edge ({from}, {to}) out of range for graph with {nodes} nodes"
+                )));
+            }
+            if from == to {
+                return Err(invalid(format!("self-loop on node {from}")));
+            }
+            graph.add_labeled_edge(from, to, label);
+        }
+        if let Some(names) = data.names {
+            if names.len() != nodes {
+                return Err(invalid(format!(
+                    "{} names for graph with {nodes} nodes",
+                    names.len()
+                )));
+            }
+            for (node, name) in names.into_iter().enumerate() {
+                graph.set_node_name(node as NodeId, name);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// A [`LabelDictionary`] as it is serialized: its strings in label order from
+/// label `1`, and the kind of label the nodes and edges read through it took,
+/// so that a restored dictionary still refuses to mix the two.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LabelDictionaryData {
+    labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nodes: Option<LabelKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    edges: Option<LabelKind>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LabelKind {
+    Integer,
+    String,
+}
+
+impl From<LabelDictionary> for LabelDictionaryData {
+    fn from(labels: LabelDictionary) -> Self {
+        let kind = |labelled| {
+            labels.kind(labelled).map(|integer| {
+                if integer {
+                    LabelKind::Integer
+                } else {
+                    LabelKind::String
+                }
+            })
+        };
+        Self {
+            nodes: kind(Labelled::Node),
+            edges: kind(Labelled::Edge),
+            labels: (1..=labels.len() as i32)
+                .map(|label| labels.name(label).unwrap_or_default().to_owned())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<LabelDictionaryData> for LabelDictionary {
+    type Error = String;
+
+    /// Rebuild the dictionary, rejecting repeated strings, which would shift
+    /// the label of every string after them.
+    fn try_from(data: LabelDictionaryData) -> Result<Self, String> {
+        let mut labels = LabelDictionary::new();
+        for name in &data.labels {
+            if labels.get(name).is_some() {
+                return Err(format!("label {name:?} is listed twice"));
+            }
+            labels.intern(name);
+        }
+        for (labelled, kind) in [(Labelled::Node, data.nodes), (Labelled::Edge, data.edges)] {
+            if let Some(kind) = kind {
+                labels.set_kind(labelled, matches!(kind, LabelKind::Integer))?;
+            }
+        }
+        Ok(labels)
+    }
+}
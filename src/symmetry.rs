@@ -0,0 +1,173 @@
+//! Automorphism groups computed with the matching engine.
+
+use std::borrow::Cow;
+
+use crate::{GraphInput, NodeId, VF3Error, graph::Csr, visit::SolutionSink};
+
+/// Automorphism group of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Automorphisms {
+    /// Permutations generating the group, each giving the image of every
+    /// node. The identity is left out, so an asymmetric graph has none.
+    pub generators: Vec<Vec<NodeId>>,
+    /// Node orbits, each sorted and ordered by its smallest node.
+    pub orbits: Vec<Vec<NodeId>>,
+    /// Number of automorphisms, saturating at `u128::MAX`.
+    pub order: u128,
+}
+
+impl Automorphisms {
+    /// Orbit containing `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of range.
+    pub fn orbit_of(&self, node: NodeId) -> &[NodeId] {
+        self.orbits
+            .iter()
+            .find(|orbit| orbit.binary_search(&node).is_ok())
+            .expect("node out of range")
+    }
+}
+
+/// Compute generators, node orbits and the order of the automorphism group,
+/// respecting node labels, edge labels and edge directions.
+///
+/// Nodes are fixed one at a time, and a first-solution VF3L search is run
+/// only for images the generators found so far do not already reach, so the
+/// engine runs at most once per pair of nodes rather than once per
+/// automorphism.
+///
+/// # Errors
+///
+/// Returns [`VF3Error::ExecutionFailed`] if the C++ algorithm fails.
+pub fn automorphisms(graph: &impl GraphInput) -> Result<Automorphisms, VF3Error> {
+    group(&graph.csr())
+}
+
+pub(crate) fn group(csr: &Csr<'_>) -> Result<Automorphisms, VF3Error> {
+    let count = csr.node_labels.len();
+    let mut labels = dense_labels(&csr.node_labels);
+    let mut next_label = labels.iter().max().map_or(0, |&l| l + 1);
+    // Out- and in-degrees, which every automorphism preserves.
+    let mut degrees: Vec<_> = (0..count)
+        .map(|n| (csr.offsets[n + 1] - csr.offsets[n], 0u32))
+        .collect();
+    for &to in csr.targets.iter() {
+        degrees[to as usize].1 += 1;
+    }
+
+    let mut generators = Vec::new();
+    let mut order: u128 = 1;
+    for base in 0..count {
+        // Orbit of `base` in the stabiliser of the nodes fixed so far.
+        let mut level = Vec::new();
+        let mut orbit = vec![base as NodeId];
+        for candidate in base + 1..count {
+            if labels[candidate] != labels[base]
+                || degrees[candidate] != degrees[base]
+                || orbit.contains(&(candidate as NodeId))
+            {
+                continue;
+            }
+            let (mut pattern, mut target) = (labels.clone(), labels.clone());
+            pattern[base] = next_label;
+            target[candidate] = next_label;
+            if let Some(perm) = find(csr, pattern, target)? {
+                level.push(perm);
+                orbit = closure(base as NodeId, &level);
+            }
+        }
+        order = order.saturating_mul(orbit.len() as u128);
+        generators.extend(level);
+        labels[base] = next_label;
+        next_label += 1;
+    }
+
+    Ok(Automorphisms {
+        orbits: orbits(count, &generators),
+        generators,
+        order,
+    })
+}
+
+/// Relabel with `0..k` so fresh labels can be appended without overflow.
+fn dense_labels(labels: &[i32]) -> Vec<i32> {
+    let mut distinct = labels.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+    labels
+        .iter()
+        .map(|l| distinct.binary_search(l).expect("label present") as i32)
+        .collect()
+}
+
+/// Find one automorphism mapping the nodes of `pattern` labels to nodes of
+/// equal `target` labels.
+fn find(
+    csr: &Csr<'_>,
+    pattern: Vec<i32>,
+    target: Vec<i32>,
+) -> Result<Option<Vec<NodeId>>, VF3Error> {
+    let with_labels = |labels: Vec<i32>| Csr {
+        directed: csr.directed,
+        node_labels: Cow::Owned(labels),
+        offsets: Cow::Borrowed(&csr.offsets),
+        targets: Cow::Borrowed(&csr.targets),
+        edge_labels: Cow::Borrowed(&csr.edge_labels),
+    };
+    let (pattern, target) = (with_labels(pattern), with_labels(target));
+    let res = crate::vf3ffi::run_vf3l_graph(
+        &pattern.ffi(),
+        &target.ffi(),
+        true,
+        true,
+        false,
+        0.0,
+        false,
+        &mut SolutionSink::none(),
+    );
+    if res.status != 0 {
+        return Err(VF3Error::ExecutionFailed { code: res.status });
+    }
+    Ok(res.mappings.into_iter().next().map(|sol| sol.targets))
+}
+
+/// Nodes reachable from `node` under `generators`.
+fn closure(node: NodeId, generators: &[Vec<NodeId>]) -> Vec<NodeId> {
+    let Some(count) = generators.first().map(Vec::len) else {
+        return vec![node];
+    };
+    let mut seen = vec![false; count];
+    seen[node as usize] = true;
+    let mut orbit = vec![node];
+    let mut idx = 0;
+    while let Some(&current) = orbit.get(idx) {
+        for perm in generators {
+            let image = perm[current as usize];
+            if !std::mem::replace(&mut seen[image as usize], true) {
+                orbit.push(image);
+            }
+        }
+        idx += 1;
+    }
+    orbit
+}
+
+fn orbits(count: usize, generators: &[Vec<NodeId>]) -> Vec<Vec<NodeId>> {
+    let mut seen = vec![false; count];
+    let mut orbits = Vec::new();
+    for node in 0..count {
+        if seen[node] {
+            continue;
+        }
+        let mut orbit = closure(node as NodeId, generators);
+        orbit.sort_unstable();
+        for &n in &orbit {
+            seen[n as usize] = true;
+        }
+        orbits.push(orbit);
+    }
+    orbits
+}
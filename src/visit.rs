@@ -0,0 +1,91 @@
+//! The per-solution hook shared by the matchers.
+
+use std::{
+    any::Any,
+    ops::ControlFlow,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{NodeId, graph::Csr, occurrences::Seen};
+
+/// A solution callback of the `run_*_graphs_with` runners.
+pub(crate) type Visit<'a, 'f> = &'a mut (dyn FnMut(&[(NodeId, NodeId)]) -> ControlFlow<()> + 'f);
+
+/// The callback a [`SolutionSink`] adapts a [`Visit`] into.
+type Callback<'a> = Box<dyn FnMut(&[NodeId]) -> bool + 'a>;
+
+/// Callback a matcher hands each solution to as it finds it, with the target
+/// node of each pattern node; returning `true` stops the search.
+///
+/// The cxx bridge calls it from C++, so a panic in the callback is caught,
+/// stops the search and is resumed by [`SolutionSink::finish`] once the
+/// matcher has returned.
+pub(crate) struct SolutionSink<'a> {
+    callback: Option<Callback<'a>>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> SolutionSink<'a> {
+    /// A sink for runs that only count or store solutions.
+    pub(crate) fn none() -> Self {
+        Self {
+            callback: None,
+            panic: None,
+        }
+    }
+
+    /// Adapt `visit`, if any, to the matchers: pair each target node with
+    /// its pattern node and, for distinct occurrences, skip mappings of an
+    /// occurrence already reported. The reported occurrences are kept for the
+    /// whole run.
+    pub(crate) fn new<'f: 'a>(
+        visit: Option<Visit<'a, 'f>>,
+        pattern: &'a Csr<'a>,
+        distinct: bool,
+    ) -> Self {
+        let Some(visit) = visit else {
+            return Self::none();
+        };
+        let mut seen = distinct.then(|| Seen::new(pattern));
+        let mut mapping = Vec::with_capacity(pattern.node_labels.len());
+        Self {
+            callback: Some(Box::new(move |targets: &[NodeId]| {
+                mapping.clear();
+                mapping.extend((0..).zip(targets.iter().copied()));
+                if seen.as_mut().is_some_and(|seen| !seen.insert(&mapping)) {
+                    return false;
+                }
+                visit(&mapping).is_break()
+            })),
+            panic: None,
+        }
+    }
+
+    /// Whether solutions need to be handed over at all, asked by the C++
+    /// visitor before it copies one out of the search state.
+    #[cfg(vf3_cxx)]
+    pub(crate) fn enabled(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    pub(crate) fn visit(&mut self, targets: &[NodeId]) -> bool {
+        let Some(callback) = self.callback.as_mut() else {
+            return false;
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| callback(targets))) {
+            Ok(stop) => stop,
+            Err(payload) => {
+                self.panic = Some(payload);
+                self.callback = None;
+                true
+            }
+        }
+    }
+
+    /// Resume a panic raised by the callback.
+    pub(crate) fn finish(self) {
+        if let Some(payload) = self.panic {
+            panic::resume_unwind(payload);
+        }
+    }
+}
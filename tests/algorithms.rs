@@ -61,7 +61,7 @@ fn vf3_multiple_small_graphs() {
             target.to_string_lossy().as_ref(),
             opts,
         )
-        .expect(&format!("VF3 failed for {}", pattern_name));
+        .unwrap_or_else(|e| panic!("VF3 failed for {}: {e:?}", pattern_name));
         assert!(
             res.solutions >= 1,
             "No solutions found for {}",
@@ -90,7 +90,7 @@ fn vf3l_sparse_graphs() {
             target.to_string_lossy().as_ref(),
             opts,
         )
-        .expect(&format!("VF3L failed for {}", pattern_name));
+        .unwrap_or_else(|e| panic!("VF3L failed for {}: {e:?}", pattern_name));
         assert!(
             res.solutions >= 1,
             "No solutions found for {}",
@@ -121,7 +121,7 @@ fn vf3_medium_si2_datasets() {
             target.to_string_lossy().as_ref(),
             opts,
         )
-        .expect(&format!("VF3 failed for {}", pattern_name));
+        .unwrap_or_else(|e| panic!("VF3 failed for {}: {e:?}", pattern_name));
         assert!(
             res.solutions >= 1,
             "No solutions found for {}",
@@ -170,7 +170,7 @@ fn vf3_non_isomorphic_pairs() {
             target.to_string_lossy().as_ref(),
             opts,
         )
-        .expect(&format!("VF3 failed for {}", pattern_name));
+        .unwrap_or_else(|e| panic!("VF3 failed for {}: {e:?}", pattern_name));
         // These might still have subgraph isomorphisms, just checking it doesn't crash
     }
 }
@@ -201,7 +201,7 @@ fn vf3p_parallel_small_graphs() {
             opts,
             par.clone(),
         )
-        .expect(&format!("VF3P failed for {}", pattern_name));
+        .unwrap_or_else(|e| panic!("VF3P failed for {}: {e:?}", pattern_name));
         assert!(
             res.solutions >= 1,
             "No solutions found for {}",
@@ -0,0 +1,102 @@
+//! Results both backends must reproduce.
+//!
+//! The solution counts were recorded with the vendored C++ vf3lib. CI runs
+//! this file with default features and with `--no-default-features`, so the
+//! pure-Rust backend is held to the same numbers.
+
+mod common;
+
+use std::{fs::File, io::BufReader};
+
+use common::fixture_pair;
+use vf3lib_rs::{Mapping, RunOptions, formats::grf, run_vf3, run_vf3_graphs, run_vf3l};
+
+/// Solution counts for directed node-induced, directed edge-induced,
+/// undirected node-induced and undirected edge-induced matching.
+const COUNTS: &[(&str, &str, [u64; 4])] = &[
+    ("bvg1.sub.grf", "bvg1.grf", [8, 22, 168, 192]),
+    ("bvg1_2.sub.grf", "bvg1.grf", [0, 0, 0, 0]),
+    ("bvg1_3.sub.grf", "bvg1.grf", [0, 0, 0, 0]),
+    ("bvg1_4.sub.grf", "bvg1.grf", [0, 0, 0, 0]),
+    ("bvg2.sub.grf", "bvg2.grf", [1, 2, 1396, 4564]),
+    ("bvg3.sub.grf", "bvg3.grf", [25, 52, 928, 1888]),
+    ("m2d1.sub.grf", "m2d1.grf", [5, 6, 128, 144]),
+    ("m2d2.sub.grf", "m2d2.grf", [1, 1, 24, 24]),
+    ("rand1.sub.grf", "rand1.grf", [3, 5, 580, 820]),
+    ("rand2.sub.grf", "rand2.grf", [5, 5, 94, 94]),
+    ("rand3.sub.grf", "rand3.grf", [36, 108, 534, 1002]),
+    ("rand4.sub.grf", "rand1.grf", [0, 0, 0, 0]),
+    (
+        "si2_b03_m400_37.sub.grf",
+        "si2_b03_m400_37.grf",
+        [1, 1, 800, 800],
+    ),
+];
+
+fn modes() -> impl Iterator<Item = RunOptions> {
+    [(false, false), (false, true), (true, false), (true, true)]
+        .into_iter()
+        .map(|(undirected, edge_induced)| RunOptions {
+            undirected,
+            edge_induced,
+            store_solutions: true,
+            repetition_time_limit: 0.0,
+            ..Default::default()
+        })
+}
+
+fn sorted(mut mappings: Vec<Mapping>) -> Vec<Mapping> {
+    mappings.sort();
+    mappings
+}
+
+#[test]
+fn fixture_counts_match_vf3lib() {
+    for &(pattern, target, counts) in COUNTS {
+        let (pattern_path, target_path) = fixture_pair(pattern, target);
+        for (opts, expected) in modes().zip(counts) {
+            let full = run_vf3(&pattern_path, &target_path, opts.clone()).unwrap();
+            let light = run_vf3l(&pattern_path, &target_path, opts.clone()).unwrap();
+            let mode = (opts.undirected, opts.edge_induced);
+            assert_eq!(full.solutions, expected, "VF3 {pattern} {mode:?}");
+            assert_eq!(light.solutions, expected, "VF3L {pattern} {mode:?}");
+            assert_eq!(full.mappings.len() as u64, expected);
+            assert_eq!(
+                sorted(full.mappings),
+                sorted(light.mappings),
+                "{pattern} {mode:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn first_solution_is_the_first_of_all() {
+    for &(pattern, target, _) in COUNTS {
+        let (pattern_path, target_path) = fixture_pair(pattern, target);
+        for opts in modes() {
+            let all = run_vf3(&pattern_path, &target_path, opts.clone()).unwrap();
+            let first = RunOptions {
+                first_only: true,
+                ..opts
+            };
+            let first = run_vf3(&pattern_path, &target_path, first).unwrap();
+            assert_eq!(first.mappings.first(), all.mappings.first(), "{pattern}");
+        }
+    }
+}
+
+#[test]
+fn file_and_in_memory_runs_agree() {
+    // The path runners hand `.grf` files to the backend's own loader.
+    for &(pattern, target, counts) in COUNTS {
+        let (pattern_path, target_path) = fixture_pair(pattern, target);
+        for (opts, expected) in modes().zip(counts) {
+            let read = |path: &str| {
+                grf::read(BufReader::new(File::open(path).unwrap()), opts.undirected).unwrap()
+            };
+            let res = run_vf3_graphs(&read(&pattern_path), &read(&target_path), opts.clone());
+            assert_eq!(res.unwrap().solutions, expected, "{pattern}");
+        }
+    }
+}
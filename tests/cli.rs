@@ -0,0 +1,155 @@
+//! The `vf3` command-line tool (`cli` feature).
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use common::{fixture_pair, fixture_path, unique_temp_dir};
+use vf3lib_rs::{Graph, GraphFormat, NodeId, RunOptions, run_vf3};
+
+/// Run `vf3` and return its exit code and standard output.
+fn vf3(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_vf3"))
+        .args(args)
+        .output()
+        .expect("Failed to run vf3");
+    let stdout = String::from_utf8(output.stdout).expect("Output is not UTF-8");
+    (output.status.code().expect("vf3 was killed"), stdout)
+}
+
+#[test]
+fn match_prints_solution_strings() {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let expected = run_vf3(&pattern, &target, opts).unwrap();
+
+    let (code, stdout) = vf3(&["match", &pattern, &target, "-r", "0"]);
+    assert_eq!(code, 0);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), expected.mappings.len());
+    let first: String = expected.mappings[0]
+        .iter()
+        .map(|(p, t)| format!("{p},{t}:"))
+        .collect();
+    assert_eq!(lines[0], first);
+
+    let (code, stdout) = vf3(&["count", &pattern, &target, "-r", "0", "-u", "-e"]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.split_whitespace().next(), Some("192"));
+}
+
+#[test]
+fn json_output() {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let (code, stdout) = vf3(&["match", &pattern, &target, "-r", "0", "--json"]);
+    assert_eq!(code, 0);
+    let value: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(value["solutions"], 8);
+    assert_eq!(value["mappings"].as_array().unwrap().len(), 8);
+    assert_eq!(value["mappings"][0][1], serde_json::json!([1, 2]));
+
+    let (code, stdout) = vf3(&["count", &pattern, &target, "-r", "0", "--json"]);
+    assert_eq!(code, 0);
+    let value: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(value["solutions"], 8);
+    assert_eq!(value["mappings"], serde_json::json!([]));
+    // The output is the library's serde form of the result.
+    let res: vf3lib_rs::ResultData = serde_json::from_value(value).unwrap();
+    assert_eq!(res.solutions, 8);
+}
+
+#[test]
+fn first_and_isomorphic_exit_codes() {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let (code, stdout) = vf3(&["first", &pattern, &target, "-r", "0"]);
+    assert_eq!((code, stdout.lines().count()), (0, 1));
+
+    let missing = fixture_path("bvg1_2.sub.grf");
+    let (code, stdout) = vf3(&["first", &missing, &target, "-r", "0", "--json"]);
+    assert_eq!(
+        (code, stdout.trim()),
+        (1, r#"{"found":false,"mapping":null}"#)
+    );
+
+    let (code, _) = vf3(&["isomorphic", &target, &target, "-r", "0"]);
+    assert_eq!(code, 0);
+    let (code, _) = vf3(&["isomorphic", &pattern, &target, "-r", "0"]);
+    assert_eq!(code, 1);
+
+    let (code, stdout) = vf3(&["count", "/no/such/file.grf", &target, "-r", "0"]);
+    assert_eq!((code, stdout.as_str()), (2, ""));
+}
+
+#[test]
+fn named_mappings_use_node_names() {
+    let dir = unique_temp_dir("cli_names");
+    let pattern = dir.join("pattern.edges");
+    let target = dir.join("target.edges");
+    fs::write(&pattern, "a b\nb c\n").unwrap();
+    fs::write(&target, "x y\ny z\n").unwrap();
+    let (code, stdout) = vf3(&[
+        "match",
+        pattern.to_str().unwrap(),
+        target.to_str().unwrap(),
+        "-f",
+        "edge",
+        "--string-ids",
+        "-r",
+        "0",
+    ]);
+    assert_eq!((code, stdout.as_str()), (0, "a,x:b,y:c,z:\n"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+/// Equal nodes, labels and edges, whatever the node names.
+fn same_graph(a: &Graph, b: &Graph) -> bool {
+    a.is_directed() == b.is_directed()
+        && a.node_count() == b.node_count()
+        && (0..a.node_count() as NodeId).all(|node| {
+            a.node_label(node) == b.node_label(node)
+                && a.successors(node) == b.successors(node)
+                && a.successor_labels(node) == b.successor_labels(node)
+        })
+}
+
+#[test]
+fn convert_round_trips() {
+    let dir = unique_temp_dir("cli_convert");
+    let source = fixture_path("bvg1.grf");
+    let original = Graph::from_bytes(&fs::read(&source).unwrap(), GraphFormat::VFLegacy).unwrap();
+    for (to, format) in [
+        ("csr", GraphFormat::Csr),
+        ("graphml", GraphFormat::GraphML),
+        ("vf", GraphFormat::VFLegacy),
+    ] {
+        let output = dir.join(format!("bvg1.{to}"));
+        let output = output.to_str().unwrap();
+        let (code, _) = vf3(&["convert", &source, output, "--to", to]);
+        assert_eq!(code, 0, "{to}");
+        let converted = Graph::from_bytes(&fs::read(output).unwrap(), format).unwrap();
+        assert!(same_graph(&converted, &original), "{to}");
+    }
+
+    let (code, stdout) = vf3(&["convert", &source, "-", "--to", "graph6", "-u"]);
+    assert_eq!(code, 0);
+    let graph = Graph::from_bytes(stdout.as_bytes(), GraphFormat::Graph6).unwrap();
+    // graph6 keeps the structure only.
+    let undirected = original.to_undirected();
+    assert!(
+        (0..undirected.node_count() as NodeId)
+            .all(|node| graph.successors(node) == undirected.successors(node))
+    );
+
+    let (code, stdout) = vf3(&["convert", &source, "-", "--to", "dot"]);
+    assert_eq!(code, 0);
+    assert!(stdout.starts_with("digraph G {\n"));
+    assert_eq!(stdout.matches(" -> ").count(), original.edge_count());
+
+    let _ = fs::remove_dir_all(&dir);
+}
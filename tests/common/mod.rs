@@ -1,4 +1,9 @@
-use std::path::PathBuf;
+// Each test crate uses its own subset of these helpers.
+#![allow(dead_code)]
+
+use std::{fs, path::PathBuf};
+
+use vf3lib_rs::{Graph, NodeId, RunOptions};
 
 fn fixtures_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data")
@@ -13,3 +18,60 @@ pub fn fixture_path(name: &str) -> String {
 pub fn fixture_pair(pattern: &str, target: &str) -> (String, String) {
     (fixture_path(pattern), fixture_path(target))
 }
+
+/// Fresh directory under the system temp dir, unique to this process and
+/// call so reruns never see stale files.
+pub fn unique_temp_dir(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vf3_{tag}_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    fs::create_dir_all(&dir).expect("Failed to create test directory");
+    dir
+}
+
+/// An edge for [`graph`]: `(from, to)` with label 0, or `(from, to, label)`.
+pub trait Edge: Copy {
+    fn parts(self) -> (NodeId, NodeId, i32);
+}
+
+impl Edge for (NodeId, NodeId) {
+    fn parts(self) -> (NodeId, NodeId, i32) {
+        (self.0, self.1, 0)
+    }
+}
+
+impl Edge for (NodeId, NodeId, i32) {
+    fn parts(self) -> (NodeId, NodeId, i32) {
+        self
+    }
+}
+
+/// Graph with one node per label and the given edges.
+pub fn graph<E: Edge>(directed: bool, labels: &[i32], edges: &[E]) -> Graph {
+    let mut graph = if directed {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    for &label in labels {
+        graph.add_node(label);
+    }
+    for &edge in edges {
+        let (from, to, label) = edge.parts();
+        graph.add_labeled_edge(from, to, label);
+    }
+    graph
+}
+
+/// Options that store every solution.
+pub fn opts() -> RunOptions {
+    RunOptions {
+        store_solutions: true,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,121 @@
+//! Compressed graph inputs (`gzip` and `zstd` features).
+
+mod common;
+
+use std::fs;
+
+use common::{fixture_pair, unique_temp_dir};
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use vf3lib_rs::GraphFormat;
+#[cfg(not(feature = "gzip"))]
+use vf3lib_rs::VF3Error;
+use vf3lib_rs::{RunOptions, run_vf3};
+
+#[cfg(feature = "gzip")]
+fn gzip(data: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_grf_matches_uncompressed() {
+    let dir = unique_temp_dir("gzip_grf");
+    let (patt, targ) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let targ_gz = dir.join("bvg1.grf.gz");
+    fs::write(&targ_gz, gzip(&fs::read(&targ).unwrap())).unwrap();
+
+    let expected = run_vf3(&patt, &targ, RunOptions::default()).expect("VF3 on .grf failed");
+    let res = run_vf3(
+        &patt,
+        targ_gz.to_string_lossy().as_ref(),
+        RunOptions::default(),
+    )
+    .expect("VF3 on .grf.gz failed");
+    assert_eq!(res.solutions, expected.solutions);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_detected_by_magic_bytes() {
+    let dir = unique_temp_dir("gzip_magic");
+    let pattern = dir.join("pattern.edges");
+    let target = dir.join("target.edges");
+    fs::write(&pattern, "1 2\n").unwrap();
+    fs::write(&target, gzip(b"1 2\n2 3\n")).unwrap();
+
+    let res = run_vf3(
+        pattern.to_string_lossy().as_ref(),
+        target.to_string_lossy().as_ref(),
+        RunOptions {
+            format: GraphFormat::EdgeList,
+            ..Default::default()
+        },
+    )
+    .expect("VF3 on gzip edge list failed");
+    assert_eq!(res.solutions, 2);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_bytes_are_decompressed() {
+    let graph = vf3lib_rs::Graph::from_bytes(&gzip(b"1 2\n2 3\n"), GraphFormat::EdgeList)
+        .expect("Failed to read gzip bytes");
+    assert_eq!((graph.node_count(), graph.edge_count()), (3, 2));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_edge_list() {
+    let dir = unique_temp_dir("zstd_edges");
+    let pattern = dir.join("pattern.edges.zst");
+    let target = dir.join("target.edges.zst");
+    fs::write(&pattern, zstd::encode_all(&b"1 2\n2 3\n"[..], 3).unwrap()).unwrap();
+    fs::write(
+        &target,
+        zstd::encode_all(&b"# chain\n1 2\n2 3\n3 4\n"[..], 3).unwrap(),
+    )
+    .unwrap();
+
+    let res = run_vf3(
+        pattern.to_string_lossy().as_ref(),
+        target.to_string_lossy().as_ref(),
+        RunOptions {
+            format: GraphFormat::EdgeList,
+            ..Default::default()
+        },
+    )
+    .expect("VF3 on .edges.zst failed");
+    assert_eq!(res.solutions, 2);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_without_feature_is_unsupported() {
+    let dir = unique_temp_dir("gzip_disabled");
+    let (patt, _) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let targ_gz = dir.join("bvg1.grf.gz");
+    fs::write(&targ_gz, [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+
+    let err = run_vf3(
+        &patt,
+        targ_gz.to_string_lossy().as_ref(),
+        RunOptions::default(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VF3Error::UnsupportedFormat { .. }),
+        "Unexpected error: {err:?}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,247 @@
+//! Differential tests: every matcher against a brute-force oracle.
+//!
+//! Patterns are random graphs or subgraphs extracted from a random target,
+//! relabelled so that node ids carry no hint of their images. VF3, VF3L and
+//! each VF3P configuration must report exactly the mappings the oracle
+//! enumerates, in node-induced and edge-induced mode, for directed and
+//! undirected graphs.
+
+use vf3lib_rs::{Graph, Mapping, NodeId, RunOptions, run_vf3_graphs, run_vf3l_graphs};
+#[cfg(target_os = "linux")]
+use vf3lib_rs::{ParallelOptions, run_vf3p_graphs};
+
+/// Linear congruential generator, so failures are reproducible from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+}
+
+fn empty(directed: bool) -> Graph {
+    if directed {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    }
+}
+
+/// Random graph with node and edge labels from `0..labels`.
+fn random_graph(rng: &mut Rng, directed: bool, nodes: u32, density: u64, labels: u64) -> Graph {
+    let mut graph = empty(directed);
+    for _ in 0..nodes {
+        graph.add_node(rng.below(labels) as i32);
+    }
+    for from in 0..nodes {
+        for to in 0..nodes {
+            if from != to && (directed || from < to) && rng.chance(density) {
+                graph.add_labeled_edge(from, to, rng.below(labels) as i32);
+            }
+        }
+    }
+    graph
+}
+
+/// Subgraph of `target` on `size` random nodes in random order, keeping each
+/// edge with probability `keep` percent.
+fn extract(rng: &mut Rng, target: &Graph, size: usize, keep: u64) -> Graph {
+    let mut nodes: Vec<NodeId> = (0..target.node_count() as NodeId).collect();
+    for i in (1..nodes.len()).rev() {
+        nodes.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    nodes.truncate(size);
+    let mut pattern = empty(target.is_directed());
+    for &node in &nodes {
+        pattern.add_node(target.node_label(node));
+    }
+    for (from, &image_from) in nodes.iter().enumerate() {
+        for (to, &image_to) in nodes.iter().enumerate() {
+            if from == to || (!target.is_directed() && from > to) {
+                continue;
+            }
+            if let Some(label) = target.edge_label(image_from, image_to)
+                && rng.chance(keep)
+            {
+                pattern.add_labeled_edge(from as NodeId, to as NodeId, label);
+            }
+        }
+    }
+    pattern
+}
+
+/// Every injective, label-preserving mapping of `pattern` into `target`
+/// that keeps the pattern's edges and, when `induced`, its non-edges.
+fn brute_force(pattern: &Graph, target: &Graph, induced: bool) -> Vec<Mapping> {
+    fn extend(
+        pattern: &Graph,
+        target: &Graph,
+        induced: bool,
+        images: &mut Vec<NodeId>,
+        found: &mut Vec<Mapping>,
+    ) {
+        let node = images.len() as NodeId;
+        if node as usize == pattern.node_count() {
+            found.push(
+                images
+                    .iter()
+                    .enumerate()
+                    .map(|(p, &t)| (p as NodeId, t))
+                    .collect(),
+            );
+            return;
+        }
+        for image in 0..target.node_count() as NodeId {
+            if images.contains(&image) || pattern.node_label(node) != target.node_label(image) {
+                continue;
+            }
+            let consistent = images.iter().enumerate().all(|(other, &other_image)| {
+                let other = other as NodeId;
+                [
+                    (node, other, image, other_image),
+                    (other, node, other_image, image),
+                ]
+                .into_iter()
+                .all(|(from, to, image_from, image_to)| {
+                    match (
+                        pattern.edge_label(from, to),
+                        target.edge_label(image_from, image_to),
+                    ) {
+                        (Some(label), image_label) => image_label == Some(label),
+                        (None, image_label) => !induced || image_label.is_none(),
+                    }
+                })
+            });
+            if consistent {
+                images.push(image);
+                extend(pattern, target, induced, images, found);
+                images.pop();
+            }
+        }
+    }
+    let mut found = Vec::new();
+    extend(pattern, target, induced, &mut Vec::new(), &mut found);
+    found
+}
+
+fn sorted(mut mappings: Vec<Mapping>) -> Vec<Mapping> {
+    mappings.sort();
+    mappings
+}
+
+/// Check every matcher on `pattern` and `target` against the oracle.
+fn check(pattern: &Graph, target: &Graph, edge_induced: bool, context: &str) {
+    let expected = brute_force(pattern, target, !edge_induced);
+    let opts = RunOptions {
+        edge_induced,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let mut runs = vec![
+        ("VF3", run_vf3_graphs(pattern, target, opts.clone())),
+        ("VF3L", run_vf3l_graphs(pattern, target, opts.clone())),
+    ];
+    #[cfg(target_os = "linux")]
+    for (name, algo, lock_free) in [
+        ("VF3P GSS", 1, false),
+        ("VF3P GSS lock-free", 1, true),
+        ("VF3P WLS", 2, false),
+        ("VF3P WLS lock-free", 2, true),
+    ] {
+        let par = ParallelOptions {
+            algo,
+            num_threads: 2,
+            lock_free,
+            ..Default::default()
+        };
+        runs.push((name, run_vf3p_graphs(pattern, target, opts.clone(), par)));
+    }
+    for (name, res) in runs {
+        let res = res.unwrap_or_else(|e| panic!("{name} failed on {context}: {e}"));
+        assert_eq!(
+            res.solutions,
+            expected.len() as u64,
+            "{name} count on {context}\n{pattern:?}\n{target:?}"
+        );
+        assert_eq!(
+            sorted(res.mappings),
+            expected,
+            "{name} mappings on {context}\n{pattern:?}\n{target:?}"
+        );
+    }
+}
+
+fn run_rounds(directed: bool, edge_induced: bool, seed: u64) {
+    let mut rng = Rng(seed);
+    for round in 0..40 {
+        let nodes = 4 + rng.below(4) as u32;
+        let density = 30 + rng.below(40);
+        let target = random_graph(&mut rng, directed, nodes, density, 2);
+        let size = 1 + rng.below(u64::from(nodes).min(5)) as usize;
+        let pattern = if round % 4 == 3 {
+            random_graph(&mut rng, directed, size as u32, 40, 2)
+        } else {
+            // Dropping edges only keeps the extraction a match when edges
+            // need not be induced.
+            let keep = if edge_induced { 70 } else { 100 };
+            extract(&mut rng, &target, size, keep)
+        };
+        let context = format!("seed {seed} round {round}");
+        check(&pattern, &target, edge_induced, &context);
+    }
+}
+
+#[test]
+fn directed_node_induced() {
+    run_rounds(true, false, 1);
+}
+
+#[test]
+fn directed_edge_induced() {
+    run_rounds(true, true, 2);
+}
+
+#[test]
+fn undirected_node_induced() {
+    run_rounds(false, false, 3);
+}
+
+#[test]
+fn undirected_edge_induced() {
+    run_rounds(false, true, 4);
+}
+
+#[test]
+fn extracted_patterns_are_found() {
+    // The oracle itself must see the planted occurrence.
+    let mut rng = Rng(5);
+    for directed in [true, false] {
+        let target = random_graph(&mut rng, directed, 7, 50, 3);
+        let pattern = extract(&mut rng, &target, 4, 100);
+        assert!(!brute_force(&pattern, &target, true).is_empty());
+        let sparse = extract(&mut rng, &target, 4, 60);
+        assert!(!brute_force(&sparse, &target, false).is_empty());
+    }
+}
+
+#[test]
+fn unlabelled_dense_graphs() {
+    // Many symmetric mappings stress the enumeration rather than pruning.
+    let mut rng = Rng(6);
+    for directed in [true, false] {
+        for edge_induced in [false, true] {
+            let target = random_graph(&mut rng, directed, 7, 70, 1);
+            let pattern = extract(&mut rng, &target, 4, if edge_induced { 80 } else { 100 });
+            check(&pattern, &target, edge_induced, "unlabelled");
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Graphviz DOT rendering of graphs and matches.
+
+mod common;
+
+use common::graph;
+use vf3lib_rs::{Graph, MatchRegion, formats::dot};
+
+fn render(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+    let mut out = Vec::new();
+    write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn plain_graphs() {
+    let graph = graph(false, &[0, 0, 0], &[(0, 1, 0), (2, 1, 0)]);
+    assert_eq!(
+        render(|out| dot::write(out, &graph)),
+        "graph G {\n  n0 [label=\"0\"];\n  n1 [label=\"1\"];\n  n2 [label=\"2\"];\n  \
+         n0 -- n1;\n  n1 -- n2;\n}\n"
+    );
+
+    let mut graph = Graph::directed();
+    for (label, name) in [(1, "a\"b"), (2, "c")] {
+        let node = graph.add_node(label);
+        graph.set_node_name(node, name);
+    }
+    graph.add_labeled_edge(1, 0, 3);
+    assert_eq!(
+        render(|out| dot::write(out, &graph)),
+        "digraph G {\n  n0 [label=\"a\\\"b:1\"];\n  n1 [label=\"c:2\"];\n  \
+         n1 -> n0 [label=\"3\"];\n}\n"
+    );
+}
+
+#[test]
+fn match_is_highlighted_with_pattern_counterparts() {
+    let mut pattern = graph(false, &[0, 0], &[(0, 1, 0)]);
+    pattern.set_node_name(0, "x");
+    pattern.set_node_name(1, "y");
+    // A path 0-1-2 matched onto its second edge.
+    let target = graph(false, &[0, 0, 0], &[(0, 1, 0), (1, 2, 0)]);
+    assert_eq!(
+        render(|out| dot::write_match(out, &pattern, &target, &[(0, 2), (1, 1)])),
+        "graph match {\n  n0 [label=\"0\"];\n  \
+         n1 [label=\"1\\n(y)\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n  \
+         n2 [label=\"2\\n(x)\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n  \
+         n0 -- n1;\n  n1 -- n2 [label=\"(x-y)\", color=\"#d62728\", penwidth=2];\n}\n"
+    );
+}
+
+#[test]
+fn directed_pattern_edges_keep_their_direction() {
+    let mut pattern = Graph::directed();
+    pattern.add_node(0);
+    pattern.add_node(0);
+    pattern.add_edge(0, 1);
+    let mut target = Graph::directed();
+    target.add_node(0);
+    target.add_node(0);
+    target.add_edge(0, 1);
+    target.add_edge(1, 0);
+    let out = render(|out| dot::write_match(out, &pattern, &target, &[(0, 1), (1, 0)]));
+    assert!(out.contains("n1 -> n0 [label=\"(0->1)\", color=\"#d62728\", penwidth=2];"));
+    assert!(out.contains("n0 -> n1;"));
+}
+
+#[test]
+fn region_shows_coverage() {
+    let edge = graph(false, &[0, 0], &[(0, 1, 0)]);
+    let target = graph(false, &[0, 0, 0], &[(0, 1, 0), (1, 2, 0)]);
+    let mappings = vec![vec![(0, 0), (1, 1)], vec![(0, 1), (1, 2)]];
+    let region = MatchRegion::union(&edge, &target, &mappings, false);
+    let out = render(|out| dot::write_region(out, &target, &region));
+    assert!(out.starts_with("graph region {\n"));
+    assert!(out.contains("n1 [label=\"1\\n(2 matches)\", style=filled"));
+    assert!(out.contains("n0 [label=\"0\\n(1 match)\", style=filled"));
+    assert!(out.contains("n1 -- n2 [label=\"(1 match)\", color=\"#d62728\", penwidth=2];"));
+}
+
+#[test]
+fn side_by_side_joins_pattern_nodes_to_their_images() {
+    let pattern = graph(false, &[0, 0], &[(0, 1, 0)]);
+    let mut target = Graph::directed();
+    for _ in 0..3 {
+        target.add_node(0);
+    }
+    target.add_edge(2, 1);
+    target.add_edge(0, 1);
+    assert_eq!(
+        render(|out| dot::write_side_by_side(out, &pattern, &target, Some(&[(0, 2), (1, 1)]))),
+        "digraph match {\n  subgraph cluster_pattern {\n    \
+         label=\"pattern\";\n    \
+         p0 [label=\"0\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n    \
+         p1 [label=\"1\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n    \
+         p0 -> p1 [color=\"#d62728\", penwidth=2, dir=none];\n  }\n  \
+         subgraph cluster_target {\n    label=\"target\";\n    \
+         t0 [label=\"0\"];\n    \
+         t1 [label=\"1\\n(1)\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n    \
+         t2 [label=\"2\\n(0)\", style=filled, fillcolor=\"#ffd966\", penwidth=2];\n    \
+         t0 -> t1;\n    t2 -> t1 [label=\"(0-1)\", color=\"#d62728\", penwidth=2];\n  }\n  \
+         p0 -> t2 [style=dashed, color=gray, constraint=false, dir=none];\n  \
+         p1 -> t1 [style=dashed, color=gray, constraint=false, dir=none];\n}\n"
+    );
+
+    // Without a mapping nothing is highlighted or joined.
+    let out = render(|out| dot::write_side_by_side(out, &pattern, &target, None));
+    assert!(!out.contains("filled") && !out.contains("dashed"));
+}
@@ -0,0 +1,215 @@
+//! Maximum common subgraph search.
+
+mod common;
+
+use common::graph;
+use vf3lib_rs::{Graph, McsOptions, MixedEdges, NodeId, maximum_common_subgraph};
+
+/// Small graph with pseudo-random labels, edges and edge labels.
+fn random_graph(seed: &mut u64, directed: bool, nodes: u32) -> Graph {
+    let mut next = |bound: u64| {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 33) % bound
+    };
+    let mut graph = if directed {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    for _ in 0..nodes {
+        graph.add_node(next(2) as i32);
+    }
+    for from in 0..nodes {
+        for to in 0..nodes {
+            if from != to && (directed || from < to) && next(2) == 0 {
+                graph.add_labeled_edge(from, to, next(2) as i32);
+            }
+        }
+    }
+    graph
+}
+
+/// Preserved edges of `images`, or `None` unless it is a label-preserving
+/// injective mapping that is node-induced when `induced` is set.
+fn score(
+    pattern: &Graph,
+    target: &Graph,
+    images: &[Option<NodeId>],
+    induced: bool,
+) -> Option<usize> {
+    let mut edges = 0;
+    for (node, &image) in images.iter().enumerate() {
+        let Some(image) = image else { continue };
+        if pattern.node_label(node as NodeId) != target.node_label(image) {
+            return None;
+        }
+        for (other, &other_image) in images.iter().enumerate() {
+            let Some(other_image) = other_image else {
+                continue;
+            };
+            if node == other {
+                continue;
+            }
+            if image == other_image {
+                return None;
+            }
+            let arc = pattern.edge_label(node as NodeId, other as NodeId);
+            let image_arc = target.edge_label(image, other_image);
+            if arc.is_some() && arc == image_arc {
+                edges += 1;
+            } else if induced && arc != image_arc {
+                return None;
+            }
+        }
+    }
+    Some(if pattern.is_directed() {
+        edges
+    } else {
+        edges / 2
+    })
+}
+
+/// Best (nodes, edges) for induced search and (edges, nodes) for
+/// edge-induced search over every partial mapping.
+fn brute_force(pattern: &Graph, target: &Graph, induced: bool) -> (usize, usize) {
+    fn walk(
+        pattern: &Graph,
+        target: &Graph,
+        induced: bool,
+        images: &mut Vec<Option<NodeId>>,
+        best: &mut (usize, usize),
+    ) {
+        if images.len() == pattern.node_count() {
+            if let Some(edges) = score(pattern, target, images, induced) {
+                let nodes = images.iter().flatten().count();
+                let found = if induced { (nodes, 0) } else { (edges, nodes) };
+                *best = (*best).max(found);
+            }
+            return;
+        }
+        for image in (0..target.node_count() as NodeId).map(Some).chain([None]) {
+            images.push(image);
+            walk(pattern, target, induced, images, best);
+            images.pop();
+        }
+    }
+    let mut best = (0, 0);
+    walk(pattern, target, induced, &mut Vec::new(), &mut best);
+    best
+}
+
+#[test]
+fn triangle_and_path() {
+    let triangle = graph(false, &[0; 3], &[(0, 1), (1, 2), (0, 2)]);
+    let path = graph(false, &[0; 3], &[(0, 1), (1, 2)]);
+
+    let induced = maximum_common_subgraph(&triangle, &path, &McsOptions::default());
+    assert!(induced.optimal);
+    assert_eq!(induced.mapping.len(), 2);
+    assert_eq!(induced.edges, 1);
+
+    let opts = McsOptions {
+        edge_induced: true,
+        ..Default::default()
+    };
+    let common = maximum_common_subgraph(&triangle, &path, &opts);
+    assert!(common.optimal);
+    assert_eq!(common.mapping.len(), 3);
+    assert_eq!(common.edges, 2);
+
+    // The path embeds in the triangle as a monomorphism, but not induced.
+    let embedded = maximum_common_subgraph(&path, &triangle, &opts);
+    assert_eq!((embedded.mapping.len(), embedded.edges), (3, 2));
+    let induced = maximum_common_subgraph(&path, &triangle, &McsOptions::default());
+    assert_eq!((induced.mapping.len(), induced.edges), (2, 1));
+}
+
+#[test]
+fn labels_and_directions_limit_the_common_subgraph() {
+    let labelled = graph(false, &[0, 1, 0], &[(0, 1), (1, 2)]);
+    let other = graph(false, &[0, 0, 1], &[(0, 1), (1, 2)]);
+    let common = maximum_common_subgraph(&labelled, &other, &McsOptions::default());
+    // Only one of the label-0 neighbours fits next to the label-1 node.
+    assert_eq!((common.mapping.len(), common.edges), (2, 1));
+    assert!(common.mapping.contains(&(1, 2)));
+
+    let cycle = graph(true, &[0; 3], &[(0, 1), (1, 2), (2, 0)]);
+    let reversed = graph(true, &[0; 3], &[(1, 0), (2, 1), (0, 2)]);
+    let opts = McsOptions {
+        edge_induced: true,
+        ..Default::default()
+    };
+    assert_eq!(maximum_common_subgraph(&cycle, &reversed, &opts).edges, 3);
+    let path = graph(true, &[0; 3], &[(0, 1), (2, 1)]);
+    assert_eq!(maximum_common_subgraph(&cycle, &path, &opts).edges, 1);
+
+    // An undirected edge needs both arcs unless either orientation will do.
+    let edge = graph(false, &[0; 2], &[(0, 1)]);
+    let arc = graph(true, &[0; 2], &[(0, 1)]);
+    assert_eq!(maximum_common_subgraph(&edge, &arc, &opts).edges, 0);
+    let either = McsOptions {
+        mixed_edges: MixedEdges::Either,
+        ..opts
+    };
+    assert_eq!(maximum_common_subgraph(&edge, &arc, &either).edges, 1);
+}
+
+#[test]
+fn agrees_with_brute_force() {
+    let mut seed = 7;
+    for round in 0..60 {
+        let directed = round % 2 == 0;
+        let pattern = random_graph(&mut seed, directed, 4);
+        let target = random_graph(&mut seed, directed, 5);
+        for induced in [true, false] {
+            let opts = McsOptions {
+                edge_induced: !induced,
+                ..Default::default()
+            };
+            let common = maximum_common_subgraph(&pattern, &target, &opts);
+            assert!(common.optimal);
+            let mut images = vec![None; pattern.node_count()];
+            for &(node, image) in &common.mapping {
+                images[node as usize] = Some(image);
+            }
+            let edges = score(&pattern, &target, &images, induced)
+                .unwrap_or_else(|| panic!("invalid mapping {:?}", common.mapping));
+            assert_eq!(edges, common.edges);
+            let found = if induced {
+                (common.mapping.len(), 0)
+            } else {
+                (common.edges, common.mapping.len())
+            };
+            assert_eq!(
+                found,
+                brute_force(&pattern, &target, induced),
+                "{pattern:?} in {target:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn limits_return_the_best_so_far() {
+    let mut seed = 11;
+    let pattern = random_graph(&mut seed, false, 12);
+    let target = random_graph(&mut seed, false, 14);
+
+    let opts = McsOptions {
+        size_limit: Some(3),
+        ..Default::default()
+    };
+    let common = maximum_common_subgraph(&pattern, &target, &opts);
+    assert!(!common.optimal);
+    assert_eq!(common.mapping.len(), 3);
+
+    let opts = McsOptions {
+        edge_induced: true,
+        time_limit: Some(0.0),
+        ..Default::default()
+    };
+    let common = maximum_common_subgraph(&pattern, &target, &opts);
+    assert!(!common.optimal);
+}
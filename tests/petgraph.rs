@@ -0,0 +1,85 @@
+//! Running the matchers on petgraph graphs (`petgraph` feature).
+
+#![cfg(feature = "petgraph")]
+
+mod common;
+
+use common::opts;
+use petgraph::{
+    graph::{DiGraph, UnGraph},
+    stable_graph::StableDiGraph,
+};
+use vf3lib_rs::{VF3Error, run_vf3_petgraph, run_vf3l_petgraph};
+
+#[test]
+fn digraph_pattern_on_stable_graph_with_holes() {
+    let mut pattern = DiGraph::<&str, u8>::new();
+    let a = pattern.add_node("A");
+    let b = pattern.add_node("B");
+    pattern.add_edge(a, b, 1);
+
+    let mut target = StableDiGraph::<&str, u8>::new();
+    let removed = target.add_node("A");
+    let x = target.add_node("A");
+    let y = target.add_node("B");
+    let z = target.add_node("B");
+    target.add_edge(x, y, 1);
+    target.add_edge(x, z, 2);
+    target.remove_node(removed);
+
+    let res = run_vf3_petgraph(&pattern, &target, |l, r| l == r, |l, r| l == r, opts())
+        .expect("petgraph VF3 failed");
+    assert_eq!(res.result.solutions, 1);
+    assert_eq!(res.mappings, vec![vec![(a, x), (b, y)]]);
+
+    // Ignoring edge weights also matches the second edge.
+    let mut res = run_vf3l_petgraph(&pattern, &target, |l, r| l == r, |_, _| true, opts())
+        .expect("petgraph VF3L failed");
+    res.mappings.sort();
+    assert_eq!(
+        res.mappings,
+        vec![vec![(a, x), (b, y)], vec![(a, x), (b, z)]]
+    );
+}
+
+#[test]
+fn undirected_graphs_with_custom_node_equality() {
+    // Weights match when they have the same parity.
+    let mut pattern = UnGraph::<u32, ()>::new_undirected();
+    let p0 = pattern.add_node(4);
+    let p1 = pattern.add_node(7);
+    pattern.add_edge(p0, p1, ());
+
+    let mut target = UnGraph::<u32, ()>::new_undirected();
+    let nodes: Vec<_> = [2, 5, 8].into_iter().map(|w| target.add_node(w)).collect();
+    target.add_edge(nodes[0], nodes[1], ());
+    target.add_edge(nodes[1], nodes[2], ());
+
+    let mut res = run_vf3_petgraph(
+        &pattern,
+        &target,
+        |l, r| l % 2 == r % 2,
+        |_, _| true,
+        opts(),
+    )
+    .expect("petgraph VF3 failed");
+    res.mappings.sort();
+    assert_eq!(
+        res.mappings,
+        vec![
+            vec![(p0, nodes[0]), (p1, nodes[1])],
+            vec![(p0, nodes[2]), (p1, nodes[1])]
+        ]
+    );
+}
+
+#[test]
+fn self_loops_are_rejected() {
+    let pattern = DiGraph::<(), ()>::from_edges([(0, 1)]);
+    let target = DiGraph::<(), ()>::from_edges([(0, 1), (1, 1)]);
+    let err = run_vf3_petgraph(&pattern, &target, |_, _| true, |_, _| true, opts()).unwrap_err();
+    assert!(
+        matches!(err, VF3Error::InvalidGraph { .. }),
+        "Unexpected error: {err:?}"
+    );
+}
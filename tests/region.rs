@@ -0,0 +1,127 @@
+//! Matched subgraphs and the union of all matches.
+
+mod common;
+
+use std::{fs::File, ops::ControlFlow};
+
+use common::{fixture_pair, graph, opts};
+use vf3lib_rs::{Graph, GraphFormat, MatchRegion, run_vf3_graphs, run_vf3_graphs_with};
+
+/// Two triangles sharing the edge 1-2.
+fn diamond() -> Graph {
+    graph(
+        false,
+        &[5, 6, 6, 5],
+        &[(0, 1, 1), (0, 2, 1), (1, 2, 2), (1, 3, 1), (2, 3, 1)],
+    )
+}
+
+#[test]
+fn node_induced_match_is_a_copy_of_the_pattern() {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let read = |path| Graph::from_reader(File::open(path).unwrap(), GraphFormat::VFLegacy).unwrap();
+    let (pattern, target) = (read(pattern), read(target));
+    let res = run_vf3_graphs(&pattern, &target, opts()).unwrap();
+    for mapping in &res.mappings {
+        let region = MatchRegion::of_match(&pattern, &target, mapping, false);
+        let graph = region.to_graph(&target);
+        assert_eq!(graph.node_count(), pattern.node_count());
+        assert_eq!(graph.edge_count(), pattern.edge_count());
+        assert!(graph.is_directed());
+        let copy = run_vf3_graphs(&pattern, &graph, opts()).unwrap();
+        assert!(copy.solutions > 0);
+        for &(_, t) in mapping {
+            assert!(region.contains_node(t));
+        }
+    }
+}
+
+#[test]
+fn edge_induced_match_keeps_only_pattern_edges() {
+    let path = graph(false, &[5, 6, 6], &[(0, 1, 1), (1, 2, 2)]);
+    let target = diamond();
+    let mapping = [(0, 0), (1, 1), (2, 2)];
+
+    let region = MatchRegion::of_match(&path, &target, &mapping, true);
+    assert_eq!(
+        region.edges.keys().copied().collect::<Vec<_>>(),
+        [(0, 1), (1, 2)]
+    );
+    assert!(region.contains_edge(&target, 2, 1));
+    assert!(!region.contains_edge(&target, 0, 2));
+
+    let region = MatchRegion::of_match(&path, &target, &mapping, false);
+    assert_eq!(region.edges.len(), 3);
+
+    let graph = region.to_graph(&target);
+    assert!(!graph.is_directed());
+    assert_eq!(graph.edge_label(0, 1), Some(1));
+    assert_eq!(graph.edge_label(2, 1), Some(2));
+    assert_eq!(graph.node_label(0), 5);
+}
+
+#[test]
+fn union_counts_covering_matches() {
+    let triangle = graph(false, &[0, 0, 0], &[(0, 1, 0), (1, 2, 0), (0, 2, 0)]);
+    // The diamond without labels, plus an isolated node.
+    let mut target = Graph::undirected();
+    for _ in 0..5 {
+        target.add_node(0);
+    }
+    for (from, to) in [(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)] {
+        target.add_edge(from, to);
+    }
+    target.set_node_name(4, "isolated");
+
+    let res = run_vf3_graphs(&triangle, &target, opts()).unwrap();
+    assert_eq!(res.solutions, 12);
+    let region = MatchRegion::union(&triangle, &target, &res.mappings, false);
+    assert_eq!(
+        region
+            .nodes
+            .iter()
+            .map(|(&n, &c)| (n, c))
+            .collect::<Vec<_>>(),
+        [(0, 6), (1, 12), (2, 12), (3, 6)]
+    );
+    assert_eq!(region.edges[&(1, 2)], 12);
+    assert_eq!(region.edges[&(0, 1)], 6);
+    assert!(!region.contains_node(4));
+
+    // The region grows the same way from a solution callback.
+    let mut streamed = MatchRegion::new();
+    run_vf3_graphs_with(&triangle, &target, opts(), |mapping| {
+        streamed.add(&triangle, &target, mapping, false);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(streamed, region);
+
+    let graph = region.to_graph(&target);
+    assert_eq!((graph.node_count(), graph.edge_count()), (4, 5));
+    assert_eq!(
+        graph.node_names().unwrap(),
+        ["0", "1", "2", "3"].map(String::from)
+    );
+}
+
+#[test]
+fn subgraph_keeps_target_names_and_directions() {
+    let mut pattern = Graph::directed();
+    let (a, b) = (pattern.add_node(0), pattern.add_node(0));
+    pattern.add_edge(a, b);
+    let mut target = Graph::directed();
+    for name in ["x", "y", "z"] {
+        let node = target.add_node(0);
+        target.set_node_name(node, name);
+    }
+    target.add_labeled_edge(2, 0, 7);
+
+    let region = MatchRegion::of_match(&pattern, &target, &[(0, 2), (1, 0)], true);
+    assert!(region.contains_edge(&target, 2, 0));
+    assert!(!region.contains_edge(&target, 0, 2));
+    let graph = region.to_graph(&target);
+    assert_eq!(graph.node_names().unwrap(), ["x", "z"].map(String::from));
+    assert_eq!(graph.edge_label(1, 0), Some(7));
+    assert!(!graph.has_edge(0, 1));
+}
@@ -0,0 +1,186 @@
+//! Serialized options, results and graphs (`serde` feature).
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::fixture_pair;
+use serde_json::json;
+use vf3lib_rs::{
+    EdgeListOptions, Graph, GraphFormat, LabelDictionary, MixedEdges, ParallelOptions, ResultData,
+    RunOptions, Tolerance, run_vf3,
+};
+
+#[test]
+fn options_round_trip_and_default_missing_fields() {
+    let opts = RunOptions {
+        format: GraphFormat::EdgeList,
+        undirected: true,
+        target_undirected: Some(false),
+        mixed_edges: MixedEdges::Either,
+        edge_induced: true,
+        may_share: vec![1, 2],
+        tolerance: Tolerance {
+            edges: 1,
+            labels: 0,
+        },
+        edge_list: EdgeListOptions {
+            index_base: 0,
+            string_ids: true,
+        },
+        ..Default::default()
+    };
+    let value = serde_json::to_value(&opts).unwrap();
+    assert_eq!(value["format"], "edge");
+    assert_eq!(value["mixed_edges"], "either");
+    assert_eq!(value["tolerance"], json!({"edges": 1, "labels": 0}));
+    assert_eq!(value["graphml"]["node_label_key"], "label");
+    let back: RunOptions = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&back).unwrap(), value);
+
+    // Stored configurations only need the fields that differ from the defaults.
+    let sparse: RunOptions =
+        serde_json::from_str(r#"{"format": "graphml", "edge_list": {"string_ids": true}}"#)
+            .unwrap();
+    assert_eq!(sparse.format, GraphFormat::GraphML);
+    assert_eq!(sparse.repetition_time_limit, 1.0);
+    assert_eq!(sparse.edge_list.index_base, 1);
+    assert!(sparse.edge_list.string_ids);
+
+    let par: ParallelOptions = serde_json::from_str(r#"{"num_threads": 4}"#).unwrap();
+    assert_eq!((par.algo, par.num_threads, par.ssr_high_limit), (1, 4, 3));
+
+    let formats = [
+        (GraphFormat::VFLegacy, "vf"),
+        (GraphFormat::EdgeList, "edge"),
+        (GraphFormat::GraphML, "graphml"),
+        (GraphFormat::Lad, "lad"),
+        (GraphFormat::Dimacs, "dimacs"),
+        (GraphFormat::Gfu, "gfu"),
+        (GraphFormat::Gfd, "gfd"),
+        (GraphFormat::Graph6, "graph6"),
+        (GraphFormat::Csr, "csr"),
+    ];
+    for (format, name) in formats {
+        assert_eq!(serde_json::to_value(format).unwrap(), name);
+    }
+}
+
+#[test]
+fn stored_query_replays_to_the_same_result() {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let opts = RunOptions {
+        undirected: true,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let stored = serde_json::to_string(&opts).unwrap();
+    let res = run_vf3(&pattern, &target, opts).unwrap();
+    let saved = serde_json::to_string(&res).unwrap();
+
+    let replayed = run_vf3(&pattern, &target, serde_json::from_str(&stored).unwrap()).unwrap();
+    let loaded: ResultData = serde_json::from_str(&saved).unwrap();
+    assert_eq!(loaded.solutions, 168);
+    assert_eq!(replayed.solutions, loaded.solutions);
+    assert_eq!(replayed.mappings, loaded.mappings);
+
+    let value = serde_json::to_value(&res).unwrap();
+    assert_eq!(value["mappings"][0][0], json!([0, 0]));
+    assert_eq!(value["pattern_names"], json!(null));
+}
+
+#[test]
+fn graphs_serialize_as_labels_and_edge_lists() {
+    let mut graph = Graph::undirected();
+    let a = graph.add_node(7);
+    let b = graph.add_node(8);
+    let c = graph.add_node(7);
+    graph.add_labeled_edge(a, b, 1);
+    graph.add_labeled_edge(c, b, 2);
+    let value = serde_json::to_value(&graph).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "directed": false,
+            "labels": [7, 8, 7],
+            "edges": [[0, 1, 1], [1, 2, 2]],
+        })
+    );
+    let back: Graph = serde_json::from_value(value).unwrap();
+    assert_eq!(back, graph);
+
+    graph.set_node_name(b, "hub");
+    let back: Graph = serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+    assert_eq!(back, graph);
+    assert_eq!(back.node_name(b), Some("hub"));
+
+    let directed: Graph =
+        serde_json::from_str(r#"{"directed": true, "labels": [0, 0], "edges": [[1, 0, 3]]}"#)
+            .unwrap();
+    assert_eq!(directed.edge_label(1, 0), Some(3));
+    assert_eq!(directed.edge_label(0, 1), None);
+    let back: Graph = serde_json::from_value(serde_json::to_value(&directed).unwrap()).unwrap();
+    assert_eq!(back, directed);
+}
+
+#[test]
+fn invalid_graphs_are_rejected() {
+    for (name, text) in [
+        (
+            "out_of_range",
+            r#"{"directed": true, "labels": [0], "edges": [[0, 1, 0]]}"#,
+        ),
+        (
+            "self_loop",
+            r#"{"directed": true, "labels": [0, 0], "edges": [[1, 1, 0]]}"#,
+        ),
+        (
+            "names",
+            r#"{"directed": true, "labels": [0], "edges": [], "names": []}"#,
+        ),
+    ] {
+        let err = serde_json::from_str::<Graph>(text).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Invalid graph"),
+            "{name}: {err}"
+        );
+    }
+}
+
+#[test]
+fn label_dictionaries_serialize_as_their_strings_and_kinds() {
+    let mut labels = LabelDictionary::new();
+    labels.intern("C");
+    labels.intern("O");
+    let value = serde_json::to_value(&labels).unwrap();
+    assert_eq!(value, json!({"labels": ["C", "O"]}));
+    let back: LabelDictionary = serde_json::from_value(value).unwrap();
+    assert_eq!(back, labels);
+    assert_eq!(back.get("O"), Some(2));
+
+    // A repeated string would renumber every label after it.
+    let err = serde_json::from_value::<LabelDictionary>(json!({"labels": ["a", "b", "a", "c"]}))
+        .unwrap_err();
+    assert!(err.to_string().contains("\"a\" is listed twice"), "{err}");
+
+    // A restored dictionary still refuses integer labels after string ones.
+    let graphml = |key: &str, data: &str| {
+        format!(
+            r#"<graphml>
+  <key id="d0" for="node" attr.name="label" attr.type="{key}"/>
+  <graph edgedefault="undirected"><node id="a"><data key="d0">{data}</data></node></graph>
+</graphml>"#
+        )
+    };
+    let opts = RunOptions {
+        format: GraphFormat::GraphML,
+        ..Default::default()
+    };
+    let mut labels = LabelDictionary::new();
+    Graph::from_reader_with(graphml("string", "C").as_bytes(), &opts, &mut labels).unwrap();
+    let value = serde_json::to_value(&labels).unwrap();
+    assert_eq!(value, json!({"labels": ["C"], "nodes": "string"}));
+    let mut back: LabelDictionary = serde_json::from_value(value).unwrap();
+    assert_eq!(back, labels);
+    assert!(Graph::from_reader_with(graphml("int", "1").as_bytes(), &opts, &mut back).is_err());
+}
@@ -7,11 +7,21 @@ mod common;
 
 use std::{
     fs,
+    io::BufReader,
     path::{Path, PathBuf},
 };
 
-use common::fixture_pair;
-use vf3lib_rs::{GraphFormat, RunOptions, run_vf3, run_vf3l};
+use common::{fixture_pair, unique_temp_dir};
+use vf3lib_rs::{
+    EdgeListOptions, Graph, GraphFormat, GraphMlOptions, LabelDictionary, LadOptions, MixedEdges,
+    RunOptions, Tolerance, VF3Error, VF3Query, Violations,
+    formats::{
+        csr,
+        graph6::{self, Graph6Format},
+        graphml, grf, ri,
+    },
+    run_vf3, run_vf3_graphs, run_vf3l, run_vf3l_graphs,
+};
 #[cfg(target_os = "linux")]
 use vf3lib_rs::{ParallelOptions, run_vf3p};
 
@@ -84,6 +94,1361 @@ fn edge_list_undirected_triangle() {
     let _ = fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn edge_list_zero_based_ids() {
+    let dir = unique_temp_dir("edge_list_zero_based");
+    let patt = write_tmp(&dir, "pattern.edgelist", "0 1\n1 2\n0 2\n");
+    let targ = write_tmp(&dir, "target.edgelist", "0 1\n1 2\n0 2\n2 3\n");
+
+    let opts = RunOptions {
+        format: GraphFormat::EdgeList,
+        edge_list: EdgeListOptions {
+            index_base: 0,
+            ..Default::default()
+        },
+        undirected: true,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .expect("Zero-based edge list failed");
+    assert_eq!(res.solutions, 6);
+    let named = res.named_mappings().expect("edge lists carry node names");
+    assert_eq!(named.len(), 6);
+    for mapping in named {
+        assert!(mapping.iter().all(|(_, t)| ["0", "1", "2"].contains(t)));
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn edge_list_string_ids_report_names() {
+    let dir = unique_temp_dir("edge_list_string_ids");
+    let patt = write_tmp(&dir, "pattern.edgelist", "a b\nb c\n");
+    let targ = write_tmp(
+        &dir,
+        "target.edgelist",
+        "# Directed chain\nuser:1 user:2\nuser:2 user:3\n",
+    );
+
+    let opts = RunOptions {
+        format: GraphFormat::EdgeList,
+        edge_list: EdgeListOptions {
+            string_ids: true,
+            ..Default::default()
+        },
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3l(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .expect("String-id edge list failed");
+    assert_eq!(res.solutions, 1);
+    assert_eq!(
+        res.named_mappings().unwrap(),
+        vec![vec![("a", "user:1"), ("b", "user:2"), ("c", "user:3")]]
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn edge_list_below_index_base_is_parse_error() {
+    let dir = unique_temp_dir("edge_list_below_base");
+    let patt = write_tmp(&dir, "pattern.edgelist", "1 2\n");
+    let targ = write_tmp(
+        &dir,
+        "target.edgelist",
+        "# zero is invalid one-based\n0 1\n",
+    );
+
+    let opts = RunOptions {
+        format: GraphFormat::EdgeList,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let err = run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VF3Error::Parse { line: 2, .. }),
+        "Unexpected error: {err:?}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+const GRAPHML_PATTERN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="label" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="label" attr.type="string">
+    <default>single</default>
+  </key>
+  <graph edgedefault="undirected">
+    <node id="c"><data key="d0">C</data></node>
+    <node id="o"><data key="d0">O</data></node>
+    <edge source="c" target="o"><data key="d1">double</data></edge>
+  </graph>
+</graphml>
+"#;
+
+const GRAPHML_TARGET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="label" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="label" attr.type="string">
+    <default>single</default>
+  </key>
+  <graph edgedefault="undirected">
+    <node id="c1"><data key="d0">C</data></node>
+    <node id="o1"><data key="d0">O</data></node>
+    <node id="o2"><data key="d0">O</data></node>
+    <node id="h1"><data key="d0">H</data></node>
+    <edge source="c1" target="o1"><data key="d1">double</data></edge>
+    <edge source="c1" target="o2"/>
+    <edge source="c1" target="h1"/>
+  </graph>
+</graphml>
+"#;
+
+#[test]
+fn graphml_string_node_and_edge_labels() {
+    let dir = unique_temp_dir("graphml_labels");
+    let patt = write_tmp(&dir, "pattern.graphml", GRAPHML_PATTERN);
+    let targ = write_tmp(&dir, "target.graphml", GRAPHML_TARGET);
+
+    let opts = RunOptions {
+        format: GraphFormat::GraphML,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .expect("GraphML run failed");
+    // Only the C=O double bond matches; the single C-O bond has another edge label.
+    assert_eq!(res.solutions, 1);
+    assert_eq!(
+        res.named_mappings().unwrap(),
+        vec![vec![("c", "c1"), ("o", "o1")]]
+    );
+
+    // Errors report a line even when the parser's offset splits a character.
+    for bad in ["\u{feff}<", "\u{feff}\n<graphml><é"] {
+        let err = Graph::from_bytes(bad.as_bytes(), GraphFormat::GraphML).unwrap_err();
+        assert!(matches!(err, VF3Error::Parse { .. }), "{err:?}");
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn graphml_unlabelled_nodes_differ_from_string_labels() {
+    let graphml = |key: &str, data: &str| {
+        format!(
+            r#"<graphml>
+  <key id="d0" for="node" attr.name="label" attr.type="{key}"/>
+  <graph edgedefault="undirected"><node id="a">{data}</node></graph>
+</graphml>"#
+        )
+    };
+    let opts = RunOptions {
+        format: GraphFormat::GraphML,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let read = |text: String, labels: &mut LabelDictionary| {
+        Graph::from_reader_with(text.as_bytes(), &opts, labels)
+    };
+
+    // The first string seen must not take the label of unlabelled nodes.
+    let mut labels = LabelDictionary::new();
+    let labelled = read(graphml("string", r#"<data key="d0">C</data>"#), &mut labels).unwrap();
+    let unlabelled = read(graphml("string", ""), &mut labels).unwrap();
+    assert_ne!(labelled.node_label(0), unlabelled.node_label(0));
+    for (pattern, target) in [(&labelled, &unlabelled), (&unlabelled, &labelled)] {
+        assert_eq!(
+            run_vf3_graphs(pattern, target, opts.clone())
+                .unwrap()
+                .solutions,
+            0
+        );
+    }
+
+    // Integer labels would share their values with interned strings.
+    let err = read(graphml("int", r#"<data key="d0">1</data>"#), &mut labels).unwrap_err();
+    assert!(matches!(err, VF3Error::Parse { line: 2, .. }), "{err:?}");
+    let mut labels = LabelDictionary::new();
+    read(graphml("int", r#"<data key="d0">1</data>"#), &mut labels).unwrap();
+    assert!(read(graphml("string", ""), &mut labels).is_err());
+}
+
+#[test]
+fn graphml_edge_directedness() {
+    let mixed = r#"<graphml>
+  <graph edgedefault="directed">
+    <node id="a"/><node id="b"/><node id="c"/>
+    <edge source="a" target="b"/>
+    <edge source="b" target="c" directed="false"/>
+  </graph>
+</graphml>"#;
+    let graph = graphml::read(
+        mixed.as_bytes(),
+        &GraphMlOptions::default(),
+        &mut LabelDictionary::new(),
+    )
+    .expect("Failed to read GraphML");
+    assert!(graph.is_directed());
+    assert!(graph.has_edge(0, 1) && !graph.has_edge(1, 0));
+    assert!(graph.has_edge(1, 2) && graph.has_edge(2, 1));
+
+    let invalid = r#"<graphml>
+  <graph edgedefault="undirected">
+    <node id="a"/><node id="b"/>
+    <edge source="a" target="b" directed="true"/>
+  </graph>
+</graphml>"#;
+    let err = graphml::read(
+        invalid.as_bytes(),
+        &GraphMlOptions::default(),
+        &mut LabelDictionary::new(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VF3Error::Parse { line: 4, .. }),
+        "Unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn graphml_write_read_round_trip() {
+    let mut graph = Graph::undirected();
+    for (name, label) in [("x", 3), ("y & z", -1), ("w", 3)] {
+        let node = graph.add_node(label);
+        graph.set_node_name(node, name);
+    }
+    graph.add_labeled_edge(0, 1, 7);
+    graph.add_labeled_edge(2, 1, 8);
+
+    let mut buf = Vec::new();
+    graphml::write(&mut buf, &graph).expect("Failed to write GraphML");
+    let read = graphml::read(
+        buf.as_slice(),
+        &GraphMlOptions::default(),
+        &mut LabelDictionary::new(),
+    )
+    .expect("Failed to read written GraphML");
+    assert_eq!(read, graph);
+}
+
+#[test]
+fn graphml_write_match_exports_matched_subgraph() {
+    let mut labels = LabelDictionary::new();
+    let opts = GraphMlOptions::default();
+    let pattern = graphml::read(GRAPHML_PATTERN.as_bytes(), &opts, &mut labels).unwrap();
+    let target = graphml::read(GRAPHML_TARGET.as_bytes(), &opts, &mut labels).unwrap();
+    let res = run_vf3_graphs(
+        &pattern,
+        &target,
+        RunOptions {
+            store_solutions: true,
+            repetition_time_limit: 0.0,
+            ..Default::default()
+        },
+    )
+    .expect("In-memory VF3 failed");
+
+    let mut buf = Vec::new();
+    graphml::write_match(&mut buf, &pattern, &target, &res.mappings[0])
+        .expect("Failed to write match");
+    let text = String::from_utf8(buf).unwrap();
+    assert!(text.contains(r#"<node id="c1">"#) && text.contains(r#"<node id="o1">"#));
+    assert!(!text.contains("o2") && !text.contains("h1"));
+    assert!(text.contains(r#"<data key="pattern">c</data>"#));
+
+    // Labels are written as integers, so they are read without the strings.
+    let matched = graphml::read(text.as_bytes(), &opts, &mut LabelDictionary::new()).unwrap();
+    assert_eq!(matched.node_count(), 2);
+    assert_eq!(matched.edge_count(), 1);
+    assert_eq!(matched.node_label(0), target.node_label(0));
+    assert_eq!(matched.edge_label(0, 1), target.edge_label(0, 1));
+}
+
+fn run_files(dir: &Path, pattern: &str, target: &str, opts: RunOptions) -> vf3lib_rs::ResultData {
+    let patt = write_tmp(dir, "pattern", pattern);
+    let targ = write_tmp(dir, "target", target);
+    run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .expect("VF3 run failed")
+}
+
+#[test]
+fn lad_unlabelled_triangle() {
+    let dir = unique_temp_dir("lad_unlabelled");
+    // Undirected triangle and a square with one diagonal, both ends listed.
+    let pattern = "3\n2 1 2\n2 0 2\n2 0 1\n";
+    let target = "4\n3 1 2 3\n2 0 2\n3 0 1 3\n2 0 2\n";
+    let opts = RunOptions {
+        format: GraphFormat::Lad,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_files(&dir, pattern, target, opts);
+    // Two triangles, six automorphisms each.
+    assert_eq!(res.solutions, 12);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn lad_node_and_edge_labels() {
+    let dir = unique_temp_dir("lad_labelled");
+    // Pattern: 7 -> 8 with edge label 1.
+    let pattern = "2\n7 1 1 1\n8 0\n";
+    // Target: 7 -> 8 (label 1), 7 -> 8 (label 2), 8 -> 7 (label 1).
+    let target = "4\n7 1 1 1\n8 0\n7 1 3 2\n8 1 2 1\n";
+    let opts = RunOptions {
+        format: GraphFormat::Lad,
+        lad: LadOptions {
+            node_labels: true,
+            edge_labels: true,
+        },
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_files(&dir, pattern, target, opts);
+    assert_eq!(res.mappings, vec![vec![(0, 0), (1, 1)]]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn lad_successor_out_of_range_is_parse_error() {
+    let dir = unique_temp_dir("lad_out_of_range");
+    let patt = write_tmp(&dir, "pattern.lad", "2\n1 1\n1 0\n");
+    let targ = write_tmp(&dir, "target.lad", "2\n1 1\n1 2\n");
+    let opts = RunOptions {
+        format: GraphFormat::Lad,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let err = run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VF3Error::Parse { line: 3, .. }),
+        "Unexpected error: {err:?}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn dimacs_labelled_triangle() {
+    let dir = unique_temp_dir("dimacs");
+    let pattern = "c labelled path\np edge 3 2\nn 1 5\ne 1 2\ne 2 3\n";
+    // K4 where only node 4 carries label 5; repeated edges are ignored.
+    let target = "p edge 4 6\nn 4 5\ne 1 2\ne 1 3\ne 1 4\ne 2 3\ne 2 4\ne 3 4\ne 4 3\n";
+    let opts = RunOptions {
+        format: GraphFormat::Dimacs,
+        edge_induced: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_files(&dir, pattern, target, opts);
+    // Node 1 of the pattern must map to node 4; the other two take any ordered pair.
+    assert_eq!(res.solutions, 6);
+
+    // A declared node count is not trusted to fit in memory.
+    let err =
+        Graph::from_bytes(b"p edge 18446744073709551615 0\n", GraphFormat::Dimacs).unwrap_err();
+    assert!(matches!(err, VF3Error::Parse { .. }), "{err:?}");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn gfu_string_labels() {
+    let dir = unique_temp_dir("gfu");
+    let pattern = "#query\n2\nC\nO\n1\n0 1\n";
+    let target = "#molecule\n4\nH\nC\nO\nO\n3\n1 0\n1 2\n1 3\n";
+    let opts = RunOptions {
+        format: GraphFormat::Gfu,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let mut res = run_files(&dir, pattern, target, opts);
+    res.mappings.sort();
+    assert_eq!(
+        res.mappings,
+        vec![vec![(0, 1), (1, 2)], vec![(0, 1), (1, 3)]]
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn gfd_multi_graph_file_yields_collection() {
+    let data = "#first\n2\nA\nB\n1\n0 1\n\n#second\n3\nB\nA\nA\n2\n1 0\n2 0\n";
+    let mut labels = LabelDictionary::new();
+    let graphs = ri::read(data.as_bytes(), true, &mut labels).expect("Failed to read .gfd");
+    assert_eq!(graphs.len(), 2);
+    assert_eq!(graphs[0].name, "first");
+    assert_eq!(graphs[1].name, "second");
+    assert!(graphs[1].graph.is_directed());
+    assert_eq!(graphs[1].graph.edge_count(), 2);
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels.get("B"), Some(graphs[1].graph.node_label(0)));
+
+    let counts: Vec<u64> = graphs
+        .iter()
+        .map(|target| {
+            run_vf3_graphs(
+                &graphs[0].graph,
+                &target.graph,
+                RunOptions {
+                    repetition_time_limit: 0.0,
+                    ..Default::default()
+                },
+            )
+            .expect("In-memory VF3 failed")
+            .solutions
+        })
+        .collect();
+    assert_eq!(counts, vec![1, 2]);
+
+    // The path-based runners need exactly one graph per file.
+    let dir = unique_temp_dir("gfd_multi");
+    let patt = write_tmp(&dir, "pattern.gfd", "#p\n1\nA\n0\n");
+    let targ = write_tmp(&dir, "target.gfd", data);
+    let opts = RunOptions {
+        format: GraphFormat::Gfd,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let err = run_vf3(
+        patt.to_string_lossy().as_ref(),
+        targ.to_string_lossy().as_ref(),
+        opts,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VF3Error::Parse { .. }),
+        "Unexpected error: {err:?}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn graph6_family_decodes_reference_strings() {
+    // Examples from nauty's formats.txt.
+    let g = graph6::decode("DQc").expect("graph6");
+    assert!(!g.is_directed());
+    assert_eq!(g.node_count(), 5);
+    for (u, v) in [(0, 2), (0, 4), (1, 3), (3, 4)] {
+        assert!(g.has_edge(u, v), "missing {u}-{v}");
+    }
+    assert_eq!(g.edge_count(), 4);
+
+    let s = graph6::decode(":Fa@x^").expect("sparse6");
+    assert_eq!(s.node_count(), 7);
+    for (u, v) in [(0, 1), (0, 2), (1, 2), (5, 6)] {
+        assert!(s.has_edge(u, v), "missing {u}-{v}");
+    }
+    assert_eq!(s.edge_count(), 4);
+
+    let d = graph6::decode("&DI?AO?").expect("digraph6");
+    assert!(d.is_directed());
+    for (u, v) in [(0, 2), (0, 4), (3, 1), (3, 4)] {
+        assert!(d.has_edge(u, v), "missing {u}->{v}");
+    }
+    assert_eq!(d.edge_count(), 4);
+
+    assert_eq!(graph6::encode(&g, Graph6Format::Graph6).unwrap(), "DQc");
+    assert_eq!(graph6::encode(&s, Graph6Format::Sparse6).unwrap(), ":Fa@x^");
+    assert_eq!(
+        graph6::encode(&d, Graph6Format::Digraph6).unwrap(),
+        "&DI?AO?"
+    );
+    assert!(matches!(
+        graph6::encode(&d, Graph6Format::Graph6),
+        Err(VF3Error::UnsupportedFormat { .. })
+    ));
+
+    // A `~~` size needs six bytes; fewer must not be read as a `~` size.
+    for truncated in ["~~", "~~~~~", "~~??", ":~~??~", "&~~???"] {
+        assert!(
+            matches!(graph6::decode(truncated), Err(VF3Error::Parse { .. })),
+            "{truncated}"
+        );
+    }
+}
+
+#[test]
+fn graph6_family_round_trips() {
+    let mut graphs = Vec::new();
+    // Sizes around the sparse6 padding corner case (n a power of two) and
+    // the multi-byte node count.
+    for n in [0u32, 1, 2, 4, 8, 16, 63, 64] {
+        let mut undirected = Graph::undirected();
+        let mut directed = Graph::directed();
+        for _ in 0..n {
+            undirected.add_node(0);
+            directed.add_node(0);
+        }
+        // The last undirected node stays isolated, which is the sparse6
+        // corner case whenever n is a power of two.
+        for v in 1..n.saturating_sub(1) {
+            undirected.add_edge(v / 2, v);
+            if v >= 2 {
+                undirected.add_edge(v - 2, v);
+            }
+        }
+        for v in 1..n {
+            directed.add_edge(v, v / 2);
+            if v >= 2 {
+                directed.add_edge(v - 2, v);
+            }
+        }
+        graphs.push(undirected);
+        graphs.push(directed);
+    }
+
+    for graph in &graphs {
+        let formats: &[Graph6Format] = if graph.is_directed() {
+            &[Graph6Format::Digraph6]
+        } else {
+            &[Graph6Format::Graph6, Graph6Format::Sparse6]
+        };
+        for &format in formats {
+            let mut buf = Vec::new();
+            graph6::write(&mut buf, [graph], format).expect("write");
+            let read = graph6::read(buf.as_slice()).expect("read");
+            assert_eq!(
+                read,
+                vec![graph.clone()],
+                "{format:?} {}",
+                String::from_utf8_lossy(&buf)
+            );
+        }
+    }
+}
+
+#[test]
+fn graph6_files_run_by_path() {
+    let dir = unique_temp_dir("graph6");
+    // Triangle inside K4 (graph6 `Bw` and `C~`).
+    let opts = RunOptions {
+        format: GraphFormat::Graph6,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_files(&dir, ">>graph6<<Bw\n", "C~\n", opts);
+    assert_eq!(res.solutions, 24);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn grf_writer_round_trips_with_loader() {
+    let dir = unique_temp_dir("grf_round_trip");
+    let (patt, targ) = default_bvg_pair();
+    let read = |path: &str| grf::read(BufReader::new(fs::File::open(path).unwrap()), false);
+    let (pattern, target) = (read(&patt).unwrap(), read(&targ).unwrap());
+
+    let mut written = Vec::new();
+    for (name, graph) in [("pattern.grf", &pattern), ("target.grf", &target)] {
+        let mut buf = Vec::new();
+        grf::write(&mut buf, graph).expect("Failed to write .grf");
+        assert_eq!(&grf::read(buf.as_slice(), false).unwrap(), graph);
+        written.push(write_tmp(&dir, name, std::str::from_utf8(&buf).unwrap()));
+    }
+
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let original = run_vf3(&patt, &targ, opts.clone()).expect("VF3 on fixtures failed");
+    let rewritten = run_vf3(
+        written[0].to_string_lossy().as_ref(),
+        written[1].to_string_lossy().as_ref(),
+        opts,
+    )
+    .expect("VF3 on rewritten files failed");
+    assert_eq!(rewritten.solutions, original.solutions);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn grf_writer_undirected_and_labels() {
+    let dir = unique_temp_dir("grf_undirected");
+    let mut path = Graph::undirected();
+    let nodes: Vec<_> = [5, -3, 5].into_iter().map(|l| path.add_node(l)).collect();
+    path.add_edge(nodes[1], nodes[0]);
+    path.add_edge(nodes[1], nodes[2]);
+
+    let mut buf = Vec::new();
+    grf::write(&mut buf, &path).expect("Failed to write .grf");
+    let text = String::from_utf8(buf).unwrap();
+    assert_eq!(text, "3\n0 5\n1 -3\n2 5\n1\n0 1\n1\n1 2\n0\n");
+    assert_eq!(grf::read(text.as_bytes(), true).unwrap(), path);
+
+    // The vf3lib loader needs the undirected flag to restore both directions.
+    let file = write_tmp(&dir, "path.grf", &text);
+    let file = file.to_string_lossy();
+    let opts = RunOptions {
+        undirected: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3(file.as_ref(), file.as_ref(), opts).expect("VF3 failed");
+    assert_eq!(res.solutions, 2);
+
+    let mut labelled = Graph::directed();
+    let (a, b) = (labelled.add_node(0), labelled.add_node(0));
+    labelled.add_labeled_edge(a, b, 4);
+    assert!(matches!(
+        grf::write(Vec::new(), &labelled),
+        Err(VF3Error::InvalidGraph { .. })
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn patterns_with_isolated_nodes() {
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let mut target = Graph::undirected();
+    let nodes: Vec<_> = (0..3).map(|_| target.add_node(0)).collect();
+    target.add_edge(nodes[1], nodes[2]);
+
+    let mut pair = Graph::undirected();
+    pair.add_node(0);
+    pair.add_node(0);
+    let res = run_vf3_graphs(&pair, &target, opts.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 4);
+    let res = run_vf3l_graphs(&pair, &pair, opts.clone()).expect("VF3L failed");
+    assert_eq!(res.solutions, 2);
+
+    // An isolated node sorted ahead of connected ones.
+    let mut lone_first = Graph::undirected();
+    let nodes: Vec<_> = (0..3).map(|_| lone_first.add_node(0)).collect();
+    lone_first.add_edge(nodes[1], nodes[2]);
+    let res = run_vf3_graphs(&lone_first, &target, opts).expect("VF3 failed");
+    assert_eq!(res.solutions, 2);
+}
+
+#[test]
+fn empty_pattern_has_one_empty_match() {
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let (_, target_path) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let target = Graph::from_reader(fs::File::open(&target_path).unwrap(), GraphFormat::VFLegacy)
+        .expect("Failed to read target");
+    let pattern = Graph::directed();
+    let expected = vec![Vec::new()];
+
+    let res = run_vf3_graphs(&pattern, &target, opts.clone()).expect("VF3 failed");
+    assert_eq!((res.solutions, res.mappings), (1, expected.clone()));
+    let res = run_vf3l_graphs(&pattern, &target, opts.clone()).expect("VF3L failed");
+    assert_eq!((res.solutions, res.mappings), (1, expected.clone()));
+
+    let dir = unique_temp_dir("empty_pattern");
+    let pattern_path = write_tmp(&dir, "empty.grf", "0\n");
+    let pattern_path = pattern_path.to_string_lossy();
+    let res = run_vf3(&pattern_path, &target_path, opts.clone()).expect("VF3 failed");
+    assert_eq!((res.solutions, res.mappings), (1, expected.clone()));
+    #[cfg(target_os = "linux")]
+    {
+        let res = run_vf3p(
+            &pattern_path,
+            &target_path,
+            opts,
+            ParallelOptions::default(),
+        )
+        .expect("VF3P failed");
+        assert_eq!((res.solutions, res.mappings), (1, expected));
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn csr_files_match_like_their_source() {
+    let dir = unique_temp_dir("csr");
+    let (patt, targ) = bvg2_pair();
+    let (patt_csr, targ_csr) = (dir.join("pattern.csr"), dir.join("target.csr"));
+    let grf_opts = RunOptions::default();
+    csr::convert(&patt, &patt_csr, &grf_opts).expect("Failed to convert pattern");
+    csr::convert(&targ, &targ_csr, &grf_opts).expect("Failed to convert target");
+
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let expected = run_vf3(&patt, &targ, opts.clone()).expect("VF3 on .grf failed");
+    let res = run_vf3(
+        patt_csr.to_string_lossy().as_ref(),
+        targ_csr.to_string_lossy().as_ref(),
+        RunOptions {
+            format: GraphFormat::Csr,
+            ..opts.clone()
+        },
+    )
+    .expect("VF3 on CSR files failed");
+    assert_eq!(res.solutions, expected.solutions);
+
+    // A mapped target can be combined with an in-memory pattern.
+    let pattern = grf::read(BufReader::new(fs::File::open(&patt).unwrap()), false).unwrap();
+    let target = csr::MappedGraph::open(&targ_csr).expect("Failed to map target");
+    let mixed = run_vf3l_graphs(&pattern, &target, opts).expect("VF3L on mapped target failed");
+    assert_eq!(mixed.solutions, expected.solutions);
+
+    let mut buf = Vec::new();
+    csr::write(&mut buf, &pattern).unwrap();
+    assert_eq!(buf, fs::read(&patt_csr).unwrap());
+    assert_eq!(csr::read(buf.as_slice()).unwrap(), pattern);
+    assert_eq!(target.to_graph().edge_count(), target.edge_count());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+/// A binary CSR file with two unlabelled nodes and the given arcs.
+fn csr_bytes(directed: bool, offsets: [u32; 3], arcs: &[(u32, i32)]) -> Vec<u8> {
+    let mut bytes = b"VF3CSR\0\0".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(u32::from(directed).to_le_bytes());
+    bytes.extend(2u64.to_le_bytes());
+    bytes.extend((arcs.len() as u64).to_le_bytes());
+    bytes.extend([0u8; 8]);
+    for offset in offsets {
+        bytes.extend(offset.to_le_bytes());
+    }
+    for &(to, _) in arcs {
+        bytes.extend(to.to_le_bytes());
+    }
+    for &(_, label) in arcs {
+        bytes.extend(label.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn csr_undirected_files_must_be_symmetric() {
+    let dir = unique_temp_dir("csr_symmetric");
+    let one_way = csr_bytes(false, [0, 1, 1], &[(1, 0)]);
+    let relabelled = csr_bytes(false, [0, 1, 2], &[(1, 3), (0, 4)]);
+    for (name, bytes) in [("one_way", &one_way), ("relabelled", &relabelled)] {
+        assert!(
+            matches!(csr::read(bytes.as_slice()), Err(VF3Error::Parse { .. })),
+            "{name}"
+        );
+        let path = dir.join(format!("{name}.csr"));
+        fs::write(&path, bytes).unwrap();
+        assert!(
+            matches!(csr::MappedGraph::open(&path), Err(VF3Error::Parse { .. })),
+            "{name}"
+        );
+    }
+
+    // The same arcs are fine in a directed file.
+    let directed = csr::read(csr_bytes(true, [0, 1, 1], &[(1, 0)]).as_slice()).unwrap();
+    assert_eq!(directed.edge_count(), 1);
+    let both = csr_bytes(false, [0, 1, 2], &[(1, 3), (0, 3)]);
+    let path = dir.join("both.csr");
+    fs::write(&path, &both).unwrap();
+    let mapped = csr::MappedGraph::open(&path).unwrap();
+    assert_eq!(mapped.edge_count(), 1);
+    assert_eq!(mapped.to_graph().edge_label(1, 0), Some(3));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn csr_from_edge_list_and_corrupt_files() {
+    let dir = unique_temp_dir("csr_edge_list");
+    let edges = write_tmp(&dir, "triangle.edgelist", "1 2\n2 3\n3 1\n");
+    let out = dir.join("triangle.csr");
+    let opts = RunOptions {
+        format: GraphFormat::EdgeList,
+        undirected: true,
+        ..Default::default()
+    };
+    csr::convert(&edges, &out, &opts).expect("Failed to convert edge list");
+    let mapped = csr::MappedGraph::open(&out).unwrap();
+    assert!(!mapped.is_directed());
+    assert_eq!((mapped.node_count(), mapped.edge_count()), (3, 3));
+
+    let mut bytes = fs::read(&out).unwrap();
+    let truncated = write_tmp(&dir, "truncated.csr", "");
+    fs::write(&truncated, &bytes[..bytes.len() - 4]).unwrap();
+    assert!(matches!(
+        csr::MappedGraph::open(&truncated),
+        Err(VF3Error::Parse { .. })
+    ));
+
+    // Point the first successor outside the graph.
+    let first_target = 32 + 4 * (3 + 4);
+    bytes[first_target..first_target + 4].copy_from_slice(&7u32.to_le_bytes());
+    assert!(matches!(
+        csr::read(bytes.as_slice()),
+        Err(VF3Error::Parse { .. })
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn in_memory_graphs_return_mappings() {
+    let mut pattern = Graph::directed();
+    let (a, b) = (pattern.add_node(0), pattern.add_node(1));
+    pattern.add_edge(a, b);
+
+    let mut target = Graph::directed();
+    let nodes: Vec<_> = [1, 0, 1].into_iter().map(|l| target.add_node(l)).collect();
+    target.add_edge(nodes[1], nodes[0]);
+    target.add_edge(nodes[1], nodes[2]);
+
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let mut res = run_vf3_graphs(&pattern, &target, opts).expect("In-memory VF3 failed");
+    res.mappings.sort();
+    assert_eq!(res.solutions, 2);
+    assert_eq!(
+        res.mappings,
+        vec![vec![(0, 1), (1, 0)], vec![(0, 1), (1, 2)]]
+    );
+    assert!(res.named_mappings().is_none());
+}
+
+#[test]
+fn undirected_pattern_against_directed_target() {
+    let mut pattern = Graph::undirected();
+    let (a, b) = (pattern.add_node(0), pattern.add_node(1));
+    pattern.add_edge(a, b);
+
+    // 0 -> 1 is one-way, 2 <-> 3 is reciprocated.
+    let mut target = Graph::directed();
+    for label in [0, 1, 0, 1] {
+        target.add_node(label);
+    }
+    target.add_edge(0, 1);
+    target.add_edge(2, 3);
+    target.add_edge(3, 2);
+
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3_graphs(&pattern, &target, opts.clone()).expect("Reciprocal VF3 failed");
+    assert_eq!(res.mappings, vec![vec![(0, 2), (1, 3)]]);
+
+    let either = RunOptions {
+        mixed_edges: MixedEdges::Either,
+        ..opts.clone()
+    };
+    let mut res = run_vf3l_graphs(&pattern, &target, either).expect("Either VF3L failed");
+    res.mappings.sort();
+    assert_eq!(
+        res.mappings,
+        vec![vec![(0, 0), (1, 1)], vec![(0, 2), (1, 3)]]
+    );
+
+    // A directed pattern reads an undirected target edge both ways.
+    let mut directed = Graph::directed();
+    let (a, b) = (directed.add_node(0), directed.add_node(1));
+    directed.add_edge(a, b);
+    let res = run_vf3_graphs(&directed, &pattern, opts).expect("Directed pattern VF3 failed");
+    assert_eq!(res.mappings, vec![vec![(0, 0), (1, 1)]]);
+}
+
+#[test]
+fn per_graph_directedness_for_files() {
+    let dir = unique_temp_dir("mixed_directedness");
+    let pattern = write_tmp(&dir, "pattern.grf", "2\n0 0\n1 0\n1\n0 1\n0\n");
+    let target = write_tmp(
+        &dir,
+        "target.grf",
+        "3\n0 0\n1 0\n2 0\n1\n0 1\n2\n1 0\n1 2\n0\n",
+    );
+    let (pattern, target) = (pattern.to_string_lossy(), target.to_string_lossy());
+    let opts = RunOptions {
+        pattern_undirected: Some(true),
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+
+    let res = run_vf3(&pattern, &target, opts.clone()).expect("Reciprocal VF3 failed");
+    assert_eq!(res.solutions, 2);
+    let res = VF3Query::new(&pattern, &target)
+        .pattern_undirected(true)
+        .mixed_edges(MixedEdges::Either)
+        .repetition_time_limit(0.0)
+        .run_light()
+        .expect("Either VF3L failed");
+    assert_eq!(res.solutions, 4);
+
+    // Both graphs undirected through the per-graph flags takes the C++ path.
+    let both = RunOptions {
+        target_undirected: Some(true),
+        ..opts
+    };
+    let res = run_vf3(&pattern, &target, both).expect("Undirected VF3 failed");
+    assert_eq!(res.solutions, 4);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn homomorphism_folds_path_onto_edge() {
+    let mut path = Graph::undirected();
+    for _ in 0..3 {
+        path.add_node(0);
+    }
+    path.add_edge(0, 1);
+    path.add_edge(1, 2);
+    let mut edge = Graph::undirected();
+    let (x, y) = (edge.add_node(0), edge.add_node(0));
+    edge.add_edge(x, y);
+
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3_graphs(&path, &edge, opts.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 0);
+
+    let hom = RunOptions {
+        homomorphism: true,
+        ..opts
+    };
+    let mut res = run_vf3_graphs(&path, &edge, hom).expect("Homomorphism failed");
+    res.mappings.sort();
+    assert_eq!(
+        res.mappings,
+        vec![vec![(0, 0), (1, 1), (2, 0)], vec![(0, 1), (1, 0), (2, 1)]]
+    );
+}
+
+#[test]
+fn may_share_allows_partial_injectivity() {
+    // A centre labelled 0 with two leaves labelled 1, in pattern and target.
+    let mut star = Graph::undirected();
+    let centre = star.add_node(0);
+    for _ in 0..2 {
+        let leaf = star.add_node(1);
+        star.add_edge(centre, leaf);
+    }
+
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let solutions = |may_share: Vec<u32>, edge_induced| {
+        let opts = RunOptions {
+            may_share,
+            edge_induced,
+            ..opts.clone()
+        };
+        run_vf3l_graphs(&star, &star, opts)
+            .expect("VF3L failed")
+            .solutions
+    };
+    assert_eq!(solutions(vec![], true), 2);
+    // One listed leaf cannot share with an unlisted one.
+    assert_eq!(solutions(vec![1], true), 2);
+    assert_eq!(solutions(vec![1, 2], true), 4);
+    assert_eq!(solutions(vec![1, 2], false), 4);
+    // Adjacent nodes never share.
+    assert_eq!(solutions(vec![0, 1, 2], true), 4);
+
+    let err = run_vf3_graphs(
+        &star,
+        &star,
+        RunOptions {
+            may_share: vec![3],
+            ..opts
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, VF3Error::InvalidGraph { .. }), "{err:?}");
+}
+
+#[test]
+fn non_injective_search_agrees_with_vf3_when_nothing_shares() {
+    let (patt, targ) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    for edge_induced in [false, true] {
+        let opts = RunOptions {
+            edge_induced,
+            store_solutions: true,
+            repetition_time_limit: 0.0,
+            ..Default::default()
+        };
+        let mut expected = run_vf3(&patt, &targ, opts.clone()).expect("VF3 failed");
+        // A single listed node has nobody to share with.
+        let shared = RunOptions {
+            may_share: vec![0],
+            ..opts
+        };
+        let mut res = run_vf3(&patt, &targ, shared).expect("Rust search failed");
+        expected.mappings.sort();
+        res.mappings.sort();
+        assert!(expected.solutions > 0);
+        assert_eq!(res.solutions, expected.solutions);
+        assert_eq!(res.mappings, expected.mappings);
+    }
+
+    let res = VF3Query::new(&patt, &targ)
+        .homomorphism()
+        .first_only()
+        .repetition_time_limit(0.0)
+        .run()
+        .expect("Homomorphism query failed");
+    assert_eq!(res.solutions, 1);
+}
+
+#[test]
+fn tolerance_reports_violations() {
+    let mut triangle = Graph::undirected();
+    for _ in 0..3 {
+        triangle.add_node(0);
+    }
+    triangle.add_edge(0, 1);
+    triangle.add_edge(1, 2);
+    triangle.add_edge(0, 2);
+    let mut path = Graph::undirected();
+    for label in [0, 1, 0] {
+        path.add_node(label);
+    }
+    path.add_edge(0, 1);
+    path.add_edge(1, 2);
+
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3_graphs(&triangle, &path, opts.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 0);
+    assert!(res.violations.is_empty());
+
+    // Every bijection misses one triangle edge and the label of the middle node.
+    let one_edge = RunOptions {
+        tolerance: Tolerance {
+            edges: 1,
+            labels: 0,
+        },
+        ..opts.clone()
+    };
+    let res = run_vf3_graphs(&triangle, &path, one_edge).expect("Tolerant VF3 failed");
+    assert_eq!(res.solutions, 0);
+    let tolerant = RunOptions {
+        tolerance: Tolerance {
+            edges: 1,
+            labels: 1,
+        },
+        ..opts.clone()
+    };
+    let res = run_vf3_graphs(&triangle, &path, tolerant).expect("Tolerant VF3 failed");
+    assert_eq!(res.solutions, 6);
+    assert_eq!(res.violations.len(), 6);
+    for (mapping, violations) in res.mappings.iter().zip(&res.violations) {
+        let middle = mapping.iter().find(|&&(_, t)| t == 1).unwrap().0;
+        let mut ends: Vec<_> = mapping
+            .iter()
+            .filter(|&&(_, t)| t != 1)
+            .map(|&(p, _)| p)
+            .collect();
+        ends.sort();
+        assert_eq!(violations.missing_edges, vec![(ends[0], ends[1])]);
+        assert_eq!(violations.node_labels, vec![middle]);
+        assert!(violations.extra_edges.is_empty() && violations.edge_labels.is_empty());
+    }
+
+    // Node-induced matching counts target edges the pattern lacks.
+    let mut unlabelled_path = path.clone();
+    unlabelled_path.set_node_label(1, 0);
+    let res = run_vf3_graphs(&unlabelled_path, &triangle, opts.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 0);
+    let tolerant = RunOptions {
+        tolerance: Tolerance {
+            edges: 1,
+            labels: 0,
+        },
+        ..opts
+    };
+    let res =
+        run_vf3_graphs(&unlabelled_path, &triangle, tolerant.clone()).expect("Tolerant VF3 failed");
+    assert_eq!(res.solutions, 6);
+    assert!(res.violations.iter().all(|v| v.extra_edges == vec![(0, 2)]));
+    let edge_induced = RunOptions {
+        edge_induced: true,
+        ..tolerant
+    };
+    let res =
+        run_vf3_graphs(&unlabelled_path, &triangle, edge_induced).expect("Tolerant VF3 failed");
+    assert_eq!(res.solutions, 6);
+    assert!(res.violations.iter().all(|v| *v == Violations::default()));
+}
+
+#[test]
+fn tolerance_for_directed_edges_and_files() {
+    let mut cycle = Graph::directed();
+    for _ in 0..3 {
+        cycle.add_node(0);
+    }
+    cycle.add_edge(0, 1);
+    cycle.add_edge(1, 2);
+    cycle.add_edge(2, 0);
+    let mut bent = Graph::directed();
+    for _ in 0..3 {
+        bent.add_node(0);
+    }
+    bent.add_edge(0, 1);
+    bent.add_labeled_edge(1, 2, 7);
+    bent.add_edge(0, 2);
+
+    let opts = RunOptions {
+        edge_induced: true,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        tolerance: Tolerance {
+            edges: 0,
+            labels: 1,
+        },
+        ..Default::default()
+    };
+    let res = run_vf3_graphs(&cycle, &bent, opts.clone()).expect("Tolerant VF3 failed");
+    assert_eq!(res.solutions, 0);
+    let opts = RunOptions {
+        tolerance: Tolerance {
+            edges: 1,
+            labels: 1,
+        },
+        ..opts
+    };
+    let res = run_vf3_graphs(&cycle, &bent, opts).expect("Tolerant VF3 failed");
+    assert!(res.solutions > 0);
+    for violations in &res.violations {
+        assert_eq!(violations.missing_edges.len(), 1);
+        assert!(violations.edge_labels.len() <= 1);
+    }
+
+    let dir = unique_temp_dir("tolerance");
+    let pattern = write_tmp(
+        &dir,
+        "cycle.grf",
+        "3\n0 0\n1 0\n2 0\n1\n0 1\n1\n1 2\n1\n2 0\n",
+    );
+    let target = write_tmp(
+        &dir,
+        "bent.grf",
+        "3\n0 0\n1 0\n2 0\n2\n0 1\n0 2\n1\n1 2\n0\n",
+    );
+    let (pattern, target) = (pattern.to_string_lossy(), target.to_string_lossy());
+    let exact = VF3Query::new(&pattern, &target)
+        .edge_induced()
+        .repetition_time_limit(0.0)
+        .run()
+        .expect("VF3 failed");
+    assert_eq!(exact.solutions, 0);
+    let res = VF3Query::new(&pattern, &target)
+        .edge_induced()
+        .tolerance(Tolerance {
+            edges: 1,
+            labels: 0,
+        })
+        .store_solutions()
+        .repetition_time_limit(0.0)
+        .run()
+        .expect("Tolerant query failed");
+    assert_eq!(res.solutions, 3);
+    assert_eq!(res.violations.len(), 3);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+fn complete_graph(labels: &[i32]) -> Graph {
+    let mut graph = Graph::undirected();
+    for &label in labels {
+        graph.add_node(label);
+    }
+    for from in 0..labels.len() as u32 {
+        for to in from + 1..labels.len() as u32 {
+            graph.add_edge(from, to);
+        }
+    }
+    graph
+}
+
+#[test]
+fn distinct_occurrences_ignore_pattern_symmetry() {
+    let triangle = complete_graph(&[0, 0, 0]);
+    let k4 = complete_graph(&[0, 0, 0, 0]);
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let res = run_vf3_graphs(&triangle, &k4, opts.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 24);
+
+    let distinct = RunOptions {
+        distinct_occurrences: true,
+        store_solutions: true,
+        ..opts.clone()
+    };
+    let res = run_vf3_graphs(&triangle, &k4, distinct.clone()).expect("VF3 failed");
+    assert_eq!(res.solutions, 4);
+    let mut node_sets: Vec<Vec<u32>> = res
+        .mappings
+        .iter()
+        .map(|m| {
+            let mut nodes: Vec<_> = m.iter().map(|&(_, t)| t).collect();
+            nodes.sort();
+            nodes
+        })
+        .collect();
+    node_sets.sort();
+    node_sets.dedup();
+    assert_eq!(node_sets.len(), 4);
+
+    // Labels reduce the symmetry: only the two 0-labelled nodes swap.
+    let labelled = complete_graph(&[0, 0, 1]);
+    let target = complete_graph(&[0, 0, 0, 1]);
+    let res = run_vf3l_graphs(&labelled, &target, distinct.clone()).expect("VF3L failed");
+    assert_eq!((res.solutions, res.mappings.len()), (3, 3));
+
+    // Monomorphisms are told apart by their edges: a triangle holds three
+    // paths on the same nodes.
+    let mut path = Graph::undirected();
+    for _ in 0..3 {
+        path.add_node(0);
+    }
+    path.add_edge(0, 1);
+    path.add_edge(1, 2);
+    let edge_induced = RunOptions {
+        edge_induced: true,
+        ..distinct.clone()
+    };
+    let res = run_vf3_graphs(&path, &triangle, edge_induced).expect("VF3 failed");
+    assert_eq!((res.solutions, res.mappings.len()), (3, 3));
+
+    let first = RunOptions {
+        first_only: true,
+        ..distinct.clone()
+    };
+    let res = run_vf3_graphs(&triangle, &k4, first).expect("VF3 failed");
+    assert_eq!(res.solutions, 1);
+
+    // Homomorphisms fold the path onto a single edge in two ways.
+    let edge = complete_graph(&[0, 0]);
+    let hom = RunOptions {
+        homomorphism: true,
+        store_solutions: false,
+        ..distinct
+    };
+    let res = run_vf3_graphs(&path, &edge, hom).expect("Homomorphism failed");
+    assert_eq!((res.solutions, res.mappings.len()), (1, 0));
+}
+
+#[test]
+fn distinct_occurrences_for_files() {
+    let (patt, targ) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let all = run_vf3(&patt, &targ, opts).expect("VF3 failed");
+    let distinct = VF3Query::new(&patt, &targ)
+        .distinct_occurrences()
+        .store_solutions()
+        .repetition_time_limit(0.0)
+        .run()
+        .expect("Distinct VF3 failed");
+    assert!(distinct.solutions > 0);
+    assert_eq!(all.solutions % distinct.solutions, 0);
+    assert_eq!(distinct.mappings.len() as u64, distinct.solutions);
+}
+
+#[test]
+fn graphs_from_readers_and_bytes() {
+    let (patt, targ) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let opts = RunOptions {
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let expected = run_vf3(&patt, &targ, opts.clone()).expect("VF3 on bvg1 failed");
+
+    let pattern = Graph::from_bytes(&fs::read(&patt).unwrap(), GraphFormat::VFLegacy)
+        .expect("Failed to read pattern bytes");
+    let target = Graph::from_reader(fs::File::open(&targ).unwrap(), GraphFormat::VFLegacy)
+        .expect("Failed to read target stream");
+    let res = run_vf3_graphs(&pattern, &target, opts).expect("VF3 on read graphs failed");
+    assert_eq!(res.solutions, expected.solutions);
+
+    let edges = Graph::from_bytes(b"1 2\n2 3\n", GraphFormat::EdgeList).unwrap();
+    assert_eq!((edges.node_count(), edges.edge_count()), (3, 2));
+    assert!(edges.is_directed());
+
+    let err = Graph::from_bytes(b"2\n0 0\n", GraphFormat::VFLegacy).unwrap_err();
+    assert!(matches!(err, VF3Error::Parse { .. }), "{err:?}");
+}
+
+#[test]
+fn graphs_from_readers_share_labels() {
+    let opts = RunOptions {
+        format: GraphFormat::Gfu,
+        undirected: true,
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let mut labels = LabelDictionary::new();
+    let pattern =
+        Graph::from_reader_with(&b"#q\n2\nO\nC\n1\n0 1\n"[..], &opts, &mut labels).unwrap();
+    let target =
+        Graph::from_reader_with(&b"#m\n3\nC\nO\nN\n2\n0 1\n0 2\n"[..], &opts, &mut labels).unwrap();
+    assert!(!target.is_directed());
+
+    let res = run_vf3_graphs(&pattern, &target, opts).expect("VF3 on read graphs failed");
+    assert_eq!(res.mappings, vec![vec![(0, 1), (1, 0)]]);
+}
+
 #[test]
 fn vf3_edge_induced_smoke() {
     let (pattern, target) = default_bvg_pair();
@@ -162,6 +1527,24 @@ fn vf3p_wls_lockfree_smoke() {
     assert!(res.solutions >= 1);
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn vf3p_store_solutions_collects_mappings() {
+    let (pattern, target) = default_bvg_pair();
+    let opts = RunOptions {
+        store_solutions: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    let par = ParallelOptions {
+        num_threads: 2,
+        ..Default::default()
+    };
+    let res = run_vf3p(&pattern, &target, opts, par).expect("VF3P store_solutions failed");
+    assert!(res.solutions >= 1);
+    assert_eq!(res.mappings.len() as u64, res.solutions);
+}
+
 #[test]
 fn vf3_bvg2_node_induced() {
     let (pattern, target) = bvg2_pair();
@@ -211,3 +1594,43 @@ fn bad_paths_return_error() {
         _ => panic!("Unexpected error type: {err:?}"),
     }
 }
+
+#[test]
+fn malformed_grf_files_return_error() {
+    // vf3lib's own loader exits the process or reads out of bounds on these.
+    let dir = unique_temp_dir("malformed_grf");
+    let target = write_tmp(&dir, "target.grf", "2\n0 1\n1 1\n1\n0 1\n0\n");
+    let cases = [
+        ("empty", ""),
+        ("comments_only", "# no graph\n"),
+        ("truncated", "3\n0 1\n1 1\n"),
+        ("huge_count", "4294967295\n0 0\n"),
+        ("count_overflow", "99999999999999999999\n"),
+        ("wrong_node_id", "2\n1 0\n0 0\n0\n0\n"),
+        ("edge_out_of_range", "2\n0 0\n1 0\n1\n0 7\n0\n"),
+        ("self_loop", "1\n0 0\n1\n0 0\n"),
+        ("wrong_block", "2\n0 0\n1 0\n1\n1 0\n0\n"),
+        ("huge_degree", "1\n0 0\n4294967295\n"),
+        ("negative", "-1\n"),
+        ("label_overflow", "1\n0 2147483648\n0\n"),
+        ("garbage", "2\n0 x\n"),
+    ];
+    for (name, content) in cases {
+        let pattern = write_tmp(&dir, &format!("{name}.grf"), content);
+        let opts = RunOptions {
+            repetition_time_limit: 0.0,
+            ..Default::default()
+        };
+        let err = run_vf3(
+            pattern.to_string_lossy().as_ref(),
+            target.to_string_lossy().as_ref(),
+            opts,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, vf3lib_rs::VF3Error::ExecutionFailed { .. }),
+            "{name}: {err:?}"
+        );
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,231 @@
+//! Solution callbacks and streaming solutions to JSON Lines or CSV.
+
+mod common;
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    ops::ControlFlow,
+};
+
+use common::{fixture_pair, graph, opts};
+use vf3lib_rs::{
+    Graph, GraphFormat, Mapping, RunOptions, SolutionFormat, SolutionWriter, run_vf3_graphs,
+    run_vf3_graphs_with, run_vf3l_graphs, run_vf3l_graphs_with,
+};
+#[cfg(target_os = "linux")]
+use vf3lib_rs::{ParallelOptions, run_vf3p_graphs, run_vf3p_graphs_with};
+
+/// The bvg1 pattern and target, which have 8 node-induced matches.
+fn bvg1() -> (Graph, Graph) {
+    let (pattern, target) = fixture_pair("bvg1.sub.grf", "bvg1.grf");
+    let read = |path| Graph::from_reader(File::open(path).unwrap(), GraphFormat::VFLegacy).unwrap();
+    (read(pattern), read(target))
+}
+
+#[test]
+fn visited_mappings_match_stored_ones() {
+    let (pattern, target) = bvg1();
+    let stored = opts();
+    let expected = run_vf3_graphs(&pattern, &target, stored.clone()).unwrap();
+    assert_eq!(expected.solutions, 8);
+
+    let mut visited: Vec<Mapping> = Vec::new();
+    let res = run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+        visited.push(mapping.to_vec());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(res.solutions, 8);
+    assert!(res.mappings.is_empty());
+    assert_eq!(visited, expected.mappings);
+
+    let expected = run_vf3l_graphs(&pattern, &target, stored.clone()).unwrap();
+    let mut visited: Vec<Mapping> = Vec::new();
+    run_vf3l_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+        visited.push(mapping.to_vec());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(visited, expected.mappings);
+
+    #[cfg(target_os = "linux")]
+    {
+        let par = ParallelOptions {
+            num_threads: 2,
+            ..Default::default()
+        };
+        let mut expected = run_vf3p_graphs(&pattern, &target, stored, par.clone())
+            .unwrap()
+            .mappings;
+        let mut visited: Vec<Mapping> = Vec::new();
+        run_vf3p_graphs_with(&pattern, &target, RunOptions::default(), par, |mapping| {
+            visited.push(mapping.to_vec());
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        expected.sort();
+        visited.sort();
+        assert_eq!(visited, expected);
+    }
+}
+
+#[test]
+fn break_stops_the_search() {
+    let (pattern, target) = bvg1();
+    let mut visited = 0;
+    let res = run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |_| {
+        visited += 1;
+        if visited == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+    assert_eq!((visited, res.solutions), (3, 3));
+
+    // The Rust search used for homomorphisms stops the same way.
+    let homomorphism = RunOptions {
+        homomorphism: true,
+        ..Default::default()
+    };
+    let mut visited = 0;
+    let res = run_vf3l_graphs_with(&pattern, &target, homomorphism, |_| {
+        visited += 1;
+        ControlFlow::Break(())
+    })
+    .unwrap();
+    assert_eq!((visited, res.solutions), (1, 1));
+}
+
+#[test]
+fn distinct_occurrences_are_visited_once() {
+    let triangle = graph(false, &[0, 0, 0], &[(0, 1), (1, 2), (0, 2)]);
+    let k4 = graph(
+        false,
+        &[0, 0, 0, 0],
+        &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)],
+    );
+    for homomorphism in [false, true] {
+        let distinct = RunOptions {
+            distinct_occurrences: true,
+            homomorphism,
+            ..Default::default()
+        };
+        let mut visited: Vec<Mapping> = Vec::new();
+        let res = run_vf3_graphs_with(&triangle, &k4, distinct, |mapping| {
+            visited.push(mapping.to_vec());
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!((visited.len(), res.solutions), (4, 4), "{homomorphism}");
+        let mut covered: Vec<Vec<u32>> = visited
+            .iter()
+            .map(|mapping| {
+                let mut nodes: Vec<u32> = mapping.iter().map(|&(_, t)| t).collect();
+                nodes.sort();
+                nodes
+            })
+            .collect();
+        covered.sort();
+        covered.dedup();
+        assert_eq!(covered.len(), 4, "{homomorphism}");
+    }
+}
+
+#[test]
+fn empty_pattern_is_visited_once() {
+    let (pattern, target) = (Graph::directed(), bvg1().1);
+    let mut visited: Vec<Mapping> = Vec::new();
+    let res = run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+        visited.push(mapping.to_vec());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!((res.solutions, visited), (1, vec![Vec::new()]));
+}
+
+#[test]
+#[should_panic(expected = "visitor failed")]
+fn visitor_panics_propagate() {
+    let (pattern, target) = bvg1();
+    let _ = run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |_| {
+        panic!("visitor failed")
+    });
+}
+
+#[test]
+fn writer_formats() {
+    let pattern = graph(false, &[1, 2], &[(0, 1)]);
+    let target = graph(false, &[2, 1, 2], &[(0, 1), (1, 2)]);
+    let run = |format, labels: bool| {
+        let mut out = SolutionWriter::new(Vec::new(), format);
+        if labels {
+            out = out.with_labels(&pattern, &target);
+        }
+        run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+            out.visit(mapping)
+        })
+        .unwrap();
+        assert_eq!(out.count(), 2);
+        String::from_utf8(out.finish().unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        run(SolutionFormat::JsonLines, false),
+        "{\"solution\":0,\"mapping\":[[0,1],[1,0]]}\n\
+         {\"solution\":1,\"mapping\":[[0,1],[1,2]]}\n"
+    );
+    assert_eq!(
+        run(SolutionFormat::JsonLines, true),
+        "{\"solution\":0,\"mapping\":[[0,1],[1,0]],\"labels\":[[1,1],[2,2]]}\n\
+         {\"solution\":1,\"mapping\":[[0,1],[1,2]],\"labels\":[[1,1],[2,2]]}\n"
+    );
+    assert_eq!(
+        run(SolutionFormat::Csv, false),
+        "solution,pattern,target\n0,0,1\n0,1,0\n1,0,1\n1,1,2\n"
+    );
+    assert_eq!(
+        run(SolutionFormat::Csv, true),
+        "solution,pattern,target,pattern_label,target_label\n\
+         0,0,1,1,1\n0,1,0,2,2\n1,0,1,1,1\n1,1,2,2,2\n"
+    );
+
+    // An empty result still gets its CSV header.
+    let out = SolutionWriter::new(Vec::new(), SolutionFormat::Csv);
+    assert_eq!(out.finish().unwrap(), b"solution,pattern,target\n");
+}
+
+/// Accepts `room` bytes, then fails.
+struct Full {
+    room: usize,
+}
+
+impl Write for Full {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() > self.room {
+            return Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"));
+        }
+        self.room -= buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn write_errors_stop_the_search() {
+    let (pattern, target) = bvg1();
+    let mut out = SolutionWriter::new(Full { room: 100 }, SolutionFormat::JsonLines);
+    let res = run_vf3_graphs_with(&pattern, &target, RunOptions::default(), |mapping| {
+        out.visit(mapping)
+    })
+    .unwrap();
+    assert!(res.solutions < 8);
+    assert_eq!(out.count() + 1, res.solutions);
+    let err = out.finish().err().expect("write error is reported");
+    assert_eq!(err.kind(), io::ErrorKind::StorageFull);
+}
@@ -0,0 +1,264 @@
+//! Automorphism groups and orbits.
+
+mod common;
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use common::graph;
+use vf3lib_rs::{
+    Automorphisms, Graph, NodeId, RunOptions, automorphisms, canonical_form, canonical_labeling,
+    run_vf3l_graphs,
+};
+
+fn assert_generators_preserve(graph: &Graph, group: &Automorphisms) {
+    for perm in &group.generators {
+        let mut images = perm.clone();
+        images.sort();
+        assert_eq!(
+            images,
+            (0..graph.node_count() as NodeId).collect::<Vec<_>>()
+        );
+        for node in 0..graph.node_count() as NodeId {
+            let image = perm[node as usize];
+            assert_eq!(graph.node_label(node), graph.node_label(image));
+            for &succ in graph.successors(node) {
+                assert_eq!(
+                    graph.edge_label(image, perm[succ as usize]),
+                    graph.edge_label(node, succ),
+                    "{perm:?} breaks edge {node} -> {succ}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn symmetric_graphs() {
+    let triangle = graph(false, &[0, 0, 0], &[(0, 1), (1, 2), (0, 2)]);
+    let group = automorphisms(&triangle).expect("Automorphisms failed");
+    assert_eq!(group.order, 6);
+    assert_eq!(group.orbits, vec![vec![0, 1, 2]]);
+    assert_generators_preserve(&triangle, &group);
+
+    let path = graph(false, &[0, 0, 0], &[(0, 1), (1, 2)]);
+    let group = automorphisms(&path).expect("Automorphisms failed");
+    assert_eq!(group.order, 2);
+    assert_eq!(group.orbits, vec![vec![0, 2], vec![1]]);
+    assert_eq!(group.orbit_of(2), &[0, 2]);
+    assert_eq!(group.generators, vec![vec![2, 1, 0]]);
+
+    let mut petersen = Vec::new();
+    for i in 0..5 {
+        petersen.push((i, (i + 1) % 5));
+        petersen.push((i, i + 5));
+        petersen.push((i + 5, (i + 2) % 5 + 5));
+    }
+    let petersen = graph(false, &[0; 10], &petersen);
+    let group = automorphisms(&petersen).expect("Automorphisms failed");
+    assert_eq!(group.order, 120);
+    assert_eq!(group.orbits.len(), 1);
+    assert_generators_preserve(&petersen, &group);
+}
+
+#[test]
+fn labels_and_directions_break_symmetry() {
+    let labelled = graph(false, &[0, 0, 1], &[(0, 1), (1, 2), (0, 2)]);
+    let group = automorphisms(&labelled).expect("Automorphisms failed");
+    assert_eq!(group.order, 2);
+    assert_eq!(group.orbits, vec![vec![0, 1], vec![2]]);
+
+    let mut cycle = Graph::directed();
+    for _ in 0..3 {
+        cycle.add_node(0);
+    }
+    cycle.add_edge(0, 1);
+    cycle.add_edge(1, 2);
+    cycle.add_edge(2, 0);
+    let group = automorphisms(&cycle).expect("Automorphisms failed");
+    assert_eq!(group.order, 3);
+    assert_generators_preserve(&cycle, &group);
+
+    cycle.add_labeled_edge(0, 2, 7);
+    let group = automorphisms(&cycle).expect("Automorphisms failed");
+    assert_eq!(group.order, 1);
+    assert!(group.generators.is_empty());
+    assert_eq!(group.orbits, vec![vec![0], vec![1], vec![2]]);
+}
+
+#[test]
+fn graphs_with_isolated_nodes() {
+    let edgeless = graph::<(NodeId, NodeId)>(false, &[0, 0, 0], &[]);
+    let group = automorphisms(&edgeless).expect("Automorphisms failed");
+    assert_eq!(group.order, 6);
+    assert_eq!(group.orbits, vec![vec![0, 1, 2]]);
+
+    let isolated_first = graph(false, &[0, 0, 0, 0], &[(1, 2), (2, 3)]);
+    let group = automorphisms(&isolated_first).expect("Automorphisms failed");
+    assert_eq!(group.order, 2);
+    assert_eq!(group.orbits, vec![vec![0], vec![1, 3], vec![2]]);
+}
+
+/// Every graph on `count` nodes, one per subset of the possible edges.
+fn all_graphs(count: u32, directed: bool) -> Vec<Graph> {
+    let pairs: Vec<_> = (0..count)
+        .flat_map(|a| (0..count).map(move |b| (a, b)))
+        .filter(|&(a, b)| if directed { a != b } else { a < b })
+        .collect();
+    (0..1u32 << pairs.len())
+        .map(|mask| {
+            let mut graph = if directed {
+                Graph::directed()
+            } else {
+                Graph::undirected()
+            };
+            for _ in 0..count {
+                graph.add_node(0);
+            }
+            for (bit, &(a, b)) in pairs.iter().enumerate() {
+                if mask & (1 << bit) != 0 {
+                    graph.add_edge(a, b);
+                }
+            }
+            graph
+        })
+        .collect()
+}
+
+fn relabel(graph: &Graph, perm: &[NodeId]) -> Graph {
+    let mut out = if graph.is_directed() {
+        Graph::directed()
+    } else {
+        Graph::undirected()
+    };
+    let mut at = vec![0; perm.len()];
+    for (node, &pos) in perm.iter().enumerate() {
+        at[pos as usize] = node as NodeId;
+    }
+    for &node in &at {
+        out.add_node(graph.node_label(node));
+    }
+    for node in 0..graph.node_count() as NodeId {
+        for (&succ, &label) in graph
+            .successors(node)
+            .iter()
+            .zip(graph.successor_labels(node))
+        {
+            out.add_labeled_edge(perm[node as usize], perm[succ as usize], label);
+        }
+    }
+    out
+}
+
+#[test]
+fn canonical_form_counts_isomorphism_classes() {
+    let undirected = all_graphs(4, false);
+    let forms: Vec<_> = undirected.iter().map(canonical_form).collect();
+    assert_eq!(forms.iter().collect::<HashSet<_>>().len(), 11);
+
+    let directed = all_graphs(3, true);
+    let classes: HashSet<_> = directed.iter().map(canonical_form).collect();
+    assert_eq!(classes.len(), 16);
+
+    // Equal certificates exactly when VF3L finds an isomorphism.
+    let opts = RunOptions {
+        first_only: true,
+        repetition_time_limit: 0.0,
+        ..Default::default()
+    };
+    for (a, form_a) in undirected.iter().zip(&forms) {
+        for (b, form_b) in undirected.iter().zip(&forms) {
+            let isomorphic = a.edge_count() == b.edge_count()
+                && run_vf3l_graphs(a, b, opts.clone())
+                    .expect("VF3L failed")
+                    .solutions
+                    > 0;
+            assert_eq!(form_a == form_b, isomorphic);
+        }
+    }
+}
+
+#[test]
+fn canonical_form_beyond_colour_refinement() {
+    // K3,3 and the triangular prism are both 3-regular on six nodes.
+    let k33 = graph(
+        false,
+        &[0; 6],
+        &[
+            (0, 3),
+            (0, 4),
+            (0, 5),
+            (1, 3),
+            (1, 4),
+            (1, 5),
+            (2, 3),
+            (2, 4),
+            (2, 5),
+        ],
+    );
+    let prism = graph(
+        false,
+        &[0; 6],
+        &[
+            (0, 1),
+            (1, 2),
+            (0, 2),
+            (3, 4),
+            (4, 5),
+            (3, 5),
+            (0, 3),
+            (1, 4),
+            (2, 5),
+        ],
+    );
+    assert_ne!(canonical_form(&k33), canonical_form(&prism));
+
+    let shuffled = relabel(&prism, &[4, 0, 5, 2, 1, 3]);
+    assert_eq!(canonical_form(&shuffled), canonical_form(&prism));
+
+    // The canonical labelling turns both into the same graph.
+    let a = relabel(&prism, &canonical_labeling(&prism));
+    let b = relabel(&shuffled, &canonical_labeling(&shuffled));
+    for node in 0..6 {
+        assert_eq!(a.successors(node), b.successors(node));
+    }
+
+    let mut labelled = prism.clone();
+    labelled.set_node_label(0, 1);
+    let mut other = prism.clone();
+    other.set_node_label(0, 2);
+    assert_ne!(canonical_form(&labelled), canonical_form(&prism));
+    assert_ne!(canonical_form(&labelled), canonical_form(&other));
+    let mut edge_labelled = graph(false, &[0; 3], &[(0, 1)]);
+    edge_labelled.add_labeled_edge(1, 2, 5);
+    let mut swapped = graph(false, &[0; 3], &[(1, 2)]);
+    swapped.add_labeled_edge(0, 1, 5);
+    assert_eq!(canonical_form(&edge_labelled), canonical_form(&swapped));
+    assert_ne!(
+        canonical_form(&edge_labelled),
+        canonical_form(&graph(false, &[0; 3], &[(0, 1), (1, 2)]))
+    );
+}
+
+#[test]
+fn canonical_form_of_highly_symmetric_graphs_is_fast() {
+    let star = graph(
+        false,
+        &[0; 61],
+        &(1..61).map(|leaf| (0, leaf)).collect::<Vec<_>>(),
+    );
+    let edgeless = graph::<(NodeId, NodeId)>(false, &[0; 60], &[]);
+    for graph in [star, edgeless] {
+        let start = Instant::now();
+        let form = canonical_form(&graph);
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "took {:?}",
+            start.elapsed()
+        );
+        let reversed: Vec<_> = (0..graph.node_count() as NodeId).rev().collect();
+        assert_eq!(canonical_form(&relabel(&graph, &reversed)), form);
+    }
+}